@@ -3,18 +3,95 @@
 mod labyrinth;
 
 use {
-    crate::labyrinth::scene::*,
+    crate::labyrinth::{scene::*, settings::*},
     anyhow::Result,
     rustyengine::{conf::*, engn::*},
+    std::{path::Path, time::Duration},
 };
 
 fn main() -> Result<()> {
-    let mut conf = Conf::read(vec!["src/bin/conf.toml"])?;
-    conf.initpt = gen_init_pos();
-    let mut scene = Scene::new(conf.draw_dist)?;
-    scene.expand();
-    let es = MovementEventSys::new(STEP);
-    let mut game = Game::<MovementEvent<Scene>, MovementEventSys, Scene>::new(conf, scene, es)?;
+    let mut conf = Conf::read(["src/bin/conf.toml"])?
+        .with_env()?
+        .with_args(std::env::args().skip(1))?;
+    let settings = conf.extra::<Settings>()?;
+    if let Some(path) = &settings.replay {
+        return replay(conf, &settings, path);
+    }
+
+    let draw_dist = conf.draw_dist;
+    let mut game = labyrinth(conf, &settings, Console::new())?
+        .with_hot_reload()
+        .with_scene_reload(&settings.scene, move |scene, path| {
+            let mut walls = Scene::read(path, draw_dist)?;
+            walls.expand();
+            scene.release();
+            *scene = walls;
+            Ok(())
+        });
+    if let Some(path) = &settings.stats {
+        game = game.with_stats_file(path);
+    }
+    if let Some(path) = &settings.record {
+        game = game.with_recording(path);
+    }
     game.run()?;
     Ok(())
 }
+
+/// Plays recorded session back without console and prints the final camera pose
+fn replay(mut conf: Conf, settings: &Settings, path: &Path) -> Result<()> {
+    let recording = Recording::read(path)?;
+    conf.seed = recording.seed;
+    let backend = Headless::replay(&recording).with_frames(false);
+    let mut game = labyrinth(conf, settings, backend)?;
+    game.run()?;
+    let (camera, pos) = (game.camera(), game.camera().pos());
+    println!(
+        "camera at ({}, {}, {}), zenith {}, azimuth {}",
+        pos[0],
+        pos[1],
+        pos[2],
+        camera.zenith(),
+        camera.azimuth()
+    );
+    Ok(())
+}
+
+/// Game in labyrinth with its hud and exit presented by the given backend,
+/// camera starts in random cell
+fn labyrinth<Bk: AsBackend>(
+    mut conf: Conf,
+    settings: &Settings,
+    backend: Bk,
+) -> Result<Game<MovementEvent<Scene>, MovementEventSys, Scene, Bk>> {
+    let random = Random::new(conf.seed);
+    conf.initpt = gen_init_pos(&mut random.stream("spawn"));
+    let mut scene = Scene::read(&settings.scene, conf.draw_dist)?;
+    scene.expand();
+    let es = MovementEventSys::new(settings.speed)
+        .with_sensitivity(conf.mouse_sensitivity)
+        .with_controller(Controller::new(conf.motion.clone()));
+    let mut game = Game::with_backend(conf, scene, es, backend)?
+        .with_ids(IdPool::shared())
+        .with_random(random);
+    game.hud_mut()
+        .add("title", Anchor::Top, Label::new("Escape from labyrinth!"));
+    game.hud_mut()
+        .add("crosshair", Anchor::Center, Crosshair::new('+'));
+    game.hud_mut().add(
+        MINIMAP,
+        Anchor::TopRight,
+        Minimap::new((11, 21), PASSAGE / 4.0),
+    );
+    game.hud_mut()
+        .add(STATS, Anchor::TopLeft, StatsOverlay::new(30));
+    game.hud_mut().show(STATS, false);
+    let entity = game.entity();
+    game.triggers_mut().add(exit(&entity).on(|event, hud| {
+        if event.phase == Phase::Enter && event.occupant == Occupant::Camera {
+            hud.toast("You escaped!", Duration::from_secs(3));
+            hud.quit();
+        }
+    }));
+    Ok(game)
+}
@@ -1,7 +1,6 @@
 use {
     rustyengine::{conf::*, engn::*, math::*},
-    std::{collections::HashMap, rc::Rc},
-    uuid::Uuid,
+    std::collections::HashMap,
 };
 
 #[derive(Debug)]
@@ -16,7 +15,7 @@ impl Ground {
 }
 
 impl AsEntity for Ground {
-    fn id(&self) -> &Rc<Uuid> {
+    fn id(&self) -> EntityId {
         self.entity.id()
     }
 
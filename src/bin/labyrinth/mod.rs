@@ -1,3 +1,4 @@
 pub mod ground;
 pub mod scene;
+pub mod settings;
 pub mod walls;
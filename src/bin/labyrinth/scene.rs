@@ -6,15 +6,22 @@ use {
     std::{
         cmp::Ordering,
         ops::{Div, Mul},
+        path::Path,
     },
+    toml::Table,
 };
 
-pub const STEP: f64 = 1.0;
 pub const XZWALLS: usize = 11;
 pub const YZWALLS: usize = 11;
 pub const PASSAGE: f64 = 5.0;
 pub const BACKWALL: f64 = 55.0;
 
+/// Walls along axis x and along axis y
+type Walls = ([XzWalls; XZWALLS], [YzWalls; YZWALLS]);
+
+/// Name of scene kept in save in errors
+const SAVE: &str = "<save>";
+
 pub struct Scene {
     xz_walls: [XzWalls; XZWALLS],
     xz_charcoal: Charcoal,
@@ -23,112 +30,17 @@ pub struct Scene {
     ground: Ground,
     ground_charcoal: Charcoal,
     sun: HypeEllipse,
+    collider: Collider,
+    counters: Counters,
 }
 
 impl Scene {
-    pub fn new(draw_dist: f64) -> ReRes<Self> {
-        let xz_walls = [
-            XzWalls::new(Entity::new(IdPool::get().generate()), 0.0, vec![0.0, 10.0]),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                1.0,
-                vec![3.0, 7.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                2.0,
-                vec![0.0, 1.0, 4.0, 7.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                3.0,
-                vec![1.0, 2.0, 4.0, 5.0, 6.0, 7.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                4.0,
-                vec![0.0, 1.0, 3.0, 6.0, 7.0, 10.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                5.0,
-                vec![1.0, 6.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                6.0,
-                vec![2.0, 3.0, 4.0, 7.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                7.0,
-                vec![3.0, 4.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                8.0,
-                vec![4.0, 5.0, 6.0, 7.0, 9.0, 10.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                9.0,
-                vec![4.0, 5.0, 8.0, 9.0],
-            ),
-            XzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                10.0,
-                vec![0.0, 4.0, 5.0, 10.0],
-            ),
-        ];
-        let yz_walls = [
-            YzWalls::new(Entity::new(IdPool::get().generate()), 0.0, vec![0.0, 10.0]),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                1.0,
-                vec![1.0, 2.0, 5.0, 9.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                2.0,
-                vec![0.0, 8.0, 9.0, 10.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                3.0,
-                vec![1.0, 4.0, 7.0, 9.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                4.0,
-                vec![2.0, 3.0, 6.0, 7.0, 8.0, 9.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                5.0,
-                vec![7.0, 8.0, 9.0, 10.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                6.0,
-                vec![3.0, 5.0, 7.0, 8.0, 9.0, 10.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                7.0,
-                vec![2.0, 3.0, 5.0, 9.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                8.0,
-                vec![0.0, 2.0, 3.0, 4.0, 5.0, 9.0],
-            ),
-            YzWalls::new(
-                Entity::new(IdPool::get().generate()),
-                9.0,
-                vec![2.0, 3.0, 9.0, 10.0],
-            ),
-            YzWalls::new(Entity::new(IdPool::get().generate()), 10.0, vec![0.0, 10.0]),
-        ];
+    /// Reads walls from scene file, see `walls`
+    pub fn read(path: impl AsRef<Path>, draw_dist: f64) -> ReRes<Self> {
+        let path = path.as_ref();
+        let file = SceneFile::read(path)?;
+        let ids = std::iter::repeat_with(IdPool::generate_shared);
+        let (xz_walls, yz_walls) = walls(&file, &path.display().to_string(), ids)?;
 
         Ok(Self {
             xz_walls,
@@ -137,19 +49,54 @@ impl Scene {
             yz_walls,
             yz_charcoal: Charcoal::new("^\"".to_string(), 6.0),
 
-            ground: Ground::new(Entity::new(IdPool::get().generate())),
+            ground: Ground::new(Entity::new(IdPool::generate_shared())),
             ground_charcoal: Charcoal::new("#$?".to_string(), draw_dist),
 
             sun: HypeEllipse::new(
-                Entity::new(IdPool::get().generate()),
+                Entity::new(IdPool::generate_shared()),
                 Point::new(vec![-10.0, -10.0, 80.0]),
                 Basis::new(Matrix::identity(3).to_multicol())?,
                 vec![20.0, 20.0, 20.0],
                 Some(Charcoal::new("0Oo".to_string(), draw_dist)),
             )?,
+
+            collider: Collider::new(Shape::Capsule {
+                radius: 0.5,
+                height: 1.0,
+            })
+            .with_step_height(0.5),
+            counters: Counters::default(),
         })
     }
 
+    /// Releases ids of walls, ground and sun into the shared pool, eg before the scene is replaced
+    pub fn release(&self) {
+        for id in self
+            .wall_ids()
+            .into_iter()
+            .chain([self.ground.id(), self.sun.id()])
+        {
+            IdPool::release_shared(id);
+        }
+    }
+
+    /// Walls saved by `AsScene::save` with ids of the current walls
+    fn saved_walls(&self, state: &Table) -> ReRes<Option<Walls>> {
+        if state.is_empty() {
+            return Ok(None);
+        }
+        let file = SceneFile::from_table(state.clone())?;
+        walls(&file, SAVE, self.wall_ids()).map(Some)
+    }
+
+    /// Ids of walls
+    fn wall_ids(&self) -> Vec<EntityId> {
+        let walls = self.xz_walls.iter().map(|wall| wall.id());
+        walls
+            .chain(self.yz_walls.iter().map(|wall| wall.id()))
+            .collect()
+    }
+
     pub fn expand(&mut self) {
         let coef = PASSAGE;
         for wall in &mut self.xz_walls {
@@ -175,6 +122,7 @@ impl Scene {
                     false => (inc[1] / PASSAGE + 1.0).floor() as usize..XZWALLS,
                 };
                 for i in rng {
+                    self.counters.count_collisions(1);
                     if let Some(dist) = self.xz_walls[i].collide(cs, inc, dir) {
                         if collision.is_none() || dist < collision.unwrap() {
                             collision = Some(dist);
@@ -189,6 +137,7 @@ impl Scene {
                     false => (0..(inc[1] / PASSAGE).ceil() as usize).rev(),
                 };
                 for i in rng {
+                    self.counters.count_collisions(1);
                     if let Some(dist) = self.xz_walls[i].collide(cs, inc, dir) {
                         if collision.is_none() || dist < collision.unwrap() {
                             collision = Some(dist);
@@ -212,6 +161,7 @@ impl Scene {
                     false => (inc[0] / PASSAGE + 1.0).floor() as usize..YZWALLS,
                 };
                 for i in rng {
+                    self.counters.count_collisions(1);
                     if let Some(dist) = self.yz_walls[i].collide(cs, inc, dir) {
                         if collision.is_none() || dist < collision.unwrap() {
                             collision = Some(dist);
@@ -226,6 +176,7 @@ impl Scene {
                     false => (0..(inc[0] / PASSAGE).ceil() as usize).rev(),
                 };
                 for i in rng {
+                    self.counters.count_collisions(1);
                     if let Some(dist) = self.yz_walls[i].collide(cs, inc, dir) {
                         if collision.is_none() || dist < collision.unwrap() {
                             collision = Some(dist);
@@ -256,6 +207,8 @@ impl AsScene for Scene {
             }
         }
 
+        // ground and sun, walls are counted as they are tested
+        self.counters.count_collisions(2);
         if let Some(dist) = self.ground.collide(cs, inc, dir) {
             if collision.is_none() || dist < collision.unwrap().0 {
                 collision = Some((dist, self.ground_charcoal.ignite(dist)));
@@ -276,28 +229,130 @@ impl AsScene for Scene {
     }
 
     fn validate_mv(&self, cs: &CoordSys, pos: &Point, mv: &mut Vector) {
+        *mv = self.collider.move_and_slide(self, cs, pos, mv);
+    }
+
+    fn trace(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64> {
         let mut collision = None;
-        if let Some(dist) = self.collision_ag_xz_walls(cs, pos, mv) {
+        if let Some(dist) = self.collision_ag_xz_walls(cs, inc, dir) {
             if collision.is_none() || dist < collision.unwrap() {
                 collision = Some(dist);
             }
         }
-        if let Some(dist) = self.collision_ag_yz_walls(cs, pos, mv) {
+        if let Some(dist) = self.collision_ag_yz_walls(cs, inc, dir) {
+            if collision.is_none() || dist < collision.unwrap() {
+                collision = Some(dist);
+            }
+        }
+        // ground, walls are counted as they are tested
+        self.counters.count_collisions(1);
+        if let Some(dist) = self.ground.collide(cs, inc, dir) {
             if collision.is_none() || dist < collision.unwrap() {
                 collision = Some(dist);
             }
         }
+        collision
+    }
 
-        if let Some(dist) = collision {
-            if dist < 1.0 {
-                *mv = Vector::new(vec![0.0; 3])
+    fn hit(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<Hit> {
+        let mut hit: Option<Hit> = None;
+        // ground, walls are counted as they are tested
+        self.counters.count_collisions(1);
+        let sides = [
+            (self.collision_ag_xz_walls(cs, inc, dir), 1),
+            (self.collision_ag_yz_walls(cs, inc, dir), 0),
+            (self.ground.collide(cs, inc, dir), 2),
+        ];
+        for (dist, axis) in sides {
+            if let Some(t) = dist {
+                if hit.is_none() || t < hit.as_ref().unwrap().t {
+                    let mut normal = Vector::new(vec![0.0; 3]);
+                    *normal.at_mut(axis) = -dir[axis].signum();
+                    hit = Some(Hit { t, normal });
+                }
             }
         }
+        hit
+    }
+
+    fn set_counters(&mut self, counters: Counters) {
+        self.counters = counters
     }
+
+    /// Walls as materials of scene file, so the save keeps the labyrinth it was made in
+    fn save(&self) -> ReRes<Table> {
+        let walls = self.xz_walls.iter().map(|wall| wall.describe());
+        let walls = walls.chain(self.yz_walls.iter().map(|wall| wall.describe()));
+        let materials = walls.collect::<Option<Vec<_>>>();
+        let materials = materials.ok_or(ReErr::GameErr(GameErr::UnsavableMaterial))?;
+        Ok(SceneFile { materials }.to_table())
+    }
+
+    fn validate_save(&self, state: &Table) -> ReRes<()> {
+        self.saved_walls(state).map(|_| ())
+    }
+
+    /// Replaces walls with the saved ones keeping their ids, save without walls keeps
+    /// the current ones. Nothing is changed if the state is invalid
+    fn restore(&mut self, state: &Table) -> ReRes<()> {
+        if let Some((xz_walls, yz_walls)) = self.saved_walls(state)? {
+            (self.xz_walls, self.yz_walls) = (xz_walls, yz_walls);
+        }
+        Ok(())
+    }
+}
+
+/// Walls of scene file, `XZWALLS` walls of `XzWalls::KIND` and `YZWALLS` walls of
+/// `YzWalls::KIND` are expected. Walls take `ids` in the order of the file, `name` of the file
+/// is used in errors
+fn walls(file: &SceneFile, name: &str, ids: impl IntoIterator<Item = EntityId>) -> ReRes<Walls> {
+    let invalid = |reason: String| {
+        ReErr::GameErr(GameErr::InvalidSceneFile {
+            file: name.to_string(),
+            reason,
+        })
+    };
+    // ids are taken only when all the walls are there
+    if file.materials.len() != XZWALLS + YZWALLS {
+        let count = XZWALLS + YZWALLS;
+        return Err(invalid(format!("expected {} walls", count)));
+    }
+    let (mut xz_walls, mut yz_walls) = (vec![], vec![]);
+    for (desc, id) in file.materials.iter().zip(ids) {
+        let entity = Entity::new(id);
+        match desc.kind() {
+            XzWalls::KIND => xz_walls.push(XzWalls::from_desc(desc, entity)?),
+            YzWalls::KIND => yz_walls.push(YzWalls::from_desc(desc, entity)?),
+            kind => return Err(desc.err("kind", format!("unknown kind {:?}", kind))),
+        }
+    }
+    xz_walls.sort_by(|lhs, rhs| lhs.y0.total_cmp(&rhs.y0));
+    yz_walls.sort_by(|lhs, rhs| lhs.x0.total_cmp(&rhs.x0));
+    let wrong =
+        |kind: &str, count: usize| invalid(format!("expected {} walls of kind {:?}", count, kind));
+    let xz_walls = xz_walls
+        .try_into()
+        .map_err(|_| wrong(XzWalls::KIND, XZWALLS))?;
+    let yz_walls = yz_walls
+        .try_into()
+        .map_err(|_| wrong(YzWalls::KIND, YZWALLS))?;
+    Ok((xz_walls, yz_walls))
+}
+
+/// Trigger behind the gap in the outer wall `y = BACKWALL`
+pub fn exit(entity: &Entity) -> Trigger {
+    Trigger::new(
+        entity.id(),
+        Volume::Cuboid {
+            half: Vector::new(vec![PASSAGE / 2.0, PASSAGE / 2.0, BACKWALL]),
+        },
+        Point::new(vec![4.5 * PASSAGE, BACKWALL + PASSAGE / 2.0, 0.0]),
+    )
 }
 
-pub fn gen_init_pos() -> Point {
-    let x = (rand::thread_rng().gen_range(0..(XZWALLS - 1)) as f64 + 0.5) * PASSAGE;
-    let y = (rand::thread_rng().gen_range(0..(YZWALLS - 1)) as f64 + 0.5) * PASSAGE;
+/// Center of random cell of the labyrinth
+pub fn gen_init_pos(rng: &mut impl Rng) -> Point {
+    let x = (rng.gen_range(0..(XZWALLS - 1)) as f64 + 0.5) * PASSAGE;
+    let y = (rng.gen_range(0..(YZWALLS - 1)) as f64 + 0.5) * PASSAGE;
     Point::new(vec![x, y, 2.0])
 }
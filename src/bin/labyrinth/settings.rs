@@ -0,0 +1,33 @@
+use {rustyengine::conf::AsConfSection, serde::Deserialize, std::path::PathBuf};
+
+/// `[labyrinth]` section of `Conf`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Speed of walking in units per second, `6`
+    pub speed: f64,
+    /// File with walls of the labyrinth, `src/bin/labyrinth.toml`
+    pub scene: PathBuf,
+    /// File that frame statistics are written to on exit, none by default
+    pub stats: Option<PathBuf>,
+    /// File that the session is recorded to, none by default
+    pub record: Option<PathBuf>,
+    /// Recorded session that is played back without console instead of playing
+    pub replay: Option<PathBuf>,
+}
+
+impl AsConfSection for Settings {
+    const SECTION: &'static str = "labyrinth";
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            speed: 6.0,
+            scene: PathBuf::from("src/bin/labyrinth.toml"),
+            stats: None,
+            record: None,
+            replay: None,
+        }
+    }
+}
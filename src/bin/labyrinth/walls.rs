@@ -1,7 +1,6 @@
 use {
-    rustyengine::{conf::*, engn::*, math::*},
-    std::{any::Any, cmp::Ordering, collections::HashMap, rc::Rc},
-    uuid::Uuid,
+    rustyengine::{conf::*, engn::*, errs::ReRes, math::*},
+    std::{any::Any, cmp::Ordering, collections::HashMap},
 };
 
 /// Height of all panes
@@ -17,6 +16,9 @@ pub struct XzWalls {
 }
 
 impl XzWalls {
+    /// Kind of walls in scene file, they are given with `y0` and `segments`
+    pub const KIND: &'static str = "xz_walls";
+
     pub fn new(entity: Entity, y0: f64, x_seg: Vec<f64>) -> Self {
         Self {
             entity,
@@ -24,10 +26,18 @@ impl XzWalls {
             x_seg: x_seg.iter().map(|f| Float(*f)).collect(),
         }
     }
+
+    pub fn from_desc(desc: &MaterialDesc, entity: Entity) -> ReRes<Self> {
+        Ok(Self::new(
+            entity,
+            desc.float("y0")?,
+            desc.floats("segments")?,
+        ))
+    }
 }
 
 impl AsEntity for XzWalls {
-    fn id(&self) -> &Rc<Uuid> {
+    fn id(&self) -> EntityId {
         self.entity.id()
     }
 
@@ -79,6 +89,16 @@ impl AsCollided for XzWalls {
     fn charmap(&self, _dist: f64) -> Option<char> {
         None
     }
+
+    fn describe(&self) -> Option<MaterialDesc> {
+        let mut desc = MaterialDesc::new(Self::KIND);
+        desc.set_float("y0", self.y0);
+        desc.set_floats(
+            "segments",
+            &self.x_seg.iter().map(|f| f.0).collect::<Vec<_>>(),
+        );
+        Some(desc)
+    }
 }
 
 /// Part of the entire plane that is right rectangle and collinear to Oyz plane.
@@ -91,6 +111,9 @@ pub struct YzWalls {
 }
 
 impl YzWalls {
+    /// Kind of walls in scene file, they are given with `x0` and `segments`
+    pub const KIND: &'static str = "yz_walls";
+
     pub fn new(entity: Entity, x0: f64, y_seg: Vec<f64>) -> Self {
         Self {
             entity,
@@ -98,10 +121,18 @@ impl YzWalls {
             y_seg: y_seg.iter().map(|f| Float(*f)).collect(),
         }
     }
+
+    pub fn from_desc(desc: &MaterialDesc, entity: Entity) -> ReRes<Self> {
+        Ok(Self::new(
+            entity,
+            desc.float("x0")?,
+            desc.floats("segments")?,
+        ))
+    }
 }
 
 impl AsEntity for YzWalls {
-    fn id(&self) -> &Rc<Uuid> {
+    fn id(&self) -> EntityId {
         self.entity.id()
     }
 
@@ -153,4 +184,14 @@ impl AsCollided for YzWalls {
     fn charmap(&self, _dist: f64) -> Option<char> {
         None
     }
+
+    fn describe(&self) -> Option<MaterialDesc> {
+        let mut desc = MaterialDesc::new(Self::KIND);
+        desc.set_float("x0", self.x0);
+        desc.set_floats(
+            "segments",
+            &self.y_seg.iter().map(|f| f.0).collect::<Vec<_>>(),
+        );
+        Some(desc)
+    }
 }
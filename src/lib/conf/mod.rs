@@ -1,6 +1,13 @@
 //! Module defines struct packages configuration information - `Conf`.
 //! Constructor `Conf::read` reads provided configuration parameters from
-//! the given TOML files, counting from the same level as `src` folder
+//! the given TOML files, counting from the same level as `src` folder.
+//! Parameters are grouped in `[camera]`, `[render]`, `[input]`, `[motion]` and `[random]` tables that
+//! are deserialized with serde, the other tables are sections of the game, see `Conf::extra`.
+//! Key bindings are given in the `[input.keys]` table mapping key names to actions,
+//! eg `"ctrl+w" = "move_forward"`, empty action removes the binding.
+//! Parameters can be parsed from strings with `Conf::parse` and overridden with environment
+//! variables and command line arguments on top of files. Unknown keys and values out of range
+//! are reported as errors, see `Conf::validate`
 
 #[cfg(test)]
 mod test;
@@ -9,169 +16,613 @@ use {
     crate::{
         engn::*,
         errs::{
+            inaccessible, oneline, toml_syntax,
             GameErr::{self, *},
+            Location,
             ReErr::{self, *},
             ReRes,
         },
         math::*,
     },
-    std::{f64::consts::PI, fs::read_to_string},
+    serde::{de::DeserializeOwned, Deserialize},
+    std::{
+        collections::BTreeMap,
+        f64::consts::PI,
+        fs::read_to_string,
+        path::{Path, PathBuf},
+    },
     toml::{Table, Value},
 };
 
-const INITPT_KEY: &str = "INITIAL_POINT";
-const ANGLE_DISCR_KEY: &str = "ROTATION_HALF_PI_DISCRETIZATION";
-const WFOV_KEY: &str = "HORIZONTAL_FIELD_OF_VIEW_OUT_OF_PI";
-const HFOV_KEY: &str = "VERTICAL_FIELD_OF_VIEW_OUT_OF_PI";
-const DRAW_DIST_KEY: &str = "DRAW_DISTANCE";
-const CHARMAP_KEY: &str = "CHARMAP";
-const PRECISION_KEY: &str = "PRECISION";
+const CAMERA: &str = "camera";
+const RENDER: &str = "render";
+const INPUT: &str = "input";
+const MOTION: &str = "motion";
+const RANDOM: &str = "random";
+
+/// Tables of parameters of the engine, the other tables are sections of the game
+const SECTIONS: [&str; 5] = [CAMERA, RENDER, INPUT, MOTION, RANDOM];
+
+const INITPT_KEY: &str = "camera.initial_point";
+const ANGLE_DISCR_KEY: &str = "camera.half_pi_discretization";
+const WFOV_KEY: &str = "camera.horizontal_fov";
+const HFOV_KEY: &str = "camera.vertical_fov";
+const DRAW_DIST_KEY: &str = "camera.draw_distance";
+const CHARMAP_KEY: &str = "render.charmap";
+const PRECISION_KEY: &str = "render.precision";
+const TICK_RATE_KEY: &str = "render.tick_rate";
+const MOUSE_CAPTURE_KEY: &str = "input.mouse_capture";
+const MOUSE_SENSITIVITY_KEY: &str = "input.mouse_sensitivity";
+const REPEAT_DELAY_KEY: &str = "input.key_repeat_delay";
+const REPEAT_TIMEOUT_KEY: &str = "input.key_repeat_timeout";
+const KEYS_KEY: &str = "input.keys";
+const ACCEL_KEY: &str = "motion.acceleration";
+const FRICTION_KEY: &str = "motion.friction";
+const MAX_SPEED_KEY: &str = "motion.max_speed";
+const GRAVITY_KEY: &str = "motion.gravity";
+const JUMP_SPEED_KEY: &str = "motion.jump_speed";
+const HEIGHT_KEY: &str = "motion.eye_height";
+const CROUCH_HEIGHT_KEY: &str = "motion.crouch_height";
+const SEED_KEY: &str = "random.seed";
+
+/// Keys of parameters qualified with their sections, the other keys of `SECTIONS`
+/// are reported as unknown
+const KNOWN_KEYS: [&str; 21] = [
+    INITPT_KEY,
+    ANGLE_DISCR_KEY,
+    WFOV_KEY,
+    HFOV_KEY,
+    DRAW_DIST_KEY,
+    CHARMAP_KEY,
+    PRECISION_KEY,
+    TICK_RATE_KEY,
+    MOUSE_CAPTURE_KEY,
+    MOUSE_SENSITIVITY_KEY,
+    REPEAT_DELAY_KEY,
+    REPEAT_TIMEOUT_KEY,
+    KEYS_KEY,
+    ACCEL_KEY,
+    FRICTION_KEY,
+    MAX_SPEED_KEY,
+    GRAVITY_KEY,
+    JUMP_SPEED_KEY,
+    HEIGHT_KEY,
+    CROUCH_HEIGHT_KEY,
+    SEED_KEY,
+];
+
+/// Flat keys of the former format and their keys in sections, they are suggested instead
+const RENAMED: [(&str, &str); 19] = [
+    ("INITIAL_POINT", INITPT_KEY),
+    ("ROTATION_HALF_PI_DISCRETIZATION", ANGLE_DISCR_KEY),
+    ("HORIZONTAL_FIELD_OF_VIEW_OUT_OF_PI", WFOV_KEY),
+    ("VERTICAL_FIELD_OF_VIEW_OUT_OF_PI", HFOV_KEY),
+    ("DRAW_DISTANCE", DRAW_DIST_KEY),
+    ("CHARMAP", CHARMAP_KEY),
+    ("PRECISION", PRECISION_KEY),
+    ("TICK_RATE", TICK_RATE_KEY),
+    ("MOUSE_CAPTURE", MOUSE_CAPTURE_KEY),
+    ("MOUSE_SENSITIVITY", MOUSE_SENSITIVITY_KEY),
+    ("KEY_REPEAT_DELAY", REPEAT_DELAY_KEY),
+    ("KEY_REPEAT_TIMEOUT", REPEAT_TIMEOUT_KEY),
+    ("ACCELERATION", ACCEL_KEY),
+    ("FRICTION", FRICTION_KEY),
+    ("MAX_SPEED", MAX_SPEED_KEY),
+    ("GRAVITY", GRAVITY_KEY),
+    ("JUMP_SPEED", JUMP_SPEED_KEY),
+    ("EYE_HEIGHT", HEIGHT_KEY),
+    ("CROUCH_HEIGHT", CROUCH_HEIGHT_KEY),
+];
+
+/// Prefix of environment variables overriding parameters, see `Conf::with_env`
+pub const ENV_PREFIX: &str = "RUSTYENGINE_";
+
+/// Name used in errors when parameters aren't read from file
+const NO_FILE: &str = "<string>";
+
+/// Name used in errors of overrides
+const OVERRIDES: &str = "<overrides>";
+
+/// Section of configuration defined by game, eg `[labyrinth]`, see `Conf::extra`
+pub trait AsConfSection: DeserializeOwned {
+    /// Name of the table
+    const SECTION: &'static str;
+}
 
 /// Struct that packages configuration parameters,
-/// it further is used for `Game` object instanciating
+/// it further is used for `Game` object instanciating.
+/// Each field is documented with its key and default value
 #[derive(Debug, Clone, PartialEq)]
 pub struct Conf {
+    /// `camera.initial_point`, position of camera at start, `[0, 0, 0]`
     pub initpt: Point,
+    /// `camera.half_pi_discretization`, number of rotation steps per right angle,
+    /// at least 1, `6`
     pub angle_discr: usize,
+    /// `camera.horizontal_fov` out of PI, within (0, 2), `PI / 2`
     pub wfov: f64,
+    /// `camera.vertical_fov` out of PI, within (0, 2), computed from `wfov`
+    /// and picture size if it isn't given
     pub hfov: Option<f64>,
+    /// `camera.draw_distance`, positive, `100`
     pub draw_dist: f64,
+    /// `render.charmap`, chars from the nearest to the farthest distance, not empty
     pub charmap: String,
+    /// `render.precision`, digits of exact mode of comparisons within [0, 255], `100`
     pub precision: u8,
+    /// `render.tick_rate`, frames per second, zero means that frames are rendered
+    /// on events only, non-negative, `30`
+    pub tick_rate: f64,
+    /// `input.mouse_capture`, `false`
+    pub mouse_capture: bool,
+    /// `input.mouse_sensitivity`, non-negative, `1`
+    pub mouse_sensitivity: f64,
+    /// `input.key_repeat_delay` in seconds, non-negative, `0.5`, see `InputState`
+    pub repeat_delay: f64,
+    /// `input.key_repeat_timeout` in seconds, non-negative, `0.1`
+    pub repeat_timeout: f64,
+    /// `[motion]` table: `acceleration`, `friction`, `max_speed`, `gravity`, `jump_speed`,
+    /// `eye_height` and `crouch_height`, non-negative, see `Motion::default`
+    pub motion: Motion,
+    /// `[input.keys]` table, see `Bindings::default`
+    pub keys: Bindings,
+    /// `random.seed`, seed of `Random` of `Game`, within [0, 2^63), random if it isn't given
+    pub seed: Option<u64>,
+    /// Tables of game sections merged from all the sources, see `Conf::extra`
+    pub extras: Table,
+    /// Files the parameters were read from, `Game::with_hot_reload` watches them
+    pub files: Vec<PathBuf>,
+    /// `KEY=VALUE` overrides applied on top of the files, see `Conf::with_overrides`
+    pub overrides: Vec<(String, String)>,
+}
+
+/// Parameters given by one source, missing ones keep their current values
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Sections {
+    camera: CameraSection,
+    render: RenderSection,
+    input: InputSection,
+    motion: MotionSection,
+    random: RandomSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CameraSection {
+    initial_point: Option<Vec<f64>>,
+    half_pi_discretization: Option<i64>,
+    horizontal_fov: Option<f64>,
+    vertical_fov: Option<f64>,
+    draw_distance: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RenderSection {
+    charmap: Option<String>,
+    precision: Option<i64>,
+    tick_rate: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct InputSection {
+    mouse_capture: Option<bool>,
+    mouse_sensitivity: Option<f64>,
+    key_repeat_delay: Option<f64>,
+    key_repeat_timeout: Option<f64>,
+    keys: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct MotionSection {
+    acceleration: Option<f64>,
+    friction: Option<f64>,
+    max_speed: Option<f64>,
+    gravity: Option<f64>,
+    jump_speed: Option<f64>,
+    eye_height: Option<f64>,
+    crouch_height: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct RandomSection {
+    seed: Option<i64>,
 }
 
 impl Conf {
     /// Reads parameters from the given TOML files.
     /// Each encountered in TOML parameter will bew reassigned if it has been already encountered
-    pub fn read(filepaths: Vec<&'static str>) -> ReRes<Self> {
+    pub fn read<P: AsRef<Path>>(filepaths: impl IntoIterator<Item = P>) -> ReRes<Self> {
         let mut conf = Self::default();
         for path in filepaths {
-            let content = match read_to_string(path) {
-                Ok(cont) => cont,
-                Err(_) => return Err(GameErr(InvalidConfFilePath(path))),
-            };
-            let mut table = match content.parse::<Table>() {
-                Ok(table) => table,
-                Err(_) => return Err(GameErr(InvalidConfFileContent(path))),
-            };
-            conf = conf
-                .parse_initpt(&mut table)?
-                .parse_angle_discr(&mut table)?
-                .parse_wfov(&mut table)?
-                .parse_hfov(&mut table)?
-                .parse_draw_dist(&mut table)?
-                .parse_charmap(&mut table)?
-                .parse_precision(&mut table)?;
+            conf = conf.merge_file(path)?;
         }
         Ok(conf)
     }
 
-    pub fn parse_initpt(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(INITPT_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
-        };
-        self.initpt = Point::new(parse_single(value, INITPT_KEY)?);
-        Ok(self)
+    /// Parses parameters from TOML string on top of the default ones
+    pub fn parse(content: &str) -> ReRes<Self> {
+        Self::default().merge_str(content, NO_FILE)
     }
 
-    pub fn parse_angle_discr(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(ANGLE_DISCR_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
-        };
-        match value {
-            Value::Integer(val) => self.angle_discr = val as usize,
-            Value::Float(val) => self.angle_discr = val as usize,
-            _ => return Err(GameErr(InvalidConfValue(ANGLE_DISCR_KEY))),
-        }
-        Ok(self)
+    /// Reads parameters from TOML file on top of the current ones, the file is added to `files`
+    pub fn merge_file(mut self, path: impl AsRef<Path>) -> ReRes<Self> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let content = read_to_string(path).map_err(|err| inaccessible(path, err))?;
+        self.files.push(path.to_path_buf());
+        self.merge_str(&content, &file)
     }
 
-    pub fn parse_wfov(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(WFOV_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
-        };
-        match value {
-            Value::Integer(fov) => self.wfov = fov as f64,
-            Value::Float(fov) => self.wfov = fov as f64,
-            _ => return Err(GameErr(InvalidConfValue(WFOV_KEY))),
+    /// Parses parameters from TOML string on top of the current ones,
+    /// `file` names the source in errors
+    fn merge_str(self, content: &str, file: &str) -> ReRes<Self> {
+        let table = content
+            .parse::<Table>()
+            .map_err(|err| toml_syntax(file, &err, content))?;
+        check(&table, file)?;
+        let sections = toml::from_str(content).map_err(|err: toml::de::Error| {
+            GameErr(InvalidConfValue {
+                file: file.to_string(),
+                location: Location::toml(&err, content),
+                reason: oneline(err.message()),
+            })
+        })?;
+        self.apply(table, sections, file)
+    }
+
+    /// Reads the same files again and applies the same overrides, eg when files are changed
+    pub fn reread(&self) -> ReRes<Self> {
+        Self::read(&self.files)?.with_overrides(self.overrides.clone())
+    }
+
+    /// Applies `KEY=VALUE` overrides, keys are `<section>.<key>` paths, eg `camera.draw_distance`
+    /// or `labyrinth.speed`, values are TOML values and strings may be given without quotes.
+    /// Bindings are overridden with `input.keys.<key name>` keys, eg `input.keys.ctrl+w = "jump"`
+    pub fn with_overrides<K, V>(
+        mut self,
+        overrides: impl IntoIterator<Item = (K, V)>,
+    ) -> ReRes<Self>
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let mut table = Table::new();
+        for (key, value) in overrides {
+            let (key, value) = (key.into(), value.into());
+            let mut path = key.splitn(3, '.').collect::<Vec<_>>();
+            let name = path.pop().unwrap_or_default();
+            let mut inner = &mut table;
+            for part in path {
+                let entry = inner
+                    .entry(part)
+                    .or_insert_with(|| Value::Table(Table::new()));
+                inner = match entry {
+                    Value::Table(table) => table,
+                    _ => return Err(invalid(OVERRIDES, format!("`{}` must be table", part))),
+                };
+            }
+            inner.insert(name.to_string(), parse_value(&value));
+            self.overrides.push((key, value));
         }
-        Ok(self)
+        check(&table, OVERRIDES)?;
+        let sections = Value::Table(table.clone())
+            .try_into()
+            .map_err(|err: toml::de::Error| invalid(OVERRIDES, oneline(&err.to_string())))?;
+        self.apply(table, sections, OVERRIDES)
     }
 
-    pub fn parse_hfov(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(HFOV_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
-        };
-        match value {
-            Value::Integer(fov) => self.hfov = Some(fov as f64),
-            Value::Float(fov) => self.hfov = Some(fov as f64),
-            _ => return Err(GameErr(InvalidConfValue(HFOV_KEY))),
+    /// Applies overrides given by environment variables `RUSTYENGINE_<SECTION>__<KEY>`,
+    /// eg `RUSTYENGINE_CAMERA__DRAW_DISTANCE=50`
+    pub fn with_env(self) -> ReRes<Self> {
+        let overrides = std::env::vars().filter_map(|(name, value)| {
+            let key = name.strip_prefix(ENV_PREFIX)?;
+            Some((key.to_lowercase().replace("__", "."), value))
+        });
+        self.with_overrides(overrides.collect::<Vec<_>>())
+    }
+
+    /// Applies overrides given by command line arguments `--<section>.<key>=<value>`, sections
+    /// and keys are case-insensitive and may use dashes instead of underscores,
+    /// eg `--camera.draw-distance=50` or `--input.keys.ctrl+w=jump`. The other arguments are ignored
+    pub fn with_args<A: AsRef<str>>(self, args: impl IntoIterator<Item = A>) -> ReRes<Self> {
+        let overrides = args
+            .into_iter()
+            .filter_map(|arg| {
+                let (key, value) = arg.as_ref().strip_prefix("--")?.split_once('=')?;
+                let key = key
+                    .splitn(3, '.')
+                    .enumerate()
+                    .map(|(i, part)| match i {
+                        2 => part.to_string(),
+                        _ => part.replace('-', "_").to_lowercase(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".");
+                Some((key, value.to_string()))
+            })
+            .collect::<Vec<_>>();
+        self.with_overrides(overrides)
+    }
+
+    /// Deserializes section of the game, eg `[labyrinth]`, from `extras`. Missing section is
+    /// deserialized from empty table, so `T` may give defaults with `#[serde(default)]`
+    pub fn extra<T: AsConfSection>(&self) -> ReRes<T> {
+        let section = self
+            .extras
+            .get(T::SECTION)
+            .cloned()
+            .unwrap_or_else(|| Value::Table(Table::new()));
+        let root = Table::from_iter([(T::SECTION.to_string(), section)]);
+        let mut sections: BTreeMap<String, T> = Value::Table(root)
+            .try_into()
+            .map_err(|err: toml::de::Error| invalid(&self.sources(), oneline(&err.to_string())))?;
+        Ok(sections.remove(T::SECTION).unwrap())
+    }
+
+    /// Files and overrides the parameters are taken from, joined for errors
+    fn sources(&self) -> String {
+        let mut sources = self
+            .files
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect::<Vec<_>>();
+        if !self.overrides.is_empty() {
+            sources.push(OVERRIDES.to_string());
+        }
+        match sources.is_empty() {
+            true => NO_FILE.to_string(),
+            false => sources.join(", "),
         }
-        Ok(self)
     }
 
-    pub fn parse_draw_dist(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(DRAW_DIST_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
+    /// Assigns the parameters given in the source, merges game sections into `extras`
+    /// and validates the result
+    fn apply(mut self, table: Table, sections: Sections, file: &str) -> ReRes<Self> {
+        let Sections {
+            camera,
+            render,
+            input,
+            motion,
+            random,
+        } = sections;
+        let out_of_range = |key, expected| {
+            GameErr(ConfValueOutOfRange {
+                file: file.to_string(),
+                key,
+                expected,
+            })
         };
-        match value {
-            Value::Integer(draw_dist) => self.draw_dist = draw_dist as f64,
-            Value::Float(draw_dist) => self.draw_dist = draw_dist as f64,
-            _ => return Err(GameErr(InvalidConfValue(DRAW_DIST_KEY))),
+        if let Some(initpt) = camera.initial_point {
+            self.initpt = Point::new(initpt);
         }
+        if let Some(discr) = camera.half_pi_discretization {
+            self.angle_discr =
+                usize::try_from(discr).map_err(|_| out_of_range(ANGLE_DISCR_KEY, "at least 1"))?;
+        }
+        set(&mut self.wfov, camera.horizontal_fov);
+        self.hfov = camera.vertical_fov.or(self.hfov);
+        set(&mut self.draw_dist, camera.draw_distance);
+
+        set(&mut self.charmap, render.charmap);
+        if let Some(precision) = render.precision {
+            self.precision = u8::try_from(precision)
+                .map_err(|_| out_of_range(PRECISION_KEY, "within [0, 255]"))?;
+        }
+        set(&mut self.tick_rate, render.tick_rate);
+
+        set(&mut self.mouse_capture, input.mouse_capture);
+        set(&mut self.mouse_sensitivity, input.mouse_sensitivity);
+        set(&mut self.repeat_delay, input.key_repeat_delay);
+        set(&mut self.repeat_timeout, input.key_repeat_timeout);
+        for (name, action) in input.keys {
+            let key = Key::parse(&name).ok_or_else(|| {
+                invalid(file, format!("unknown key {:?} in `{}`", name, KEYS_KEY))
+            })?;
+            match action.is_empty() {
+                true => self.keys.unbind(&key),
+                false => self.keys.bind(key, action),
+            }
+        }
+
+        let params = &mut self.motion;
+        set(&mut params.accel, motion.acceleration);
+        set(&mut params.friction, motion.friction);
+        set(&mut params.max_speed, motion.max_speed);
+        set(&mut params.gravity, motion.gravity);
+        set(&mut params.jump_speed, motion.jump_speed);
+        set(&mut params.height, motion.eye_height);
+        set(&mut params.crouch_height, motion.crouch_height);
+
+        if let Some(seed) = random.seed {
+            let seed =
+                u64::try_from(seed).map_err(|_| out_of_range(SEED_KEY, "within [0, 2^63)"))?;
+            self.seed = Some(seed);
+        }
+
+        for (name, value) in table {
+            if !SECTIONS.contains(&name.as_str()) {
+                merge(&mut self.extras, name, value);
+            }
+        }
+        self.validate_in(file)?;
         Ok(self)
     }
 
-    pub fn parse_charmap(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(CHARMAP_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
+    /// Checks ranges of parameters, `Game` constructors call it as fields can be set directly.
+    /// Errors name all the sources of parameters
+    pub fn validate(&self) -> ReRes<()> {
+        self.validate_in(&self.sources())
+    }
+
+    /// Checks ranges of parameters, errors name the given source
+    fn validate_in(&self, file: &str) -> ReRes<()> {
+        let out_of_range = |key, expected| {
+            Err(GameErr(ConfValueOutOfRange {
+                file: file.to_string(),
+                key,
+                expected,
+            }))
         };
-        match value {
-            Value::String(charmap) => self.charmap = charmap,
-            _ => return Err(GameErr(InvalidConfValue(CHARMAP_KEY))),
+        let fov = |fov: f64| fov > 0.0 && fov < 2.0;
+        if self.initpt.coord.rows() != 3 {
+            return out_of_range(INITPT_KEY, "array of 3 numbers");
+        }
+        if self.angle_discr == 0 {
+            return out_of_range(ANGLE_DISCR_KEY, "at least 1");
+        }
+        if !fov(self.wfov) {
+            return out_of_range(WFOV_KEY, "within (0, 2)");
+        }
+        if !self.hfov.map_or(true, fov) {
+            return out_of_range(HFOV_KEY, "within (0, 2)");
+        }
+        if !(self.draw_dist > 0.0) {
+            return out_of_range(DRAW_DIST_KEY, "positive");
+        }
+        if self.charmap.is_empty() {
+            return out_of_range(CHARMAP_KEY, "not empty");
+        }
+        if self.seed.map_or(false, |seed| seed > i64::MAX as u64) {
+            return out_of_range(SEED_KEY, "within [0, 2^63)");
+        }
+        let motion = &self.motion;
+        let non_negative = [
+            (MOUSE_SENSITIVITY_KEY, self.mouse_sensitivity),
+            (TICK_RATE_KEY, self.tick_rate),
+            (REPEAT_DELAY_KEY, self.repeat_delay),
+            (REPEAT_TIMEOUT_KEY, self.repeat_timeout),
+            (ACCEL_KEY, motion.accel),
+            (FRICTION_KEY, motion.friction),
+            (MAX_SPEED_KEY, motion.max_speed),
+            (GRAVITY_KEY, motion.gravity),
+            (JUMP_SPEED_KEY, motion.jump_speed),
+            (HEIGHT_KEY, motion.height),
+            (CROUCH_HEIGHT_KEY, motion.crouch_height),
+        ];
+        match non_negative.into_iter().find(|(_, value)| !(*value >= 0.0)) {
+            Some((key, _)) => out_of_range(key, "non-negative"),
+            None => Ok(()),
         }
-        Ok(self)
     }
+}
 
-    /// Parses `PRECISION` parameter from the `Table` parsed from TOML
-    pub fn parse_precision(mut self, table: &mut Table) -> ReRes<Self> {
-        let value = match table.remove(PRECISION_KEY) {
-            Some(value) => value,
-            None => return Ok(self),
-        };
-        match value {
-            Value::Integer(precision) => self.precision = (precision % 256) as u8,
-            _ => return Err(GameErr(InvalidConfValue(PRECISION_KEY))),
+/// Assigns value if it's given
+fn set<T>(field: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+/// Merges value into table, tables are merged key by key and the other values are replaced
+fn merge(table: &mut Table, key: String, value: Value) {
+    match (table.get_mut(&key), value) {
+        (Some(Value::Table(old)), Value::Table(new)) => {
+            for (key, value) in new {
+                merge(old, key, value);
+            }
+        }
+        (_, value) => {
+            table.insert(key, value);
         }
-        Ok(self)
     }
 }
 
-/// Parses `Vec<f64>` parameter from the `toml::Value::Array(toml::Array)`.
-/// `key` that is the name of parameter is used for error messages
-fn parse_single(value: Value, key: &'static str) -> ReRes<Vec<f64>> {
-    let array = match value {
-        Value::Array(array) => array,
-        _ => return Err(GameErr(InvalidConfValue(key))),
+/// Reports keys unknown in engine sections and top-level values that aren't tables,
+/// the other tables are sections of the game
+fn check(table: &Table, file: &str) -> ReRes<()> {
+    let unknown = |key: String, suggestion: Option<&str>| {
+        Err(GameErr(UnknownConfKey {
+            suggestion: suggestion.map(str::to_string),
+            key,
+        }))
     };
-    let mut single: Vec<f64> = vec![];
-    for val in array {
-        if single.len() == 3 {
-            return Err(GameErr(InvalidConfValue(key)));
+    for (name, value) in table {
+        match (SECTIONS.contains(&name.as_str()), value) {
+            (true, Value::Table(section)) => {
+                for key in section.keys() {
+                    let key = format!("{}.{}", name, key);
+                    if !KNOWN_KEYS.contains(&key.as_str()) {
+                        let suggestion = suggest(&key, &KNOWN_KEYS);
+                        return unknown(key, suggestion);
+                    }
+                }
+            }
+            (true, _) => return Err(invalid(file, format!("`{}` must be table", name))),
+            (false, Value::Table(_)) => (),
+            (false, _) => {
+                let renamed = RENAMED.iter().find(|(old, _)| old == name);
+                let suggestion = renamed.map(|(_, new)| *new);
+                return unknown(
+                    name.clone(),
+                    suggestion.or_else(|| suggest(name, &KNOWN_KEYS)),
+                );
+            }
         }
-        match val {
-            Value::Integer(c) => single.push(c as f64),
-            Value::Float(c) => single.push(c),
-            _ => return Err(GameErr(InvalidConfValue(key))),
+    }
+    Ok(())
+}
+
+fn invalid(file: &str, reason: String) -> ReErr {
+    GameErr(InvalidConfValue {
+        file: file.to_string(),
+        location: None,
+        reason,
+    })
+}
+
+/// Parses value of override, it's a string if it isn't valid TOML value
+fn parse_value(value: &str) -> Value {
+    match format!("value = {}", value).parse::<Table>() {
+        Ok(mut table) => table.remove("value").unwrap(),
+        Err(_) => Value::String(value.to_string()),
+    }
+}
+
+/// Name of key without its section
+fn last(key: &str) -> String {
+    key.rsplit('.').next().unwrap_or(key).to_lowercase()
+}
+
+/// Known key whose name differs from the name of unknown one in case or in a few chars
+fn similar(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    let name = last(key);
+    let (dist, known) = known
+        .iter()
+        .map(|known| (distance(&name, &last(known)), *known))
+        .min()?;
+    (dist <= (last(known).len() / 3).max(2)).then_some(known)
+}
+
+/// The most similar known key to the unknown one: key with similar name in any section
+/// or key whose name contains the unknown name or is contained in it
+fn suggest(key: &str, known: &[&'static str]) -> Option<&'static str> {
+    let name = last(key);
+    similar(key, known).or_else(|| {
+        known.iter().copied().find(|known| {
+            let known = last(known);
+            name.len() > 2 && (known.contains(&name) || name.contains(&known))
+        })
+    })
+}
+
+/// Levenshtein distance between strings
+fn distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.chars().collect::<Vec<_>>();
+    let mut prev = (0..=rhs.len()).collect::<Vec<_>>();
+    for (i, l) in lhs.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, r) in rhs.iter().enumerate() {
+            let subst = prev[j] + (l != *r) as usize;
+            cur.push(subst.min(prev[j + 1] + 1).min(cur[j] + 1));
         }
+        prev = cur;
     }
-    Ok(single)
+    prev[rhs.len()]
 }
 
 impl Default for Conf {
@@ -184,6 +635,17 @@ impl Default for Conf {
             draw_dist: 100.0,
             charmap: "$@&%#WMNB8RGAHP694XKYJOUVIL*+:-·".to_string(),
             precision: 100,
+            tick_rate: 30.0,
+            mouse_capture: false,
+            mouse_sensitivity: 1.0,
+            repeat_delay: 0.5,
+            repeat_timeout: 0.1,
+            motion: Motion::default(),
+            keys: Bindings::default(),
+            seed: None,
+            extras: Table::new(),
+            files: vec![],
+            overrides: vec![],
         }
     }
 }
@@ -1,4 +1,13 @@
-use {super::Conf, crate::math::*};
+use {
+    super::{AsConfSection, Conf},
+    crate::{
+        engn::Key,
+        errs::{GameErr, Location, ReErr},
+        math::*,
+    },
+    crossterm::event::{KeyCode, KeyModifiers},
+    serde::Deserialize,
+};
 
 #[test]
 fn drawdist_from_conf() {
@@ -51,5 +60,325 @@ fn conf_default_double_assignment() {
         "src/lib/conf/test/conf2.toml",
     ])
     .unwrap();
-    assert_eq!(conf.wscr, 100);
+    assert_eq!(conf.precision, 100);
+}
+
+#[test]
+fn keys_from_conf() {
+    let conf = Conf::read(vec!["src/lib/conf/test/conf3.toml"]).unwrap();
+    let z = Key::new(KeyCode::Char('z'), KeyModifiers::NONE);
+    let up = Key::new(KeyCode::Up, KeyModifiers::CONTROL);
+    let w = Key::new(KeyCode::Char('w'), KeyModifiers::NONE);
+    assert_eq!(conf.keys.action(&z), Some("move_forward"));
+    assert_eq!(conf.keys.action(&up), Some("jump"));
+    assert_eq!(conf.keys.action(&w), None);
+    assert_eq!(conf.keys.keys("move_left").len(), 1);
+}
+
+#[test]
+fn invalid_key_name() {
+    let err = Conf::read(vec!["src/lib/conf/test/conf4.toml"]).unwrap_err();
+    assert_eq!(
+        err,
+        ReErr::GameErr(GameErr::InvalidConfValue {
+            file: "src/lib/conf/test/conf4.toml".to_string(),
+            location: None,
+            reason: "unknown key \"hyper+w\" in `input.keys`".to_string(),
+        })
+    );
+}
+
+#[test]
+fn mouse_from_conf() {
+    let conf = Conf::read(vec!["src/lib/conf/test/conf3.toml"]).unwrap();
+    assert!(conf.mouse_capture);
+    assert_eq!(conf.mouse_sensitivity, 0.5);
+}
+
+#[test]
+fn ticks_from_conf() {
+    let conf = Conf::read(vec!["src/lib/conf/test/conf3.toml"]).unwrap();
+    assert_eq!(conf.tick_rate, 60.0);
+    assert_eq!(conf.repeat_delay, 0.25);
+    assert_eq!(conf.repeat_timeout, 0.1);
+}
+
+#[test]
+fn motion_from_conf() {
+    let conf = Conf::read(vec!["src/lib/conf/test/conf3.toml"]).unwrap();
+    assert_eq!(conf.motion.gravity, 9.8);
+    assert_eq!(conf.motion.height, 3.0);
+    assert_eq!(conf.motion.jump_speed, 7.0);
+}
+
+#[test]
+fn seed_from_conf() {
+    assert_eq!(Conf::default().seed, None);
+    assert_eq!(Conf::parse("[random]\nseed = 42").unwrap().seed, Some(42));
+    assert_eq!(out_of_range("[random]\nseed = -1"), "random.seed");
+    let mut conf = Conf::default();
+    conf.seed = Some(u64::MAX);
+    assert!(conf.validate().is_err());
+}
+
+fn out_of_range(content: &str) -> &'static str {
+    match Conf::parse(content) {
+        Err(ReErr::GameErr(GameErr::ConfValueOutOfRange { file, key, .. })) => {
+            assert_eq!(file, "<string>");
+            key
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn values_out_of_range() {
+    assert_eq!(
+        out_of_range("[camera]\nhalf_pi_discretization = 0"),
+        "camera.half_pi_discretization"
+    );
+    assert_eq!(
+        out_of_range("[camera]\nhalf_pi_discretization = -2"),
+        "camera.half_pi_discretization"
+    );
+    assert_eq!(out_of_range("[render]\ncharmap = \"\""), "render.charmap");
+    assert_eq!(
+        out_of_range("[render]\nprecision = 300"),
+        "render.precision"
+    );
+    assert_eq!(out_of_range("[render]\nprecision = -1"), "render.precision");
+    assert_eq!(
+        out_of_range("[camera]\ninitial_point = [1, 2]"),
+        "camera.initial_point"
+    );
+    assert_eq!(
+        out_of_range("[camera]\ndraw_distance = 0"),
+        "camera.draw_distance"
+    );
+    assert_eq!(
+        out_of_range("[camera]\nhorizontal_fov = 3"),
+        "camera.horizontal_fov"
+    );
+    assert_eq!(
+        out_of_range("[input]\nmouse_sensitivity = -1"),
+        "input.mouse_sensitivity"
+    );
+    assert_eq!(
+        Conf::parse("[render]\nprecision = 255").unwrap().precision,
+        255
+    );
+
+    let mut conf = Conf::read(["src/bin/conf.toml"]).unwrap();
+    conf.angle_discr = 0;
+    match conf.validate() {
+        Err(ReErr::GameErr(GameErr::ConfValueOutOfRange { file, .. })) => {
+            assert_eq!(file, "src/bin/conf.toml")
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn unknown_keys_are_suggested() {
+    let suggestion = |content| match Conf::parse(content) {
+        Err(ReErr::GameErr(GameErr::UnknownConfKey { suggestion, .. })) => suggestion,
+        res => panic!("unexpected {:?}", res),
+    };
+    let some = |key: &str| Some(key.to_string());
+    assert_eq!(
+        suggestion("[camera]\ndraw_distanse = 5"),
+        some("camera.draw_distance")
+    );
+    assert_eq!(
+        suggestion("[render]\ndraw_distance = 5"),
+        some("camera.draw_distance")
+    );
+    assert_eq!(
+        suggestion("DRAW_DISTANCE = 5"),
+        some("camera.draw_distance")
+    );
+    assert_eq!(
+        suggestion("ROTATION_HALF_PI_DISCRETIZATION = 4"),
+        some("camera.half_pi_discretization")
+    );
+    assert_eq!(
+        suggestion("HORIZONTAL_FIELD_OF_VIEW_OUT_OF_PI = 0.5"),
+        some("camera.horizontal_fov")
+    );
+    assert_eq!(suggestion("EYE_HEIGHT = 2"), some("motion.eye_height"));
+    assert_eq!(suggestion("speed_of_light = 1"), None);
+    let conf = Conf::parse("[camra]\ndraw_distance = 5").unwrap();
+    assert!(conf.extras.contains_key("camra"));
+
+    let err = Conf::parse("[render]\ntickrate = 5").unwrap_err();
+    assert_eq!(
+        err,
+        ReErr::GameErr(GameErr::UnknownConfKey {
+            key: "render.tickrate".to_string(),
+            suggestion: some("render.tick_rate"),
+        })
+    );
+    match err {
+        ReErr::GameErr(err) => {
+            assert!(err.to_string().ends_with("did you mean render.tick_rate?"))
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn invalid_values_are_located() {
+    let reason = |content| match Conf::parse(content) {
+        Err(ReErr::GameErr(GameErr::InvalidConfValue { reason, .. })) => reason,
+        res => panic!("unexpected {:?}", res),
+    };
+    assert_eq!(
+        reason("[camera]\n\ndraw_distance = \"far\""),
+        "invalid type: string \"far\", expected f64"
+    );
+    assert_eq!(reason("camera = 5"), "`camera` must be table");
+    let location = |content| match Conf::parse(content) {
+        Err(ReErr::GameErr(
+            GameErr::InvalidSyntax { location, .. } | GameErr::InvalidConfValue { location, .. },
+        )) => location,
+        res => panic!("unexpected {:?}", res),
+    };
+    assert_eq!(
+        location("[camera]\ndraw_distance = "),
+        Some(Location {
+            line: 2,
+            column: 17
+        })
+    );
+    assert_eq!(
+        location("[camera]\n\ndraw_distance = \"far\""),
+        Some(Location {
+            line: 3,
+            column: 17
+        })
+    );
+    assert_eq!(location("camera = 5"), None);
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(default)]
+struct Game {
+    title: String,
+    speed: f64,
+    levels: Vec<String>,
+}
+
+impl AsConfSection for Game {
+    const SECTION: &'static str = "game";
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self {
+            title: "default".to_string(),
+            speed: 1.0,
+            levels: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Strict {
+    name: String,
+}
+
+impl AsConfSection for Strict {
+    const SECTION: &'static str = "strict";
+}
+
+#[test]
+fn extra_sections() {
+    assert_eq!(Conf::default().extra::<Game>().unwrap(), Game::default());
+    let conf = Conf::read(["src/lib/conf/test/conf3.toml"])
+        .unwrap()
+        .merge_file("src/lib/conf/test/conf1.toml")
+        .unwrap()
+        .with_args(["--game.levels=[\"a\", \"b\"]", "--game.speed=2.5"])
+        .unwrap();
+    assert_eq!(
+        conf.extra::<Game>().unwrap(),
+        Game {
+            title: "test".to_string(),
+            speed: 2.5,
+            levels: vec!["a".to_string(), "b".to_string()],
+        }
+    );
+    assert_eq!(conf.reread().unwrap(), conf);
+
+    match Conf::parse("[strict]\nname = 5").unwrap().extra::<Strict>() {
+        Err(ReErr::GameErr(GameErr::InvalidConfValue { reason, .. })) => {
+            assert!(reason.ends_with("in `strict.name`"), "{}", reason)
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(Conf::default().extra::<Strict>().is_err());
+}
+
+#[test]
+fn conf_from_string() {
+    let conf = Conf::parse("[camera]\ndraw_distance = 7\n[input.keys]\nx = \"jump\"").unwrap();
+    assert_eq!(conf.draw_dist, 7.0);
+    let x = Key::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert_eq!(conf.keys.action(&x), Some("jump"));
+    assert!(conf.files.is_empty());
+    assert!(matches!(
+        Conf::parse("[camera]\ndraw_distance = "),
+        Err(ReErr::GameErr(GameErr::InvalidSyntax { .. }))
+    ));
+
+    let path = std::path::PathBuf::from("src/lib/conf/test/missing.toml");
+    match Conf::read([&path]) {
+        Err(ReErr::GameErr(GameErr::InaccessibleFile { file, source })) => {
+            assert_eq!(file, path.display().to_string());
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn overrides_on_top_of_files() {
+    let conf = Conf::read(["src/lib/conf/test/conf1.toml"])
+        .unwrap()
+        .with_overrides([
+            ("camera.draw_distance", "5"),
+            ("render.charmap", "ab"),
+            ("input.keys.x", "jump"),
+        ])
+        .unwrap()
+        .with_args([
+            "game",
+            "-v",
+            "--Render.Tick-Rate=12",
+            "--input.keys.ctrl+y=quit",
+        ])
+        .unwrap();
+    assert_eq!(conf.draw_dist, 5.0);
+    assert_eq!(conf.charmap, "ab");
+    assert_eq!(conf.tick_rate, 12.0);
+    assert_eq!(conf.initpt, Point::new(vec![1.0, 1.0, 1.0]));
+    let y = Key::new(KeyCode::Char('y'), KeyModifiers::CONTROL);
+    assert_eq!(conf.keys.action(&y), Some("quit"));
+    assert_eq!(conf.reread().unwrap(), conf);
+
+    assert!(matches!(
+        Conf::default().with_args(["--camera.draw-distanse=5"]),
+        Err(ReErr::GameErr(GameErr::UnknownConfKey { .. }))
+    ));
+    match Conf::default().with_overrides([("camera.draw_distance", "far")]) {
+        Err(ReErr::GameErr(GameErr::InvalidConfValue { reason, .. })) => {
+            assert!(reason.ends_with("in `camera.draw_distance`"), "{}", reason)
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+
+    std::env::set_var("RUSTYENGINE_INPUT__KEY_REPEAT_TIMEOUT", "0.3");
+    let conf = Conf::default().with_env();
+    std::env::remove_var("RUSTYENGINE_INPUT__KEY_REPEAT_TIMEOUT");
+    assert_eq!(conf.unwrap().repeat_timeout, 0.3);
 }
@@ -0,0 +1,43 @@
+use {
+    crate::{engn::*, errs::ReRes},
+    crossterm::event::Event,
+    std::time::Duration,
+};
+
+/// Trait for output and input backends, `Game` presents frames and obtains events through it.
+/// Sizes are given as (rows, cols)
+pub trait AsBackend {
+    /// Prepares backend for drawing and returns size of the drawing area
+    fn init(&mut self) -> ReRes<(usize, usize)>;
+
+    /// Current size of the drawing area
+    fn size(&self) -> ReRes<(usize, usize)>;
+
+    /// Shows the given frame
+    fn present(&mut self, frame: &Frame) -> ReRes<()>;
+
+    /// Waits for the next event no longer than `timeout`, blocks until event if `timeout` is `None`.
+    /// Returns `None` if there is no event yet
+    fn poll(&mut self, timeout: Option<Duration>) -> ReRes<Option<Event>>;
+
+    /// Time passed since backend creation, `Game` measures frame time with it
+    fn now(&self) -> Duration;
+
+    /// Whether key releases are reported, otherwise held keys are detected with timeouts
+    fn reports_releases(&self) -> bool {
+        false
+    }
+
+    /// Starts or stops reporting mouse events, backends without mouse ignore it
+    fn capture_mouse(&mut self, _capture: bool) -> ReRes<()> {
+        Ok(())
+    }
+
+    /// Whether backend won't provide events anymore, `Game::run` stops then
+    fn is_closed(&self) -> bool {
+        false
+    }
+
+    /// Restores everything that has been changed by `init`
+    fn teardown(&mut self) -> ReRes<()>;
+}
@@ -0,0 +1,158 @@
+use {
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    std::collections::HashMap,
+};
+
+/// Key with modifiers. Shift is kept in the char case for char keys,
+/// so `shift+w` and `W` are the same key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        match code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(ch.to_ascii_uppercase()),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            code => Self { code, modifiers },
+        }
+    }
+
+    /// Parses key names like `w`, `Up`, `space`, `F5`, `ctrl+shift+a`.
+    /// Names of modifiers and special keys are case insensitive
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut parts = name.split('+').collect::<Vec<_>>();
+        // `+` itself or `ctrl++`
+        if parts.len() > 1 && parts[parts.len() - 1].is_empty() {
+            parts.pop();
+            *parts.last_mut()? = "+";
+        }
+        let code = parse_code(parts.pop()?)?;
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "shift" => KeyModifiers::SHIFT,
+                "alt" => KeyModifiers::ALT,
+                "super" => KeyModifiers::SUPER,
+                _ => return None,
+            };
+        }
+        Some(Self::new(code, modifiers))
+    }
+}
+
+impl From<&KeyEvent> for Key {
+    fn from(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+/// Parses key name without modifiers
+fn parse_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+    let code = match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "insert" => KeyCode::Insert,
+        "delete" | "del" => KeyCode::Delete,
+        name => match name.strip_prefix('f').map(str::parse::<u8>) {
+            Some(Ok(n)) if (1..=24).contains(&n) => KeyCode::F(n),
+            _ => return None,
+        },
+    };
+    Some(code)
+}
+
+/// Map from keys to names of actions, eg `Up` to `rotate_up`.
+/// `Game` keeps them in `InputState` and passes them to `AsEvent::bound`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    map: HashMap<Key, String>,
+}
+
+impl Bindings {
+    /// Bindings without any key
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Binds key to the action replacing its previous action
+    pub fn bind(&mut self, key: Key, action: impl Into<String>) {
+        self.map.insert(key, action.into());
+    }
+
+    /// Removes key binding if it exists
+    pub fn unbind(&mut self, key: &Key) {
+        self.map.remove(key);
+    }
+
+    /// Action bound to the key
+    pub fn action(&self, key: &Key) -> Option<&str> {
+        self.map.get(key).map(String::as_str)
+    }
+
+    /// Action bound to the key pressed in the event, other events including releases give `None`
+    pub fn on(&self, event: &Event) -> Option<&str> {
+        match event {
+            Event::Key(key) if key.kind != KeyEventKind::Release => self.action(&key.into()),
+            _ => None,
+        }
+    }
+
+    /// All the keys bound to the action
+    pub fn keys(&self, action: &str) -> Vec<Key> {
+        self.map
+            .iter()
+            .filter(|(_, act)| act.as_str() == action)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+impl Default for Bindings {
+    /// Arrows rotate, WASD move, space jumps, `c` crouches, `m` toggles minimap, `F3` toggles stats overlay and `ctrl+c` quits
+    fn default() -> Self {
+        let mut bindings = Self::new();
+        let keys = [
+            (KeyCode::Up, "rotate_up"),
+            (KeyCode::Down, "rotate_down"),
+            (KeyCode::Left, "rotate_left"),
+            (KeyCode::Right, "rotate_right"),
+            (KeyCode::Char('w'), "move_forward"),
+            (KeyCode::Char('s'), "move_back"),
+            (KeyCode::Char('a'), "move_left"),
+            (KeyCode::Char('d'), "move_right"),
+            (KeyCode::Char(' '), "jump"),
+            (KeyCode::Char('c'), "crouch"),
+            (KeyCode::Char('m'), "toggle_minimap"),
+            (KeyCode::F(3), "toggle_stats"),
+        ];
+        for (code, action) in keys {
+            bindings.bind(Key::new(code, KeyModifiers::NONE), action);
+        }
+        bindings.bind(Key::new(KeyCode::Char('c'), KeyModifiers::CONTROL), "quit");
+        bindings
+    }
+}
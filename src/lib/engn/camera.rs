@@ -121,6 +121,38 @@ impl Camera {
         (ang.cos(), ang.sin())
     }
 
+    pub fn set_pos(&mut self, pos: Point) {
+        self.pos = pos
+    }
+
+    /// Angle of view direction above the horizon in radians
+    pub fn zenith(&self) -> f64 {
+        (self.discr as f64 - 1.0 - self.zen_idx as f64) * self.angle_step()
+    }
+
+    /// Angle of view direction from axis x towards axis y in radians
+    pub fn azimuth(&self) -> f64 {
+        self.azi_idx as f64 * self.angle_step()
+    }
+
+    /// Looks at the nearest zenith angle available with `angle_discr`,
+    /// it's clamped to the straight up and down directions
+    pub fn set_zenith(&mut self, angle: f64) {
+        let steps = (angle / self.angle_step()).round() as isize;
+        let idx = self.discr as isize - 1 - steps;
+        self.zen_idx = idx.clamp(0, self.zen_max as isize - 1) as usize
+    }
+
+    /// Looks at the nearest azimuth angle available with `angle_discr`
+    pub fn set_azimuth(&mut self, angle: f64) {
+        let steps = (angle / self.angle_step()).round() as isize;
+        self.azi_idx = steps.rem_euclid(self.azi_max as isize) as usize
+    }
+
+    fn angle_step(&self) -> f64 {
+        FRAC_PI_2 / self.discr as f64
+    }
+
     pub fn mv(&mut self, vec: &Vector) -> ReRes<()> {
         self.pos.mv_assign(vec)
     }
@@ -10,16 +10,16 @@ use {
         math::*,
     },
     either::Either,
-    std::{cmp::min, marker::PhantomData, str::Chars, thread, time::Duration},
+    std::marker::PhantomData,
 };
 
-/// Stores picture as `Vec<String>` respectively to `charmap` given in the `Conf`
+/// Stores picture as `Frame` respectively to `charmap` given in the `Conf`
 #[derive(Debug)]
 pub struct Canvas<Scn: AsScene> {
     phantom: PhantomData<Scn>,
     size: (usize, usize),
     charcoal: Charcoal,
-    picture: Vec<String>,
+    frame: Frame,
 }
 
 impl<Scn: AsScene> Canvas<Scn> {
@@ -29,45 +29,33 @@ impl<Scn: AsScene> Canvas<Scn> {
             phantom: PhantomData,
             size,
             charcoal: Charcoal::new(chars, draw_dist),
-            picture: vec![(0..size.1).map(|_| ' ').collect::<String>(); size.0],
+            frame: Frame::new(size),
         }
     }
 
     /// Updates picture via colliding entities against all camera rays
     pub fn update(&mut self, camera: &Camera, cs: &CoordSys, scene: &Scn) -> ReRes<()> {
-        for r in 0..self.size.0 {
-            self.picture[r] = (0..self.size.1)
-                .map(|c| {
-                    let ray = camera.ray(r, c);
-                    match scene.collide(cs, &camera.pos, ray) {
-                        Either::Left(d) => self.charcoal.ignite(d),
-                        Either::Right(c) => c,
-                    }
-                })
-                .collect::<String>();
-        }
+        self.frame.render(camera, cs, scene, &self.charcoal);
         Ok(())
     }
 
-    /// Prints all string to console
-    pub fn draw(&self) -> ReRes<()> {
-        console::move_cursor(3, 0)?;
-        for line in &self.picture {
-            println!("{}", line);
-        }
-        Ok(())
+    /// Last rendered picture
+    pub fn frame(&self) -> &Frame {
+        &self.frame
     }
 
-    /// Clears all console and shows one message
-    pub fn banner(&self, msg: &str, timeout: Duration) -> ReRes<()> {
-        let col = self.size.1.saturating_sub(msg.len()) / 2;
-        let row = self.size.0 / 2;
-        console::move_cursor(row as u16, col as u16)?;
+    /// Mutable ref to the last rendered picture, eg for drawing overlays
+    pub(crate) fn frame_mut(&mut self) -> &mut Frame {
+        &mut self.frame
+    }
 
-        console::clear();
-        println!("{}", &msg[..min(msg.len(), self.size.1)]);
-        thread::sleep(timeout);
-        console::clear();
-        Ok(())
+    /// `Charcoal` the distances are turned into chars with
+    pub fn charcoal(&self) -> &Charcoal {
+        &self.charcoal
+    }
+
+    /// Replaces `Charcoal`, eg when `Conf` is reloaded
+    pub fn set_charcoal(&mut self, charcoal: Charcoal) {
+        self.charcoal = charcoal;
     }
 }
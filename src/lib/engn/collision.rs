@@ -0,0 +1,256 @@
+use crate::{
+    engn::{vec3::*, *},
+    math::*,
+};
+
+/// Offset of probe rays that estimate surface normal in `estimate_hit`
+const PROBE: f64 = 1e-2;
+
+/// The nearest collision of ray with its surface normal
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    /// Coefficient of ray direction resizing to the collision, the same as `AsScene::trace` gives
+    pub t: f64,
+    /// Unit normal of the surface facing against ray direction
+    pub normal: Vector,
+}
+
+/// Default `AsScene::hit`: collision is taken from `AsScene::trace` and normal is estimated
+/// with two more rays shifted a bit across `dir`. If they miss the same surface
+/// normal is taken opposite to `dir`
+pub fn estimate_hit<Scn: AsScene + ?Sized>(
+    scene: &Scn,
+    cs: &CoordSys,
+    inc: &Point,
+    dir: &Vector,
+) -> Option<Hit> {
+    let t = scene.trace(cs, inc, dir)?;
+    let (inc, d) = (arr(inc), arr(dir));
+    let unit = unit(d)?;
+    let hit = add(inc, scale(d, t));
+    let (u, v) = across(unit)?;
+    let probe = |off: [f64; 3]| {
+        let origin = add(inc, scale(off, PROBE));
+        let t = scene.trace(cs, &vector(origin), dir)?;
+        Some(sub(add(origin, scale(d, t)), hit))
+    };
+    let normal = match (probe(u), probe(v)) {
+        (Some(a), Some(b)) => self::unit(cross(a, b)).unwrap_or(neg(unit)),
+        _ => neg(unit),
+    };
+    let normal = match dot(normal, unit) > 0.0 {
+        true => neg(normal),
+        false => normal,
+    };
+    Some(Hit {
+        t,
+        normal: vector(normal),
+    })
+}
+
+/// Shape of body moved by `Collider`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    /// Sphere centered at the position
+    Sphere { radius: f64 },
+    /// Vertical capsule: its top sphere is centered at the position
+    /// and its bottom sphere is `height` lower
+    Capsule { radius: f64, height: f64 },
+}
+
+impl Shape {
+    pub fn radius(&self) -> f64 {
+        match self {
+            Self::Sphere { radius } | Self::Capsule { radius, .. } => *radius,
+        }
+    }
+
+    /// Vertical offsets of spheres covering the shape, from the top to the bottom
+    fn offsets(&self) -> Vec<f64> {
+        match *self {
+            Self::Sphere { .. } => vec![0.0],
+            Self::Capsule { radius, height } => {
+                let n = match radius > 0.0 {
+                    true => (height / radius).ceil().max(1.0) as usize,
+                    false => 1,
+                };
+                (0..=n).map(|i| -height * i as f64 / n as f64).collect()
+            }
+        }
+    }
+}
+
+/// Collision response for bodies moving through `AsScene`: the shape is swept along the
+/// motion with rays from its spheres centers and rims, on the hit remaining motion is
+/// projected onto the surface plane so body slides along walls, and ledges lower than
+/// `step_height` are stepped up. Surface normals are obtained with `AsScene::hit`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collider {
+    pub shape: Shape,
+    /// Gap kept between the shape and surfaces
+    pub skin: f64,
+    /// Max number of slides along surfaces within one motion
+    pub max_slides: usize,
+    /// Max height of ledges that are stepped up, 0 disables stepping
+    pub step_height: f64,
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            skin: 0.01,
+            max_slides: 3,
+            step_height: 0.0,
+        }
+    }
+
+    pub fn with_skin(mut self, skin: f64) -> Self {
+        self.skin = skin;
+        self
+    }
+
+    pub fn with_max_slides(mut self, max_slides: usize) -> Self {
+        self.max_slides = max_slides;
+        self
+    }
+
+    pub fn with_step_height(mut self, step_height: f64) -> Self {
+        self.step_height = step_height;
+        self
+    }
+
+    /// The first surface that the shape at `pos` touches moving along `mv`.
+    /// `Hit::t` is the part of `mv` within [0, 1) that can be passed, `None` if the whole `mv` is free
+    pub fn sweep<Scn: AsScene + ?Sized>(
+        &self,
+        scene: &Scn,
+        cs: &CoordSys,
+        pos: &Point,
+        mv: &Vector,
+    ) -> Option<Hit> {
+        self.cast(scene, cs, arr(pos), arr(mv))
+            .map(|(t, normal)| Hit {
+                t,
+                normal: vector(normal),
+            })
+    }
+
+    /// Motion of the shape at `pos` along `mv` sliding along surfaces it hits
+    pub fn slide<Scn: AsScene + ?Sized>(
+        &self,
+        scene: &Scn,
+        cs: &CoordSys,
+        pos: &Point,
+        mv: &Vector,
+    ) -> Vector {
+        let pos = arr(pos);
+        vector(sub(self.slide_from(scene, cs, pos, arr(mv)), pos))
+    }
+
+    /// Motion of the shape at `pos` along `mv` sliding along surfaces and stepping up ledges
+    /// if that takes it further horizontally
+    pub fn move_and_slide<Scn: AsScene + ?Sized>(
+        &self,
+        scene: &Scn,
+        cs: &CoordSys,
+        pos: &Point,
+        mv: &Vector,
+    ) -> Vector {
+        let (pos, mv) = (arr(pos), arr(mv));
+        let flat = self.slide_from(scene, cs, pos, mv);
+        let progress = |end: [f64; 3]| (end[0] - pos[0]).hypot(end[1] - pos[1]);
+        if self.step_height <= 0.0 || progress(flat) + EPS >= mv[0].hypot(mv[1]) {
+            return vector(sub(flat, pos));
+        }
+
+        let up = [0.0, 0.0, self.step_height];
+        let raised = match self.cast(scene, cs, pos, up) {
+            Some((t, _)) => add(pos, scale(up, t)),
+            None => add(pos, up),
+        };
+        let moved = self.slide_from(scene, cs, raised, mv);
+        let down = [0.0, 0.0, pos[2] - raised[2]];
+        let stepped = match self.cast(scene, cs, moved, down) {
+            Some((t, _)) => add(moved, scale(down, t)),
+            None => add(moved, down),
+        };
+        match progress(stepped) > progress(flat) + EPS {
+            true => vector(sub(stepped, pos)),
+            false => vector(sub(flat, pos)),
+        }
+    }
+
+    /// End position of sliding
+    fn slide_from<Scn: AsScene + ?Sized>(
+        &self,
+        scene: &Scn,
+        cs: &CoordSys,
+        mut pos: [f64; 3],
+        mut rest: [f64; 3],
+    ) -> [f64; 3] {
+        let mut prev: Option<[f64; 3]> = None;
+        for _ in 0..=self.max_slides {
+            let (t, normal) = match self.cast(scene, cs, pos, rest) {
+                Some(hit) => hit,
+                None => return add(pos, rest),
+            };
+            pos = add(pos, scale(rest, t));
+            let left = scale(rest, 1.0 - t);
+            rest = sub(left, scale(normal, dot(left, normal)));
+            // in the corner motion goes along the crease of both surfaces
+            if let Some(prev) = prev.filter(|prev| dot(rest, *prev) < 0.0) {
+                rest = match unit(cross(prev, normal)) {
+                    Some(line) => scale(line, dot(left, line)),
+                    None => [0.0; 3],
+                };
+            }
+            prev = Some(normal);
+            if norm(rest) < EPS {
+                break;
+            }
+        }
+        pos
+    }
+
+    /// Passable part of `mv` and normal of the surface that stops the shape
+    fn cast<Scn: AsScene + ?Sized>(
+        &self,
+        scene: &Scn,
+        cs: &CoordSys,
+        pos: [f64; 3],
+        mv: [f64; 3],
+    ) -> Option<(f64, [f64; 3])> {
+        let len = norm(mv);
+        let d = unit(mv)?;
+        let (u, v) = across(d)?;
+        let (r, dir) = (self.shape.radius(), vector(mv));
+
+        let mut nearest: Option<(f64, [f64; 3])> = None;
+        for dz in self.shape.offsets() {
+            let center = add(pos, [0.0, 0.0, dz]);
+            let rims = [u, neg(u), v, neg(v)].map(|off| (add(center, scale(off, r)), false));
+            for (origin, is_center) in [(center, true)].into_iter().chain(rims) {
+                let hit = match scene.hit(cs, &vector(origin), &dir) {
+                    Some(hit) => hit,
+                    None => continue,
+                };
+                let normal = arr(&hit.normal);
+                let cos = -dot(normal, d);
+                let dist = hit.t * len;
+                // center stops when it's `r` away from the surface plane,
+                // rim ray covers edges that center misses
+                let free = match (is_center, cos > EPS) {
+                    (true, true) => (dist * cos - r - self.skin) / cos,
+                    (true, false) => continue,
+                    (false, _) => dist - self.skin,
+                };
+                let free = free.max(0.0);
+                if free < len && nearest.map_or(true, |(best, _)| free < best) {
+                    nearest = Some((free, normal));
+                }
+            }
+        }
+        nearest.map(|(free, normal)| (free / len, normal))
+    }
+}
@@ -1,34 +1,121 @@
 use {
-    crate::errs::{ReErr, ReRes},
+    crate::{
+        engn::*,
+        errs::{ReErr, ReRes},
+    },
     crossterm::{
         cursor,
-        event::{self as crossterm_event, Event},
-        terminal::{enable_raw_mode, size},
-        ExecutableCommand, Result,
+        event::{
+            self as crossterm_event, DisableMouseCapture, EnableMouseCapture, Event,
+            KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        },
+        terminal::{self, disable_raw_mode, enable_raw_mode, ClearType},
+        ExecutableCommand, QueueableCommand,
+    },
+    std::{
+        io::{stdout, Stdout, Write},
+        time::{Duration, Instant},
     },
-    std::io::{stdout, Error as IoError},
 };
 
-/// Clears, enables raw mode anr returns console size as (rows, cols)
-pub fn init() -> ReRes<(u16, u16)> {
-    let size = size()?;
-    enable_raw_mode()?;
-    // clear();
-    Ok((size.1, size.0))
+/// Backend that draws in console and listens to its events via `crossterm`.
+/// Key releases are reported if console supports kitty keyboard protocol
+#[derive(Debug)]
+pub struct Console {
+    out: Stdout,
+    start: Instant,
+    mouse: bool,
+    kitty: bool,
 }
 
-/// Clears console
-pub fn clear() {
-    print!("\x1b[2J");
+impl Console {
+    pub fn new() -> Self {
+        Self {
+            out: stdout(),
+            start: Instant::now(),
+            mouse: false,
+            kitty: false,
+        }
+    }
+
+    /// Clears console
+    pub fn clear(&mut self) -> ReRes<()> {
+        self.out.execute(terminal::Clear(ClearType::All))?;
+        Ok(())
+    }
 }
 
-/// Blocks process until new event obtained
-pub fn listen() -> Result<Event> {
-    crossterm_event::read()
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-/// Moves cursor on the given position retunring error if position isn't valid
-pub(crate) fn move_cursor(row: u16, col: u16) -> Result<()> {
-    stdout().execute(cursor::MoveTo(col, row))?;
-    Ok(())
+impl AsBackend for Console {
+    /// Enables raw mode and key releases reporting, hides cursor and returns console size
+    fn init(&mut self) -> ReRes<(usize, usize)> {
+        let size = self.size()?;
+        enable_raw_mode()?;
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            let flags = KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+            self.out.execute(PushKeyboardEnhancementFlags(flags))?;
+            self.kitty = true;
+        }
+        self.out.execute(cursor::Hide)?;
+        Ok(size)
+    }
+
+    fn size(&self) -> ReRes<(usize, usize)> {
+        let (cols, rows) = terminal::size()?;
+        Ok((rows as usize, cols as usize))
+    }
+
+    fn present(&mut self, frame: &Frame) -> ReRes<()> {
+        for (r, line) in frame.lines().iter().enumerate() {
+            self.out.queue(cursor::MoveTo(0, r as u16))?;
+            self.out.write_all(line.as_bytes())?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn poll(&mut self, timeout: Option<Duration>) -> ReRes<Option<Event>> {
+        match timeout {
+            Some(timeout) if !crossterm_event::poll(timeout)? => Ok(None),
+            _ => Ok(Some(crossterm_event::read()?)),
+        }
+    }
+
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn reports_releases(&self) -> bool {
+        self.kitty
+    }
+
+    fn capture_mouse(&mut self, capture: bool) -> ReRes<()> {
+        match capture {
+            true => self.out.execute(EnableMouseCapture)?,
+            false => self.out.execute(DisableMouseCapture)?,
+        };
+        self.mouse = capture;
+        Ok(())
+    }
+
+    /// Disables raw mode and mouse capture, shows cursor and clears console
+    fn teardown(&mut self) -> ReRes<()> {
+        if self.mouse {
+            self.capture_mouse(false)?;
+        }
+        if self.kitty {
+            self.out.execute(PopKeyboardEnhancementFlags)?;
+            self.kitty = false;
+        }
+        self.clear()?;
+        self.out.execute(cursor::MoveTo(0, 0))?;
+        self.out.execute(cursor::Show)?;
+        disable_raw_mode()?;
+        Ok(())
+    }
 }
@@ -0,0 +1,200 @@
+use crate::{engn::*, errs::ReRes, math::*};
+
+/// Distance to the ground within which camera is considered standing on it
+const GROUND_EPS: f64 = 0.05;
+
+/// Parameters of `Controller`, distances are given in units and times in seconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Motion {
+    /// Horizontal acceleration while movement keys are held
+    pub accel: f64,
+    /// Part of horizontal velocity that is lost per second while there is no movement intent
+    pub friction: f64,
+    /// Limit of horizontal speed, it's halved while crouching
+    pub max_speed: f64,
+    /// Vertical acceleration downwards
+    pub gravity: f64,
+    /// Vertical speed at the start of jump
+    pub jump_speed: f64,
+    /// Height of camera above the ground
+    pub height: f64,
+    /// Height of camera above the ground while crouching
+    pub crouch_height: f64,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self {
+            accel: 40.0,
+            friction: 8.0,
+            max_speed: 6.0,
+            gravity: 20.0,
+            jump_speed: 7.0,
+            height: 2.0,
+            crouch_height: 1.0,
+        }
+    }
+}
+
+/// What the player wants to do during the frame
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Intent {
+    /// Movement along camera direction within [-1, 1]
+    pub forward: f64,
+    /// Movement to the left of camera direction within [-1, 1]
+    pub left: f64,
+    pub jump: bool,
+    pub crouch: bool,
+}
+
+/// Physics-based camera controller: velocity with acceleration and friction, gravity,
+/// jumping and crouching. Ground is detected with `AsScene::trace` straight down,
+/// horizontal motion is checked with `AsScene::validate_mv`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controller {
+    pub motion: Motion,
+    velocity: Vector,
+    grounded: bool,
+    crouching: bool,
+}
+
+impl Controller {
+    pub fn new(motion: Motion) -> Self {
+        Self {
+            motion,
+            velocity: Vector::new(vec![0.0; 3]),
+            grounded: false,
+            crouching: false,
+        }
+    }
+
+    /// Current velocity in units per second
+    pub fn velocity(&self) -> &Vector {
+        &self.velocity
+    }
+
+    /// Sets velocity in units per second, eg to restore it from save
+    pub fn set_velocity(&mut self, velocity: Vector) {
+        self.velocity = velocity;
+    }
+
+    /// Whether camera stands on the ground
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// Whether camera is crouching
+    pub fn is_crouching(&self) -> bool {
+        self.crouching
+    }
+
+    /// Current height of camera above the ground
+    pub fn height(&self) -> f64 {
+        match self.crouching {
+            true => self.motion.crouch_height,
+            false => self.motion.height,
+        }
+    }
+
+    /// Moves camera according to the intent during `dt` seconds
+    pub fn step(
+        &mut self,
+        cs: &CoordSys,
+        camera: &mut Camera,
+        scene: &dyn AsScene,
+        intent: Intent,
+        dt: f64,
+    ) -> ReRes<()> {
+        self.crouching = intent.crouch;
+        self.accelerate(camera.dir(), intent, dt);
+
+        let ground = self.ground(cs, camera.pos(), scene);
+        self.grounded = self.velocity[2] <= 0.0
+            && matches!(ground, Some(dist) if dist <= self.motion.height + GROUND_EPS);
+        if self.grounded && intent.jump {
+            self.grounded = false;
+            *self.velocity.at_mut(2) = self.motion.jump_speed;
+        }
+
+        let mut mv = Vector::new(vec![self.velocity[0] * dt, self.velocity[1] * dt, 0.0]);
+        scene.validate_mv(cs, camera.pos(), &mut mv);
+        camera.mv(&mv)?;
+
+        let ground = self.ground(cs, camera.pos(), scene);
+        self.grounded = self.grounded
+            && matches!(ground, Some(dist) if dist <= self.motion.height + GROUND_EPS);
+        let dz = match (self.grounded, ground) {
+            (true, Some(dist)) => {
+                *self.velocity.at_mut(2) = 0.0;
+                self.height() - dist
+            }
+            _ => self.fall(cs, camera.pos(), scene, ground, dt),
+        };
+        camera.mv(&Vector::new(vec![0.0, 0.0, dz]))
+    }
+
+    /// Changes horizontal velocity with acceleration towards the intent or with friction
+    fn accelerate(&mut self, dir: (f64, f64), intent: Intent, dt: f64) {
+        let mut wish = (
+            dir.0 * intent.forward - dir.1 * intent.left,
+            dir.1 * intent.forward + dir.0 * intent.left,
+        );
+        let len = wish.0.hypot(wish.1);
+        if len > 1.0 {
+            wish = (wish.0 / len, wish.1 / len);
+        }
+        let keep = match len > 0.0 {
+            true => 1.0,
+            false => (1.0 - self.motion.friction * dt).max(0.0),
+        };
+        let mut vel = (
+            self.velocity[0] * keep + wish.0 * self.motion.accel * dt,
+            self.velocity[1] * keep + wish.1 * self.motion.accel * dt,
+        );
+        let max_speed = match self.crouching {
+            true => self.motion.max_speed / 2.0,
+            false => self.motion.max_speed,
+        };
+        let speed = vel.0.hypot(vel.1);
+        if speed > max_speed {
+            vel = (vel.0 * max_speed / speed, vel.1 * max_speed / speed);
+        }
+        *self.velocity.at_mut(0) = vel.0;
+        *self.velocity.at_mut(1) = vel.1;
+    }
+
+    /// Vertical motion in the air, it stops at the ground and at the ceiling
+    fn fall(
+        &mut self,
+        cs: &CoordSys,
+        pos: &Point,
+        scene: &dyn AsScene,
+        ground: Option<f64>,
+        dt: f64,
+    ) -> f64 {
+        *self.velocity.at_mut(2) -= self.motion.gravity * dt;
+        let dz = self.velocity[2] * dt;
+        if dz < 0.0 {
+            if let Some(dist) = ground {
+                let limit = self.height() - dist;
+                if dz <= limit {
+                    *self.velocity.at_mut(2) = 0.0;
+                    self.grounded = true;
+                    return limit;
+                }
+            }
+        } else if let Some(ceil) = scene.trace(cs, pos, &Vector::new(vec![0.0, 0.0, 1.0])) {
+            let limit = (ceil - GROUND_EPS).max(0.0);
+            if dz >= limit {
+                *self.velocity.at_mut(2) = 0.0;
+                return limit;
+            }
+        }
+        dz
+    }
+
+    /// Distance to the ground straight down
+    fn ground(&self, cs: &CoordSys, pos: &Point, scene: &dyn AsScene) -> Option<f64> {
+        scene.trace(cs, pos, &Vector::new(vec![0.0, 0.0, -1.0]))
+    }
+}
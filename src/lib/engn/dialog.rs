@@ -0,0 +1,232 @@
+//! Non-blocking dialogs drawn over the picture by `Hud`. Toasts disappear on timeout and don't
+//! take input. Confirmations and menus are modal: while one of them is open `Game` routes input
+//! to it, the answer is kept in `Hud` until it's taken with `Hud::answer`
+
+use {
+    crate::engn::*,
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind},
+    std::time::Duration,
+};
+
+/// Name of the pause menu that `Game` opens on `Esc`
+pub const PAUSE: &str = "pause";
+
+/// Result of modal dialog
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Answer {
+    /// Confirmation answered with yes or no
+    Confirmed(bool),
+    /// Menu item with the given index is chosen
+    Chosen(usize),
+    /// Dialog is closed without answer
+    Dismissed,
+}
+
+/// Modal dialog
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dialog {
+    /// Question with yes and no options
+    Confirm { msg: String, yes: bool },
+    /// List of items with the selected one
+    Menu {
+        title: String,
+        items: Vec<String>,
+        selected: usize,
+    },
+}
+
+impl Dialog {
+    /// Handles key press returning answer if dialog is answered,
+    /// menu without items can only be dismissed
+    pub fn input(&mut self, key: &KeyEvent) -> Option<Answer> {
+        match self {
+            Dialog::Confirm { yes, .. } => match key.code {
+                KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+                    *yes = !*yes;
+                    None
+                }
+                KeyCode::Char('y') => Some(Answer::Confirmed(true)),
+                KeyCode::Char('n') => Some(Answer::Confirmed(false)),
+                KeyCode::Enter => Some(Answer::Confirmed(*yes)),
+                KeyCode::Esc => Some(Answer::Confirmed(false)),
+                _ => None,
+            },
+            Dialog::Menu { items, .. } if items.is_empty() => match key.code {
+                KeyCode::Esc => Some(Answer::Dismissed),
+                _ => None,
+            },
+            Dialog::Menu {
+                items, selected, ..
+            } => match key.code {
+                KeyCode::Up => {
+                    *selected = (*selected + items.len() - 1) % items.len();
+                    None
+                }
+                KeyCode::Down => {
+                    *selected = (*selected + 1) % items.len();
+                    None
+                }
+                KeyCode::Enter => Some(Answer::Chosen(*selected)),
+                KeyCode::Esc => Some(Answer::Dismissed),
+                _ => None,
+            },
+        }
+    }
+
+    /// Dialog as bordered panel
+    pub fn panel(&self) -> Panel {
+        let lines = match self {
+            Dialog::Confirm { msg, yes } => {
+                let options = match yes {
+                    true => "[Yes]  No ",
+                    false => " Yes  [No]",
+                };
+                vec![msg.clone(), String::new(), options.to_string()]
+            }
+            Dialog::Menu {
+                title,
+                items,
+                selected,
+            } => {
+                let mut lines = vec![title.clone(), String::new()];
+                for (i, item) in items.iter().enumerate() {
+                    let mark = if i == *selected { '>' } else { ' ' };
+                    lines.push(format!("{} {}", mark, item));
+                }
+                lines
+            }
+        };
+        Panel::new(lines, true)
+    }
+}
+
+/// Dialogs that are currently shown and answers that aren't taken yet.
+/// Toasts expire by backend clock given to `Hud::expire`
+#[derive(Debug, Default)]
+pub(crate) struct Dialogs {
+    pub(crate) toasts: Vec<(String, Duration)>,
+    pub(crate) now: Duration,
+    pub(crate) modals: Vec<(&'static str, Dialog)>,
+    pub(crate) answers: Vec<(&'static str, Answer)>,
+    pub(crate) quitting: bool,
+}
+
+impl Dialogs {
+    /// Advances clock to `now` and removes expired toasts
+    pub(crate) fn expire(&mut self, now: Duration) {
+        self.now = now;
+        self.toasts.retain(|(_, until)| *until > now)
+    }
+
+    /// Draws toasts at the bottom and the top modal dialog in the center
+    pub(crate) fn compose(&self, frame: &mut Frame) {
+        for (i, (msg, _)) in self.toasts.iter().rev().enumerate() {
+            let offset = -(3 * i as isize);
+            let toast = Panel::new(vec![msg.clone()], true);
+            paint(frame, &toast, Anchor::Bottom, (offset, 0));
+        }
+        if let Some((_, dialog)) = self.modals.last() {
+            paint(frame, &dialog.panel(), Anchor::Center, (0, 0));
+        }
+    }
+}
+
+impl Hud {
+    /// Shows message at the bottom for the given time counted from the time
+    /// of backend clock given to the last `Hud::expire`
+    pub fn toast(&mut self, msg: impl Into<String>, timeout: Duration) {
+        let until = self.dialogs.now + timeout;
+        self.dialogs.toasts.push((msg.into(), until))
+    }
+
+    /// Opens modal question with yes and no options, yes is selected by default
+    pub fn confirm(&mut self, name: &'static str, msg: impl Into<String>) {
+        let dialog = Dialog::Confirm {
+            msg: msg.into(),
+            yes: true,
+        };
+        self.open(name, dialog)
+    }
+
+    /// Opens modal menu with the given items, the first one is selected by default
+    pub fn menu(&mut self, name: &'static str, title: impl Into<String>, items: Vec<String>) {
+        let dialog = Dialog::Menu {
+            title: title.into(),
+            items,
+            selected: 0,
+        };
+        self.open(name, dialog)
+    }
+
+    /// Opens modal dialog over the others, dialog with the same name is replaced
+    pub fn open(&mut self, name: &'static str, dialog: Dialog) {
+        self.close(name);
+        self.dialogs.modals.push((name, dialog))
+    }
+
+    /// Closes modal dialog without answer
+    pub fn close(&mut self, name: &'static str) {
+        self.dialogs.modals.retain(|(n, _)| *n != name)
+    }
+
+    /// Whether modal dialog with the given name is open
+    pub fn is_open(&self, name: &'static str) -> bool {
+        self.dialogs.modals.iter().any(|(n, _)| *n == name)
+    }
+
+    /// Whether any modal dialog is open, input goes to it then
+    pub fn is_modal(&self) -> bool {
+        !self.dialogs.modals.is_empty()
+    }
+
+    /// Whether any toast or modal dialog is shown
+    pub fn has_dialogs(&self) -> bool {
+        self.is_modal() || !self.dialogs.toasts.is_empty()
+    }
+
+    /// The time of backend clock when the first toast expires if there are toasts
+    pub fn deadline(&self) -> Option<Duration> {
+        self.dialogs.toasts.iter().map(|(_, until)| *until).min()
+    }
+
+    /// Takes answer of dialog with the given name if it has been answered
+    pub fn answer(&mut self, name: &'static str) -> Option<Answer> {
+        let idx = self.dialogs.answers.iter().position(|(n, _)| *n == name)?;
+        Some(self.dialogs.answers.remove(idx).1)
+    }
+
+    /// Passes event to the top modal dialog, returns `false` if there is no modal dialog.
+    /// Answered dialog is closed
+    pub fn input(&mut self, event: &Event) -> bool {
+        let (name, dialog) = match self.dialogs.modals.last_mut() {
+            Some((name, dialog)) => (*name, dialog),
+            None => return false,
+        };
+        if let Event::Key(key) = event {
+            if key.kind == KeyEventKind::Release {
+                return true;
+            }
+            if let Some(answer) = dialog.input(key) {
+                self.dialogs.modals.pop();
+                self.dialogs.answers.push((name, answer));
+            }
+        }
+        true
+    }
+
+    /// Asks `Game` to stop as soon as all the dialogs are closed
+    pub fn quit(&mut self) {
+        self.dialogs.quitting = true
+    }
+
+    /// Whether `Hud::quit` has been called
+    pub fn is_quitting(&self) -> bool {
+        self.dialogs.quitting
+    }
+
+    /// Removes toasts expired by `now` of backend clock, `Game` calls it each frame
+    /// with `AsBackend::now` so replayed games expire toasts at the same frames
+    pub fn expire(&mut self, now: Duration) {
+        self.dialogs.expire(now)
+    }
+}
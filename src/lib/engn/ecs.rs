@@ -0,0 +1,548 @@
+use {
+    crate::{
+        engn::*,
+        errs::{
+            AsContext,
+            GameErr::{self, *},
+            ReErr::{self, *},
+            ReRes,
+        },
+        math::*,
+    },
+    either::Either,
+    std::{
+        any::{Any, TypeId},
+        cell::{Ref, RefCell, RefMut},
+        collections::HashMap,
+        fmt::{self, Debug},
+    },
+    toml::{Table, Value},
+    uuid::Uuid,
+};
+
+const ENTITIES_KEY: &str = "entities";
+const ID_KEY: &str = "id";
+const PROPS_KEY: &str = "props";
+
+/// Dense storage of components of the same type: components are kept contiguously
+/// and found by entity index via sparse array
+#[derive(Debug)]
+pub struct Storage<T> {
+    dense: Vec<T>,
+    ids: Vec<EntityId>,
+    sparse: Vec<Option<usize>>,
+}
+
+impl<T> Storage<T> {
+    fn new() -> Self {
+        Self {
+            dense: vec![],
+            ids: vec![],
+            sparse: vec![],
+        }
+    }
+
+    fn slot(&self, id: EntityId) -> Option<usize> {
+        let slot = (*self.sparse.get(id.index as usize)?)?;
+        match self.ids[slot] == id {
+            true => Some(slot),
+            false => None,
+        }
+    }
+
+    /// Inserts component of the entity returning the previous one
+    fn insert(&mut self, id: EntityId, component: T) -> Option<T> {
+        let idx = id.index as usize;
+        if self.sparse.len() <= idx {
+            self.sparse.resize(idx + 1, None);
+        }
+        if let Some(slot) = self.sparse[idx] {
+            let old = std::mem::replace(&mut self.dense[slot], component);
+            return match std::mem::replace(&mut self.ids[slot], id) == id {
+                true => Some(old),
+                false => None,
+            };
+        }
+        self.sparse[idx] = Some(self.dense.len());
+        self.dense.push(component);
+        self.ids.push(id);
+        None
+    }
+
+    fn remove(&mut self, id: EntityId) -> Option<T> {
+        let slot = self.slot(id)?;
+        self.sparse[id.index as usize] = None;
+        let component = self.dense.swap_remove(slot);
+        self.ids.swap_remove(slot);
+        if let Some(moved) = self.ids.get(slot) {
+            self.sparse[moved.index as usize] = Some(slot);
+        }
+        Some(component)
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        Some(&self.dense[self.slot(id)?])
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        let slot = self.slot(id)?;
+        Some(&mut self.dense[slot])
+    }
+
+    /// Ids of entities having the component in storage order
+    pub fn ids(&self) -> &[EntityId] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dense.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+        self.ids.iter().copied().zip(self.dense.iter())
+    }
+}
+
+/// Type-erased `Storage` kept in `World`
+trait AsStorage {
+    fn drop_component(&mut self, id: EntityId);
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> AsStorage for Storage<T> {
+    fn drop_component(&mut self, id: EntityId) {
+        self.remove(id);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Tuple of component refs that `World::query` visits, eg `(&Pos, &mut Vel)`.
+/// Component type requested mutably can't be requested again in the same query
+pub trait Query {
+    /// Borrowed storages
+    type Guard<'w>;
+    /// Components of a single entity
+    type Item<'a>;
+
+    /// Borrows storages, `None` if some of them doesn't exist yet
+    fn borrow(world: &World) -> Option<Self::Guard<'_>>;
+
+    /// Ids of entities that may match, they are taken from the smallest storage
+    fn ids(guard: &Self::Guard<'_>) -> Vec<EntityId>;
+
+    fn fetch<'a>(guard: &'a mut Self::Guard<'_>, id: EntityId) -> Option<Self::Item<'a>>;
+}
+
+impl<T: 'static> Query for &T {
+    type Guard<'w> = Ref<'w, Storage<T>>;
+    type Item<'a> = &'a T;
+
+    fn borrow(world: &World) -> Option<Self::Guard<'_>> {
+        world.storage::<T>()
+    }
+
+    fn ids(guard: &Self::Guard<'_>) -> Vec<EntityId> {
+        guard.ids().to_vec()
+    }
+
+    fn fetch<'a>(guard: &'a mut Self::Guard<'_>, id: EntityId) -> Option<Self::Item<'a>> {
+        guard.get(id)
+    }
+}
+
+impl<T: 'static> Query for &mut T {
+    type Guard<'w> = RefMut<'w, Storage<T>>;
+    type Item<'a> = &'a mut T;
+
+    fn borrow(world: &World) -> Option<Self::Guard<'_>> {
+        world.storage_mut::<T>()
+    }
+
+    fn ids(guard: &Self::Guard<'_>) -> Vec<EntityId> {
+        guard.ids().to_vec()
+    }
+
+    fn fetch<'a>(guard: &'a mut Self::Guard<'_>, id: EntityId) -> Option<Self::Item<'a>> {
+        guard.get_mut(id)
+    }
+}
+
+macro_rules! impl_query {
+    ($($q:ident: $i:tt),+) => {
+        impl<$($q: Query),+> Query for ($($q,)+) {
+            type Guard<'w> = ($($q::Guard<'w>,)+);
+            type Item<'a> = ($($q::Item<'a>,)+);
+
+            fn borrow(world: &World) -> Option<Self::Guard<'_>> {
+                Some(($($q::borrow(world)?,)+))
+            }
+
+            fn ids(guard: &Self::Guard<'_>) -> Vec<EntityId> {
+                [$($q::ids(&guard.$i)),+]
+                    .into_iter()
+                    .min_by_key(Vec::len)
+                    .unwrap()
+            }
+
+            fn fetch<'a>(guard: &'a mut Self::Guard<'_>, id: EntityId) -> Option<Self::Item<'a>> {
+                Some(($($q::fetch(&mut guard.$i, id)?,)+))
+            }
+        }
+    };
+}
+
+impl_query!(A: 0);
+impl_query!(A: 0, B: 1);
+impl_query!(A: 0, B: 1, C: 2);
+impl_query!(A: 0, B: 1, C: 2, D: 3);
+
+/// System run by `World::run_systems` each tick with frame time in seconds
+pub type System = Box<dyn FnMut(&mut World, f64) -> ReRes<()>>;
+
+/// Component that makes `AsCollided` material visible when `World` is rendered as scene
+pub struct Render(pub Box<dyn AsCollided>);
+
+impl Debug for Render {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Render").field(&self.0).finish()
+    }
+}
+
+/// Typed entity component system: entities are ids, components of each type are
+/// kept in dense `Storage`s, `World::query` visits entities having all the requested
+/// components and systems are run each tick. `World` is `AsScene` rendering
+/// `Render` components, so existing materials can be registered with `World::register`
+#[derive(Default)]
+pub struct World {
+    ids: IdPool,
+    storages: HashMap<TypeId, RefCell<Box<dyn AsStorage>>>,
+    systems: Vec<(String, System)>,
+    random: Random,
+    counters: Counters,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `Random` given by `Game`, systems draw from its streams
+    pub fn random(&self) -> &Random {
+        &self.random
+    }
+
+    /// New entity without components
+    pub fn spawn(&mut self) -> EntityId {
+        self.ids.generate()
+    }
+
+    /// Removes entity with all its components, returns whether it was alive
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        if !self.ids.release(id) {
+            return false;
+        }
+        for storage in self.storages.values_mut() {
+            storage.get_mut().drop_component(id);
+        }
+        true
+    }
+
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.ids.is_alive(id)
+    }
+
+    /// Ids of all the alive entities
+    pub fn entities(&self) -> Vec<EntityId> {
+        self.ids.ids()
+    }
+
+    /// Pool of entity ids, despawned ones are taken by `Game` each tick, see `AsScene::despawned`
+    pub fn ids(&self) -> &IdPool {
+        &self.ids
+    }
+
+    pub fn ids_mut(&mut self) -> &mut IdPool {
+        &mut self.ids
+    }
+
+    pub fn insert<T: 'static>(&mut self, id: EntityId, component: T) -> ReRes<Option<T>> {
+        if !self.is_alive(id) {
+            return Err(GameErr(NotAliveEntity(id)));
+        }
+        let storage = self
+            .storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| RefCell::new(Box::new(Storage::<T>::new())));
+        let storage = storage.get_mut().as_any_mut().downcast_mut::<Storage<T>>();
+        Ok(storage.unwrap().insert(id, component))
+    }
+
+    /// Removes component of the entity
+    pub fn remove<T: 'static>(&mut self, id: EntityId) -> Option<T> {
+        let storage = self.storages.get_mut(&TypeId::of::<T>())?.get_mut();
+        storage
+            .as_any_mut()
+            .downcast_mut::<Storage<T>>()?
+            .remove(id)
+    }
+
+    pub fn has<T: 'static>(&self, id: EntityId) -> bool {
+        self.get::<T>(id).is_some()
+    }
+
+    /// Component of the entity, it can be borrowed while another component type is borrowed mutably
+    pub fn get<T: 'static>(&self, id: EntityId) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.storage::<T>()?, |storage| storage.get(id)).ok()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, id: EntityId) -> Option<&mut T> {
+        let storage = self.storages.get_mut(&TypeId::of::<T>())?.get_mut();
+        storage
+            .as_any_mut()
+            .downcast_mut::<Storage<T>>()?
+            .get_mut(id)
+    }
+
+    /// Storage of components of the type, `None` if no component of the type was inserted
+    pub fn storage<T: 'static>(&self) -> Option<Ref<'_, Storage<T>>> {
+        let storage = self.storages.get(&TypeId::of::<T>())?.borrow();
+        Ref::filter_map(storage, |storage| storage.as_any().downcast_ref()).ok()
+    }
+
+    /// Mutable storage of components of the type
+    pub fn storage_mut<T: 'static>(&self) -> Option<RefMut<'_, Storage<T>>> {
+        let storage = self.storages.get(&TypeId::of::<T>())?.borrow_mut();
+        RefMut::filter_map(storage, |storage| storage.as_any_mut().downcast_mut()).ok()
+    }
+
+    /// Calls `f` for each entity having all the components of query `Q`, eg
+    /// `world.query::<(&Pos, &mut Vel)>(|id, (pos, vel)| ..)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `Q` requests component type mutably and requests it again, eg `(&mut Pos, &Pos)`,
+    /// or if the storage of a requested type is already borrowed mutably
+    pub fn query<Q: Query>(&self, mut f: impl FnMut(EntityId, Q::Item<'_>)) {
+        let mut guard = match Q::borrow(self) {
+            Some(guard) => guard,
+            None => return,
+        };
+        for id in Q::ids(&guard) {
+            if let Some(item) = Q::fetch(&mut guard, id) {
+                f(id, item);
+            }
+        }
+    }
+
+    /// Number of entities matching query `Q`, it panics like `World::query`
+    pub fn count<Q: Query>(&self) -> usize {
+        let mut count = 0;
+        self.query::<Q>(|_, _| count += 1);
+        count
+    }
+
+    /// Adds system that is run each tick after the previously added ones
+    pub fn add_system(
+        &mut self,
+        name: impl Into<String>,
+        system: impl FnMut(&mut World, f64) -> ReRes<()> + 'static,
+    ) {
+        self.systems.push((name.into(), Box::new(system)));
+    }
+
+    /// Removes system with the given name, returns whether it existed
+    pub fn remove_system(&mut self, name: &str) -> bool {
+        let len = self.systems.len();
+        self.systems.retain(|(n, _)| n != name);
+        len != self.systems.len()
+    }
+
+    /// Runs all the systems in order, `Game` calls it each tick while game isn't paused.
+    /// Systems added by systems are run since the next tick
+    pub fn run_systems(&mut self, dt: f64) -> ReRes<()> {
+        let mut systems = std::mem::take(&mut self.systems);
+        let res = systems.iter_mut().try_for_each(|(name, system)| {
+            system(self, dt).with_context(|| format!("system {:?} failed", name))
+        });
+        systems.append(&mut self.systems);
+        self.systems = systems;
+        res
+    }
+
+    /// Spawns entity with `Render` component made of the material
+    pub fn register(&mut self, material: impl AsCollided + 'static) -> EntityId {
+        let id = self.spawn();
+        self.insert(id, Render(Box::new(material))).unwrap();
+        id
+    }
+
+    /// The nearest `Render` component on the ray with its distance
+    fn nearest(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<(f64, Option<char>)> {
+        let storage = self.storage::<Render>()?;
+        self.counters.count_collisions(storage.len() as u64);
+        storage
+            .iter()
+            .filter_map(|(_, render)| {
+                let dist = render.0.collide(cs, inc, dir)?;
+                Some((dist, render.0.charmap(dist)))
+            })
+            .min_by(|lhs, rhs| lhs.0.total_cmp(&rhs.0))
+    }
+
+    /// Saved props of `Render` entities given by `AsScene::save`
+    fn parse_save(&self, state: &Table) -> ReRes<HashMap<EntityId, Vec<(PropKey, PropVal)>>> {
+        let invalid = |field: String, reason: &str| {
+            GameErr(InvalidSaveValue {
+                field,
+                reason: reason.to_string(),
+            })
+        };
+        let entities = match state.get(ENTITIES_KEY) {
+            Some(Value::Array(entities)) => entities,
+            _ => return Err(invalid(ENTITIES_KEY.to_string(), "expected array")),
+        };
+        let mut saved = HashMap::new();
+        for (i, entity) in entities.iter().enumerate() {
+            let field = |key: &str| format!("{}[{}].{}", ENTITIES_KEY, i, key);
+            let uuid = entity
+                .get(ID_KEY)
+                .and_then(Value::as_str)
+                .and_then(|uuid| uuid.parse::<Uuid>().ok())
+                .ok_or_else(|| invalid(field(ID_KEY), "expected uuid"))?;
+            let id = self
+                .ids
+                .lookup(&uuid)
+                .filter(|id| self.has::<Render>(*id))
+                .ok_or_else(|| invalid(field(ID_KEY), "entity doesn't exist"))?;
+            let props = match entity.get(PROPS_KEY) {
+                None => vec![],
+                Some(Value::Table(props)) => props
+                    .iter()
+                    .map(|(key, value)| {
+                        let prop = value_to_prop(value).ok_or_else(|| {
+                            invalid(field(&format!("{}.{}", PROPS_KEY, key)), "unsupported type")
+                        })?;
+                        Ok((key.clone(), prop))
+                    })
+                    .collect::<ReRes<Vec<_>>>()?,
+                Some(_) => return Err(invalid(field(PROPS_KEY), "expected table")),
+            };
+            saved.insert(id, props);
+        }
+        Ok(saved)
+    }
+}
+
+impl Debug for World {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let systems = self
+            .systems
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        f.debug_struct("World")
+            .field("entities", &self.entities())
+            .field("storages", &self.storages.len())
+            .field("systems", &systems)
+            .finish()
+    }
+}
+
+impl AsScene for World {
+    /// The nearest `Render` component, its char if material has charmap or distance otherwise
+    fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char> {
+        match self.nearest(cs, inc, dir) {
+            Some((_, Some(c))) => Either::Right(c),
+            Some((dist, None)) => Either::Left(dist),
+            None => Either::Left(-1.0),
+        }
+    }
+
+    /// Motion isn't restricted
+    fn validate_mv(&self, _cs: &CoordSys, _pos: &Point, _mv: &mut Vector) {}
+
+    fn trace(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64> {
+        self.nearest(cs, inc, dir).map(|(dist, _)| dist)
+    }
+
+    fn tick(&mut self, dt: f64) -> ReRes<()> {
+        self.run_systems(dt)
+    }
+
+    /// Ids released by `World::despawn`
+    fn despawned(&mut self) -> Vec<EntityId> {
+        self.ids.despawned()
+    }
+
+    fn set_random(&mut self, random: Random) {
+        self.random = random
+    }
+
+    /// Each ray is counted as tests of all the `Render` components
+    fn set_counters(&mut self, counters: Counters) {
+        self.counters = counters
+    }
+
+    /// Serializable properties of `Render` entities with their `Uuid`s
+    fn save(&self) -> ReRes<Table> {
+        let mut entities = vec![];
+        if let Some(storage) = self.storage::<Render>() {
+            for (id, render) in storage.iter() {
+                let Some(uuid) = self.ids.uuid(id) else {
+                    continue;
+                };
+                let mut entity = Table::new();
+                entity.insert(ID_KEY.to_string(), Value::String(uuid.to_string()));
+                let props = props_to_table(render.0.as_ref());
+                entity.insert(PROPS_KEY.to_string(), Value::Table(props));
+                entities.push(Value::Table(entity));
+            }
+        }
+        let mut state = Table::new();
+        state.insert(ENTITIES_KEY.to_string(), Value::Array(entities));
+        Ok(state)
+    }
+
+    fn validate_save(&self, state: &Table) -> ReRes<()> {
+        self.parse_save(state).map(|_| ())
+    }
+
+    /// Replaces serializable properties of `Render` entities with the saved ones,
+    /// entities that are missing in the save are despawned. Save keeps only properties,
+    /// so entities can't be respawned from it and saved entity that no longer exists is an error.
+    /// Nothing is changed if the state is invalid
+    fn restore(&mut self, state: &Table) -> ReRes<()> {
+        let mut saved = self.parse_save(state)?;
+        let ids = match self.storage::<Render>() {
+            Some(storage) => storage.ids().to_vec(),
+            None => vec![],
+        };
+        for id in ids {
+            match saved.remove(&id) {
+                Some(props) => {
+                    let props_mut = self.get_mut::<Render>(id).unwrap().0.props_mut();
+                    props_mut.retain(|_, prop| prop_to_value(prop).is_none());
+                    props_mut.extend(props);
+                }
+                None => {
+                    self.despawn(id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use {
     crate::{
-        engn::*,
+        engn::{scene_file::to_floats, *},
         errs::{
             GameErr::{self, *},
             ReErr::{self, *},
@@ -9,10 +9,14 @@ use {
         grid::*,
         math::*,
     },
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    crossterm::event::{self, Event},
     std::{cmp::Ordering, collections::VecDeque, marker::PhantomData},
+    toml::{Table, Value},
 };
 
+const VELOCITY_KEY: &str = "velocity";
+
+/// Camera movement events, keys are mapped to them via `Bindings`
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum MovementEvent<Scn: AsScene> {
     RotateUp,
@@ -23,100 +27,256 @@ pub enum MovementEvent<Scn: AsScene> {
     MoveBack,
     MoveLeft,
     MoveRight,
+    ToggleMinimap,
+    ToggleStats,
+    Quit,
+    Mouse(MouseInput),
     None(PhantomData<Scn>),
 }
 
 impl<Scn: AsScene> From<Event> for MovementEvent<Scn> {
+    /// Event with the default `Bindings`
     fn from(ev: Event) -> Self {
-        let mut key_code = KeyCode::Backspace;
-        let mut key_modif = KeyModifiers::NONE;
-
-        match ev {
-            Event::Key(KeyEvent {
-                code, modifiers, ..
-            }) => (key_code, key_modif) = (code, modifiers),
-            _ => return Self::None(PhantomData),
-        };
-
-        match (key_code, key_modif) {
-            (KeyCode::Up, KeyModifiers::NONE) => Self::RotateUp,
-            (KeyCode::Down, KeyModifiers::NONE) => Self::RotateDown,
-            (KeyCode::Left, KeyModifiers::NONE) => Self::RotateLeft,
-            (KeyCode::Right, KeyModifiers::NONE) => Self::RotateRight,
-            (KeyCode::Char('w'), KeyModifiers::NONE) => Self::MoveForward,
-            (KeyCode::Char('s'), KeyModifiers::NONE) => Self::MoveBack,
-            (KeyCode::Char('a'), KeyModifiers::NONE) => Self::MoveLeft,
-            (KeyCode::Char('d'), KeyModifiers::NONE) => Self::MoveRight,
-            (KeyCode::Char('c'), KeyModifiers::CONTROL) => std::process::exit(0),
+        Self::bound(ev, &Bindings::default())
+    }
+}
+
+impl<Scn: AsScene> AsEvent<Scn> for MovementEvent<Scn> {
+    fn bound(ev: Event, bindings: &Bindings) -> Self {
+        if let Some(input) = MouseInput::from_event(&ev) {
+            return Self::Mouse(input);
+        }
+        match bindings.on(&ev) {
+            Some("rotate_up") => Self::RotateUp,
+            Some("rotate_down") => Self::RotateDown,
+            Some("rotate_left") => Self::RotateLeft,
+            Some("rotate_right") => Self::RotateRight,
+            Some("move_forward") => Self::MoveForward,
+            Some("move_back") => Self::MoveBack,
+            Some("move_left") => Self::MoveLeft,
+            Some("move_right") => Self::MoveRight,
+            Some("toggle_minimap") => Self::ToggleMinimap,
+            Some("toggle_stats") => Self::ToggleStats,
+            Some("quit") => Self::Quit,
             _ => Self::None(PhantomData),
         }
     }
 }
 
-impl<Scn: AsScene> AsEvent<Scn> for MovementEvent<Scn> {}
+/// Rotation actions as (action, zenith steps, azimuth steps), positive steps are down and right
+const ROTATIONS: [(&str, isize, isize); 4] = [
+    ("rotate_up", -1, 0),
+    ("rotate_down", 1, 0),
+    ("rotate_left", 0, -1),
+    ("rotate_right", 0, 1),
+];
 
+/// Event system moving camera with `speed` in units per second while movement keys are held,
+/// or with `Controller` if it's given. Rotation keys rotate camera on a step when pressed and
+/// with `turn_rate` in steps per second when held longer than key repeat delay.
+/// Mouse rotates camera via `MouseLook`
 pub struct MovementEventSys {
-    step: f64,
-    movement: [usize; 8],
+    speed: f64,
+    controller: Option<Controller>,
+    turn_rate: f64,
+    spin: (f64, f64),
+    look: MouseLook,
+    turn: (isize, isize),
+    toggle_minimap: bool,
+    toggle_stats: bool,
+    quit: bool,
 }
 
 impl MovementEventSys {
-    pub fn new(step: f64) -> Self {
+    pub fn new(speed: f64) -> Self {
         Self {
-            step,
-            movement: [0; 8],
+            speed,
+            controller: None,
+            turn_rate: 8.0,
+            spin: (0.0, 0.0),
+            look: MouseLook::new(1.0),
+            turn: (0, 0),
+            toggle_minimap: false,
+            toggle_stats: false,
+            quit: false,
+        }
+    }
+
+    /// Sets number of rotation steps per console cell of mouse motion, zero disables mouse look
+    pub fn with_sensitivity(mut self, sensitivity: f64) -> Self {
+        self.look.sensitivity = sensitivity;
+        self
+    }
+
+    /// Sets number of rotation steps per second while rotation key is held
+    pub fn with_turn_rate(mut self, turn_rate: f64) -> Self {
+        self.turn_rate = turn_rate;
+        self
+    }
+
+    /// Moves camera with the physics-based controller instead of constant speed
+    pub fn with_controller(mut self, controller: Controller) -> Self {
+        self.controller = Some(controller);
+        self
+    }
+
+    /// Controller if it's given
+    pub fn controller(&self) -> Option<&Controller> {
+        self.controller.as_ref()
+    }
+
+    pub fn controller_mut(&mut self) -> Option<&mut Controller> {
+        self.controller.as_mut()
+    }
+
+    /// Adds rotation steps of held rotation keys to `turn`
+    fn spin(&mut self, input: &InputState) {
+        let bindings = &input.bindings;
+        let dt = input.dt().as_secs_f64();
+        for (action, down, right) in ROTATIONS {
+            if input.is_action_pressed(bindings, action) {
+                self.turn = (self.turn.0 + down, self.turn.1 + right);
+                continue;
+            }
+            match input.action_held_for(bindings, action) {
+                Some(held) if held > input.repeat_delay => {
+                    let spin = self.turn_rate * dt;
+                    self.spin.0 += spin * down as f64;
+                    self.spin.1 += spin * right as f64;
+                }
+                _ => (),
+            }
+        }
+        let steps = (self.spin.0.trunc(), self.spin.1.trunc());
+        self.spin = (self.spin.0 - steps.0, self.spin.1 - steps.1);
+        self.turn = (
+            self.turn.0 + steps.0 as isize,
+            self.turn.1 + steps.1 as isize,
+        );
+    }
+
+    /// Intent of the player according to held keys
+    fn intent(&self, input: &InputState) -> Intent {
+        let bindings = &input.bindings;
+        let held = |action| input.is_action_held(bindings, action) as i8 as f64;
+        Intent {
+            forward: held("move_forward") - held("move_back"),
+            left: held("move_left") - held("move_right"),
+            jump: input.is_action_pressed(bindings, "jump"),
+            crouch: input.is_action_held(bindings, "crouch"),
         }
     }
 }
 
 impl<Scn: AsScene> AsEventSys<MovementEvent<Scn>, Scn> for MovementEventSys {
+    /// Movement and rotation keys are obtained from `InputState`,
+    /// so only the other events are taken into account here
     fn push(&mut self, event: MovementEvent<Scn>) {
         match event {
-            MovementEvent::RotateUp => self.movement[0] += 1,
-            MovementEvent::RotateDown => self.movement[1] += 1,
-            MovementEvent::RotateLeft => self.movement[2] += 1,
-            MovementEvent::RotateRight => self.movement[3] += 1,
-            MovementEvent::MoveForward => self.movement[4] += 1,
-            MovementEvent::MoveBack => self.movement[5] += 1,
-            MovementEvent::MoveLeft => self.movement[6] += 1,
-            MovementEvent::MoveRight => self.movement[7] += 1,
-            MovementEvent::None(_) => (),
+            MovementEvent::ToggleMinimap => self.toggle_minimap = !self.toggle_minimap,
+            MovementEvent::ToggleStats => self.toggle_stats = !self.toggle_stats,
+            MovementEvent::Quit => self.quit = true,
+            MovementEvent::Mouse(MouseInput::Move { pos }) => {
+                let (down, right) = self.look.look(pos);
+                self.turn = (self.turn.0 + down, self.turn.1 + right);
+            }
+            _ => (),
         }
     }
 
-    fn handle_all(&mut self, cs: &CoordSys, camera: &mut Camera, scene: &mut Scn) -> ReRes<()> {
-        for i in 0..5 {
-            self.movement[i] /= 2;
+    fn handle_all(
+        &mut self,
+        cs: &CoordSys,
+        camera: &mut Camera,
+        scene: &mut Scn,
+        hud: &mut Hud,
+        input: &InputState,
+    ) -> ReRes<()> {
+        if self.toggle_minimap {
+            hud.toggle(MINIMAP);
+            self.toggle_minimap = false;
+        }
+        if self.toggle_stats {
+            hud.toggle(STATS);
+            self.toggle_stats = false;
+        }
+        if self.quit {
+            hud.quit();
         }
 
-        match self.movement[0].cmp(&self.movement[1]) {
-            Ordering::Greater => camera.rotate_up(self.movement[0] - self.movement[1]),
-            Ordering::Less => camera.rotate_down(self.movement[1] - self.movement[0]),
+        self.spin(input);
+        match self.turn.0.cmp(&0) {
+            Ordering::Greater => camera.rotate_down(self.turn.0 as usize),
+            Ordering::Less => camera.rotate_up(-self.turn.0 as usize),
             _ => (),
         }
-
-        match self.movement[2].cmp(&self.movement[3]) {
-            Ordering::Greater => camera.rotate_left(self.movement[2] - self.movement[3]),
-            Ordering::Less => camera.rotate_right(self.movement[3] - self.movement[2]),
+        match self.turn.1.cmp(&0) {
+            Ordering::Greater => camera.rotate_right(self.turn.1 as usize),
+            Ordering::Less => camera.rotate_left(-self.turn.1 as usize),
             _ => (),
         }
+        self.turn = (0, 0);
+
+        let intent = self.intent(input);
+        let dt = input.dt().as_secs_f64();
+        if let Some(controller) = &mut self.controller {
+            return controller.step(cs, camera, scene, intent, dt);
+        }
 
         let dir = camera.dir();
-        let step = self.step * (self.movement[4] as f64 - self.movement[5] as f64);
-        let mut mv = Vector::new(vec![dir.0 * step, dir.1 * step, 0.0]);
+        let (forward, left) = (
+            intent.forward * self.speed * dt,
+            intent.left * self.speed * dt,
+        );
+
+        let mut mv = Vector::new(vec![dir.0 * forward, dir.1 * forward, 0.0]);
         scene.validate_mv(cs, camera.pos(), &mut mv);
         camera.mv(&mv)?;
 
-        let step = self.step * (self.movement[6] as f64 - self.movement[7] as f64);
-        let mut mv = Vector::new(vec![-dir.1 * step, dir.0 * step, 0.0]);
+        let mut mv = Vector::new(vec![-dir.1 * left, dir.0 * left, 0.0]);
         scene.validate_mv(cs, camera.pos(), &mut mv);
         camera.mv(&mv)?;
 
-        self.movement = [0; 8];
+        Ok(())
+    }
+
+    /// Velocity of `Controller` if it's used
+    fn save(&self) -> ReRes<Table> {
+        let mut state = Table::new();
+        if let Some(controller) = &self.controller {
+            let velocity = controller.velocity();
+            let velocity = (0..3).map(|i| Value::Float(velocity[i])).collect();
+            state.insert(VELOCITY_KEY.to_string(), Value::Array(velocity));
+        }
+        Ok(state)
+    }
+
+    fn validate_save(&self, state: &Table) -> ReRes<()> {
+        saved_velocity(state).map(|_| ())
+    }
+
+    fn restore(&mut self, state: &Table) -> ReRes<()> {
+        if let (Some(controller), Some(velocity)) = (&mut self.controller, saved_velocity(state)?) {
+            controller.set_velocity(velocity);
+        }
         Ok(())
     }
 }
 
+/// Velocity of `Controller` saved by `MovementEventSys`
+fn saved_velocity(state: &Table) -> ReRes<Option<Vector>> {
+    let Some(velocity) = state.get(VELOCITY_KEY) else {
+        return Ok(None);
+    };
+    match to_floats(velocity) {
+        Some(velocity) if velocity.len() == 3 => Ok(Some(Vector::new(velocity))),
+        _ => Err(GameErr(InvalidSaveValue {
+            field: VELOCITY_KEY.to_string(),
+            reason: "expected array of 3 numbers".to_string(),
+        })),
+    }
+}
+
 /// Simple event system that is just queue of obtaining events and furthermore
 /// it implements `AsEventSys` handling events consequently
 pub struct EventQueue<Evt, Lst>
@@ -128,6 +288,20 @@ where
     pub(crate) events: VecDeque<Evt>,
 }
 
+impl<Evt, Scn> EventQueue<Evt, Scn>
+where
+    Evt: AsEvent<Scn>,
+    Scn: AsScene,
+{
+    /// Empty queue
+    pub fn new() -> Self {
+        Self {
+            phantom: PhantomData,
+            events: VecDeque::new(),
+        }
+    }
+}
+
 impl<Evt, Scn> AsEventSys<Evt, Scn> for EventQueue<Evt, Scn>
 where
     Evt: AsEvent<Scn>,
@@ -137,9 +311,16 @@ where
         self.events.push_back(event);
     }
 
-    fn handle_all(&mut self, _cs: &CoordSys, camera: &mut Camera, entities: &mut Scn) -> ReRes<()> {
+    fn handle_all(
+        &mut self,
+        _cs: &CoordSys,
+        camera: &mut Camera,
+        entities: &mut Scn,
+        hud: &mut Hud,
+        _input: &InputState,
+    ) -> ReRes<()> {
         while let Some(mut event) = self.events.pop_front() {
-            event.handle(camera, entities)?;
+            event.handle(camera, entities, hud)?;
         }
         Ok(())
     }
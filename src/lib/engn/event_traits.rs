@@ -9,6 +9,7 @@ use {
         math::*,
     },
     crossterm::event::Event,
+    toml::Table,
 };
 
 /// Trait for events, requires `From<crossterm::event::Event>`
@@ -16,17 +17,56 @@ pub trait AsEvent<Scn>: From<Event>
 where
     Scn: AsScene,
 {
-    fn handle(&mut self, _camera: &mut Camera, _entities: &mut Scn) -> ReRes<()> {
+    /// Event obtained by `Game` with its current key bindings, defaults to `From<Event>`
+    fn bound(event: Event, _bindings: &Bindings) -> Self {
+        Self::from(event)
+    }
+
+    fn handle(&mut self, _camera: &mut Camera, _entities: &mut Scn, _hud: &mut Hud) -> ReRes<()> {
         Ok(())
     }
 }
 
-/// Trait for event systems, it's single instance is stored in `Game`
+/// Trait for event systems, it's single instance is stored in `Game`.
+/// `handle_all` is called each frame, `input` tells held keys and frame time.
+/// Events of `Triggers` are passed to `trigger` after `handle_all`
 pub trait AsEventSys<Evt, Scn>
 where
     Evt: AsEvent<Scn>,
     Scn: AsScene,
 {
     fn push(&mut self, event: Evt);
-    fn handle_all(&mut self, cs: &CoordSys, camera: &mut Camera, scene: &mut Scn) -> ReRes<()>;
+    fn handle_all(
+        &mut self,
+        cs: &CoordSys,
+        camera: &mut Camera,
+        scene: &mut Scn,
+        hud: &mut Hud,
+        input: &InputState,
+    ) -> ReRes<()>;
+
+    /// Takes event of trigger, by default it's ignored
+    fn trigger(&mut self, _event: TriggerEvent) {}
+
+    /// Takes id of entity released in `IdPool` of `Game` or reported by `AsScene::despawned`,
+    /// by default it's ignored
+    fn despawn(&mut self, _id: EntityId) {}
+
+    /// Takes `Random` of `Game` when the event system is given to it, by default it's ignored
+    fn set_random(&mut self, _random: Random) {}
+
+    /// State of the event system written to `SaveGame`, by default nothing is saved
+    fn save(&self) -> ReRes<Table> {
+        Ok(Table::new())
+    }
+
+    /// Checks state given by `AsEventSys::save` without restoring it, see `AsScene::validate_save`
+    fn validate_save(&self, _state: &Table) -> ReRes<()> {
+        Ok(())
+    }
+
+    /// Restores state given by `AsEventSys::save`, by default it's ignored
+    fn restore(&mut self, _state: &Table) -> ReRes<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,245 @@
+use {
+    crate::{
+        engn::*,
+        errs::{
+            GameErr::{self, *},
+            ReErr::{self, *},
+            ReRes,
+        },
+        math::*,
+    },
+    either::Either,
+    std::{fs, path::Path},
+};
+
+/// Chars ordered from the darkest to the brightest, used to turn chars into gray levels
+const RAMP: &str = " ·.-:+*LIVUOJYKX496PHAGR8BNMW#%&@$";
+
+/// Formats that `Frame` can be saved in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameFormat {
+    /// Plain text, one line per row
+    Text,
+    /// Text with ANSI escapes painting each char with the gray level
+    Ansi,
+    /// Binary PPM image (P6)
+    Ppm,
+    /// Grayscale PNG image
+    Png,
+}
+
+/// In-memory picture made of chars. It's filled by `Canvas` and can be exported
+/// as text, ANSI-escaped text or image without touching the console
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub(crate) size: (usize, usize),
+    pub(crate) cells: Vec<Vec<char>>,
+}
+
+impl Frame {
+    /// Frame of the given size as (rows, cols) filled with spaces
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            size,
+            cells: vec![vec![' '; size.1]; size.0],
+        }
+    }
+
+    /// Renders the current `Camera` view of the scene, doesn't require console
+    pub fn shoot<Scn: AsScene>(
+        camera: &Camera,
+        cs: &CoordSys,
+        scene: &Scn,
+        charcoal: &Charcoal,
+    ) -> Self {
+        let mut frame = Self::new(camera.size);
+        frame.render(camera, cs, scene, charcoal);
+        frame
+    }
+
+    /// Fills frame via colliding scene against all camera rays
+    pub fn render<Scn: AsScene>(
+        &mut self,
+        camera: &Camera,
+        cs: &CoordSys,
+        scene: &Scn,
+        charcoal: &Charcoal,
+    ) {
+        for r in 0..self.size.0 {
+            for c in 0..self.size.1 {
+                let ray = camera.ray(r, c);
+                self.cells[r][c] = match scene.collide(cs, &camera.pos, ray) {
+                    Either::Left(d) => charcoal.ignite(d),
+                    Either::Right(c) => c,
+                };
+            }
+        }
+    }
+
+    /// Size as (rows, cols)
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Char at the given row and col
+    pub fn at(&self, r: usize, c: usize) -> char {
+        self.cells[r][c]
+    }
+
+    /// Mutable ref to char at the given row and col
+    pub fn at_mut(&mut self, r: usize, c: usize) -> &mut char {
+        &mut self.cells[r][c]
+    }
+
+    /// Rows of frame collected into strings
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect()
+    }
+
+    /// Plain text, rows are separated with `\n`
+    pub fn to_text(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// Text where each char is painted with 256-color gray level respectively to its brightness
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+        for (r, row) in self.cells.iter().enumerate() {
+            if r > 0 {
+                ansi.push('\n');
+            }
+            let mut last = None;
+            for ch in row {
+                let gray = gray_level(*ch) as u16 * 23 / 255;
+                if last != Some(gray) {
+                    ansi.push_str(&format!("\x1b[38;5;{}m", 232 + gray));
+                    last = Some(gray);
+                }
+                ansi.push(*ch);
+            }
+            ansi.push_str("\x1b[0m");
+        }
+        ansi
+    }
+
+    /// Gray pixels of image where each char is the cell `scale` wide and `2 * scale` high
+    pub fn to_pixels(&self, scale: usize) -> (usize, usize, Vec<u8>) {
+        let (h, w) = (self.size.0 * 2 * scale, self.size.1 * scale);
+        let mut pixels = Vec::with_capacity(h * w);
+        for row in &self.cells {
+            let line = row
+                .iter()
+                .flat_map(|ch| std::iter::repeat(gray_level(*ch)).take(scale))
+                .collect::<Vec<u8>>();
+            for _ in 0..(2 * scale) {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        (h, w, pixels)
+    }
+
+    /// Binary PPM (P6) image, see `Frame::to_pixels` for the `scale` meaning
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let (h, w, pixels) = self.to_pixels(scale);
+        let mut ppm = format!("P6\n{} {}\n255\n", w, h).into_bytes();
+        for px in pixels {
+            ppm.extend_from_slice(&[px, px, px]);
+        }
+        ppm
+    }
+
+    /// Grayscale 8-bit PNG image, see `Frame::to_pixels` for the `scale` meaning
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        let (h, w, pixels) = self.to_pixels(scale);
+        let mut raw = Vec::with_capacity(h * (w + 1));
+        for line in pixels.chunks(w.max(1)) {
+            raw.push(0);
+            raw.extend_from_slice(line);
+        }
+
+        let mut ihdr = vec![];
+        ihdr.extend_from_slice(&(w as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(h as u32).to_be_bytes());
+        ihdr.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png_chunk(&mut png, b"IHDR", &ihdr);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Writes frame into the file in the given format, images are scaled 4 times
+    pub fn save<P: AsRef<Path>>(&self, path: P, format: FrameFormat) -> ReRes<()> {
+        let content = match format {
+            FrameFormat::Text => self.to_text().into_bytes(),
+            FrameFormat::Ansi => self.to_ansi().into_bytes(),
+            FrameFormat::Ppm => self.to_ppm(4),
+            FrameFormat::Png => self.to_png(4),
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Gray level of char within [0, 255] according to its position in `RAMP`,
+/// chars that are out of `RAMP` are treated as middle gray
+pub fn gray_level(ch: char) -> u8 {
+    let len = RAMP.chars().count() - 1;
+    match RAMP.chars().position(|c| c == ch) {
+        Some(idx) => (idx * 255 / len) as u8,
+        None => 128,
+    }
+}
+
+/// Appends PNG chunk with its length and checksum
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Zlib stream of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    zlib
+}
+
+/// CRC-32 checksum as it's defined in PNG specification
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
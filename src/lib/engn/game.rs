@@ -1,8 +1,9 @@
 use {
     crate::{
         conf::*,
-        engn::*,
+        engn::{material::lock, reload::HotReload, *},
         errs::{
+            AsContext,
             GameErr::{self, *},
             ReErr::{self, *},
             ReRes,
@@ -10,17 +11,27 @@ use {
         grid::*,
         math::*,
     },
-    std::{f64::consts::PI, marker::PhantomData, rc::Rc, time::Duration},
-    uuid::Uuid,
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    std::{
+        f64::consts::PI,
+        marker::PhantomData,
+        path::{Path, PathBuf},
+        rc::Rc,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
 };
 
-/// Struct responsible for storing current CoordSys and EntityList and running related scripts
+/// Struct responsible for storing current CoordSys and EntityList and running related scripts.
+/// Frames are presented and events are obtained through the backend `Bk`.
+/// Frames are rendered `Conf::tick_rate` times per second and on each event
 #[derive(Debug)]
-pub struct Game<Evt, EvtSys, Scn>
+pub struct Game<Evt, EvtSys, Scn, Bk = Console>
 where
     Evt: AsEvent<Scn>,
     Scn: AsScene,
     EvtSys: AsEventSys<Evt, Scn>,
+    Bk: AsBackend,
 {
     phantom: PhantomData<Evt>,
     pub(crate) cs: CoordSys,
@@ -28,16 +39,70 @@ where
     pub(crate) scene: Scn,
     pub(crate) canvas: Canvas<Scn>,
     pub(crate) camera: Camera,
+    pub(crate) hud: Hud,
+    pub(crate) input: InputState,
+    pub(crate) tick: Option<Duration>,
+    pub(crate) physics: Option<PhysicsWorld>,
+    pub(crate) triggers: Triggers,
+    pub(crate) ids: Arc<Mutex<IdPool>>,
+    pub(crate) conf: Conf,
+    pub(crate) random: Random,
+    pub(crate) reload: Option<HotReload<Scn>>,
+    pub(crate) profiler: Profiler,
+    pub(crate) stats_file: Option<PathBuf>,
+    pub(crate) recording: Option<(Recording, PathBuf)>,
+    pub(crate) backend: Bk,
+}
+
+impl<Evt, EvtSys, Scn> Game<Evt, EvtSys, Scn, Console>
+where
+    Evt: AsEvent<Scn>,
+    Scn: AsScene,
+    EvtSys: AsEventSys<Evt, Scn>,
+{
+    /// Constructor for `Game` drawing in console, takes `Conf` and returns `ReRes` if something fails
+    pub fn new(conf: Conf, scene: Scn, es: EvtSys) -> ReRes<Self> {
+        Self::with_backend(conf, scene, es, Console::new())
+    }
 }
 
-impl<Evt, EvtSys, Scn> Game<Evt, EvtSys, Scn>
+impl<Evt, EvtSys, Scn> Game<Evt, EvtSys, Scn, Headless>
 where
     Evt: AsEvent<Scn>,
     Scn: AsScene,
     EvtSys: AsEventSys<Evt, Scn>,
 {
-    /// Constructor for `Game` taking `Conf` and returning `ReRes` if something fails
-    pub fn new(mut conf: Conf, scene: Scn, es: EvtSys) -> ReRes<Self> {
+    /// Constructor for `Game` that doesn't touch console, picture of the given size as
+    /// (rows, cols) is rendered in memory only, see `Headless`
+    pub fn headless(conf: Conf, scene: Scn, es: EvtSys, size: (usize, usize)) -> ReRes<Self> {
+        Self::with_backend(conf, scene, es, Headless::new(size))
+    }
+}
+
+impl<Evt, EvtSys, Scn, Bk> Game<Evt, EvtSys, Scn, Bk>
+where
+    Evt: AsEvent<Scn>,
+    Scn: AsScene,
+    EvtSys: AsEventSys<Evt, Scn>,
+    Bk: AsBackend,
+{
+    /// Constructor for `Game` presenting frames and obtaining events through the given backend
+    pub fn with_backend(
+        conf: Conf,
+        mut scene: Scn,
+        mut es: EvtSys,
+        mut backend: Bk,
+    ) -> ReRes<Self> {
+        conf.validate()?;
+        let mut size = backend.init()?;
+        if size.0 == 0 || size.1 == 0 {
+            backend.teardown()?;
+            return Err(GameErr(EmptyScreen(size)));
+        }
+        if conf.mouse_capture {
+            backend.capture_mouse(true)?;
+        }
+
         set_biform(Matrix::identity(3));
 
         set_exact_mode();
@@ -48,8 +113,6 @@ where
             Basis::new(Matrix::identity(3).to_multicol())?,
         )?;
 
-        let size = console::init()?;
-        let mut size = ((size.0 - 3) as usize, size.1 as usize);
         if size.0 % 2 == 0 {
             size.0 -= 1
         }
@@ -57,21 +120,25 @@ where
             size.1 -= 1
         }
 
-        let hfov = match conf.hfov {
-            Some(val) => val,
-            None => (size.0 as f64) * conf.wfov / (size.1 as f64),
-        };
+        let camera = camera(&conf, size);
+        let canvas = Canvas::new(size, conf.charmap.clone(), conf.draw_dist);
 
-        let camera = Camera::new(
-            conf.initpt,
-            conf.angle_discr,
-            conf.wfov * PI,
-            hfov * PI,
-            size.clone(),
-            conf.draw_dist,
+        let mut input = InputState::new(
+            Duration::from_secs_f64(conf.repeat_delay),
+            Duration::from_secs_f64(conf.repeat_timeout),
         );
-
-        let canvas = Canvas::new(size, conf.charmap, conf.draw_dist);
+        input.bindings = conf.keys.clone();
+        if backend.reports_releases() {
+            input.expect_releases();
+        }
+        let mut hud = Hud::new();
+        hud.expire(backend.now());
+        let tick = tick(&conf);
+        let random = Random::new(conf.seed);
+        scene.set_random(random.clone());
+        es.set_random(random.clone());
+        let profiler = Profiler::new();
+        scene.set_counters(profiler.counters().clone());
 
         Ok(Self {
             phantom: PhantomData,
@@ -80,36 +147,382 @@ where
             scene,
             canvas,
             camera,
+            hud,
+            input,
+            tick,
+            physics: None,
+            triggers: Triggers::new(),
+            ids: Arc::new(Mutex::new(IdPool::new())),
+            conf,
+            random,
+            reload: None,
+            profiler,
+            stats_file: None,
+            recording: None,
+            backend,
         })
     }
 
+    /// Adds physics world that is stepped with frame time on each tick while game isn't paused
+    pub fn with_physics(mut self, physics: PhysicsWorld) -> Self {
+        self.physics = Some(physics);
+        self
+    }
+
+    /// Takes ids of entities from the given pool instead of its own, eg `IdPool::shared`
+    /// to share them with scene built by `IdPool::generate_shared`
+    pub fn with_ids(mut self, ids: Arc<Mutex<IdPool>>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Uses the given `Random` instead of the one seeded from `Conf`, eg to draw from it
+    /// before the game is created, the scene and the event system take it
+    pub fn with_random(mut self, random: Random) -> Self {
+        self.scene.set_random(random.clone());
+        self.es.set_random(random.clone());
+        self.random = random;
+        self
+    }
+
+    /// Watches files of `Conf::files` and applies their changes while the game runs,
+    /// `Conf::overrides` are applied on top of them, see `Game::reload`
+    pub fn with_hot_reload(mut self) -> Self {
+        let reload = self.reload.get_or_insert_with(HotReload::new);
+        for path in &self.conf.files {
+            reload.conf.watch(path);
+        }
+        self
+    }
+
+    /// Watches scene file, `loader` updates the scene when the file changes
+    pub fn with_scene_reload(
+        mut self,
+        path: impl AsRef<Path>,
+        loader: impl FnMut(&mut Scn, &Path) -> ReRes<()> + 'static,
+    ) -> Self {
+        let mut watcher = Watcher::new();
+        watcher.watch(path);
+        let reload = self.reload.get_or_insert_with(HotReload::new);
+        reload.scenes.push((watcher, Box::new(loader), None));
+        self
+    }
+
+    /// Writes statistics of `Profiler` to TOML file when the game exits, see `Profiler::summary`
+    pub fn with_stats_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.stats_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Records polls of backend and seed of `Random` while the game runs
+    /// and writes `Recording` to file on exit
+    pub fn with_recording(mut self, path: impl AsRef<Path>) -> Self {
+        let recording = Recording::new(self.camera.size, Some(self.random.seed()));
+        self.recording = Some((recording, path.as_ref().to_path_buf()));
+        self
+    }
+
     /// Running game: listening to events, handling them with respect to given implementation.
-    /// Never exits if such event isn't provided
+    /// Exits when backend is closed or `Hud::quit` is called and all the dialogs are closed,
+    /// backend is teared down, statistics and recording are written even if error occurs,
+    /// the first error is returned
     pub fn run(&mut self) -> ReRes<()> {
-        loop {
-            self.es.push(Evt::from(console::listen()?));
-            self.es
-                .handle_all(&self.cs, &mut self.camera, &mut self.scene)?;
+        self.profiler = Profiler::new();
+        self.scene.set_counters(self.profiler.counters().clone());
+        let res = self.listen();
+        let teardown = self.backend.teardown();
+        let stats = match &self.stats_file {
+            Some(path) => self.profiler.write(path),
+            None => Ok(()),
+        };
+        let recording = match &self.recording {
+            Some((recording, path)) => recording.write(path),
+            None => Ok(()),
+        };
+        res.and(teardown).and(stats).and(recording)
+    }
+
+    /// Game loop, see `Game::run`. Backend clock is read once per poll
+    /// so that replayed polls give the same frame times
+    fn listen(&mut self) -> ReRes<()> {
+        let now = self.backend.now();
+        if let Some((recording, _)) = &mut self.recording {
+            recording.seed = Some(self.random.seed());
+            recording.start = now.as_nanos() as u64;
+            recording.polls.clear();
+        }
+        self.input.advance(now);
+        self.update()?;
+        while !self.backend.is_closed() {
+            self.hud.expire(self.backend.now());
+            if self.hud.is_quitting() && !self.hud.has_dialogs() {
+                break;
+            }
+            let event = self.backend.poll(self.timeout())?;
+            let (start, now) = (Instant::now(), self.backend.now());
+            if let Some((recording, _)) = &mut self.recording {
+                recording.polls.push(Poll::new(now, event.clone()));
+            }
+            if let Some(event) = event {
+                self.input.feed(&event, now);
+                self.dispatch(event);
+            }
+            if let Some(Answer::Chosen(1)) = self.hud.answer(PAUSE) {
+                self.hud.quit();
+            }
+            self.input.advance(now);
+            self.profiler.record(FramePhase::Input, start.elapsed());
+            let start = Instant::now();
+            if !self.hud.is_modal() {
+                let dt = self.input.dt().as_secs_f64();
+                if let Some(physics) = &mut self.physics {
+                    physics.step(dt)?;
+                }
+                self.scene.tick(dt)?;
+                self.es.handle_all(
+                    &self.cs,
+                    &mut self.camera,
+                    &mut self.scene,
+                    &mut self.hud,
+                    &self.input,
+                )?;
+                let mut despawned = lock(&self.ids).despawned();
+                despawned.extend(self.scene.despawned());
+                for id in despawned {
+                    self.triggers.forget(id);
+                    self.es.despawn(id);
+                }
+                for event in self.triggers.update(&self.camera, &mut self.hud) {
+                    self.es.trigger(event);
+                }
+            }
+            self.profiler.record(FramePhase::Handle, start.elapsed());
+            self.poll_reload()?;
             self.update()?;
         }
+        Ok(())
+    }
+
+    /// Time to wait for event until the next frame, toast expiration or poll of watched files,
+    /// `None` if there is nothing to wait for
+    fn timeout(&self) -> Option<Duration> {
+        let toast = self
+            .hud
+            .deadline()
+            .map(|until| until.saturating_sub(self.backend.now()));
+        let tick = self
+            .tick
+            .map(|tick| (self.input.now() + tick).saturating_sub(self.backend.now()));
+        let reload = self
+            .reload
+            .as_ref()
+            .map(|reload| reload.next.saturating_sub(self.backend.now()));
+        [toast, tick, reload].into_iter().flatten().min()
+    }
+
+    /// Reloads watched files once in `RELOAD_INTERVAL`
+    fn poll_reload(&mut self) -> ReRes<()> {
+        let now = self.backend.now();
+        match &mut self.reload {
+            Some(reload) if now >= reload.next => reload.next = now + RELOAD_INTERVAL,
+            _ => return Ok(()),
+        }
+        self.reload()
+    }
+
+    /// Reads changed watched files and applies them: `Conf` with `Game::reconfigure` and scene
+    /// files with their loaders. Errors of reading are listed in `RELOAD_ERRORS` panel of `Hud`
+    /// until the files are fixed, meanwhile the game keeps the last valid state
+    pub fn reload(&mut self) -> ReRes<()> {
+        let Some(mut reload) = self.reload.take() else {
+            return Ok(());
+        };
+        let res = self.reload_with(&mut reload);
+        self.reload = Some(reload);
+        res
     }
 
-    /// Updates image on canvas and drawing it in console
+    fn reload_with(&mut self, reload: &mut HotReload<Scn>) -> ReRes<()> {
+        if reload.conf.changed() {
+            match self.conf.reread() {
+                Ok(conf) => {
+                    reload.conf_err = None;
+                    self.reconfigure(conf)?;
+                }
+                Err(err) => reload.conf_err = Some(err.report()),
+            }
+        }
+        for (watcher, loader, err) in &mut reload.scenes {
+            if watcher.changed() {
+                let path = watcher.paths().next().unwrap();
+                *err = loader(&mut self.scene, path).err().map(|err| err.report());
+                self.scene.set_random(self.random.clone());
+                self.scene.set_counters(self.profiler.counters().clone());
+            }
+        }
+        let errors = reload.errors();
+        match errors.is_empty() {
+            true => self.hud.remove(RELOAD_ERRORS),
+            false => self
+                .hud
+                .add(RELOAD_ERRORS, Anchor::BottomLeft, Panel::new(errors, true)),
+        }
+        Ok(())
+    }
+
+    /// Validates and applies `Conf` to the running game: `Charcoal` is rebuilt, `Camera` vision is recomputed
+    /// keeping its pose, tick rate, bindings, precision and mouse capture are updated.
+    /// `Conf::initpt`, `Conf::motion` and `Conf::seed` are used by constructors only
+    pub fn reconfigure(&mut self, conf: Conf) -> ReRes<()> {
+        conf.validate()?;
+        if (&conf.charmap, conf.draw_dist) != (&self.conf.charmap, self.conf.draw_dist) {
+            let charcoal = Charcoal::new(conf.charmap.clone(), conf.draw_dist);
+            self.canvas.set_charcoal(charcoal);
+        }
+        let vision = (conf.angle_discr, conf.wfov, conf.hfov);
+        if vision != (self.conf.angle_discr, self.conf.wfov, self.conf.hfov) {
+            let mut camera = camera(&conf, self.camera.size);
+            camera.set_pos(self.camera.pos().clone());
+            camera.set_zenith(self.camera.zenith());
+            camera.set_azimuth(self.camera.azimuth());
+            self.camera = camera;
+        }
+        self.camera.draw_dist = conf.draw_dist;
+        if conf.mouse_capture != self.conf.mouse_capture {
+            self.backend.capture_mouse(conf.mouse_capture)?;
+        }
+        self.input.bindings = conf.keys.clone();
+        set_precision(conf.precision);
+        self.tick = tick(&conf);
+        self.conf = conf;
+        Ok(())
+    }
+
+    /// Routes event to the open modal dialog, opens pause menu on `Esc`
+    /// and passes the other events to the event system
+    fn dispatch(&mut self, event: Event) {
+        if self.hud.input(&event) {
+            return;
+        }
+        if let Event::Key(key) = &event {
+            if key.code == KeyCode::Esc && key.kind == KeyEventKind::Press {
+                let items = vec!["Resume".to_string(), "Quit".to_string()];
+                self.hud.menu(PAUSE, "Pause", items);
+                return;
+            }
+        }
+        self.es.push(Evt::bound(event, &self.input.bindings));
+    }
+
+    /// Updates image on canvas, presents it with backend and finishes frame of `Profiler`
     fn update(&mut self) -> ReRes<()> {
+        self.render()?;
+        let start = Instant::now();
+        self.backend.present(self.canvas.frame())?;
+        self.profiler.record(FramePhase::Draw, start.elapsed());
+        self.profiler.finish(Instant::now());
+        Ok(())
+    }
+
+    /// Updates image on canvas, draws `Hud` over it without presenting and returns rendered `Frame`.
+    /// Shown `StatsOverlay` named `STATS` is refreshed with statistics of the previous frames
+    pub fn render(&mut self) -> ReRes<&Frame> {
+        let start = Instant::now();
         self.canvas.update(&self.camera, &self.cs, &self.scene)?;
-        self.canvas.draw()?;
+        let size = self.canvas.frame().size();
+        self.profiler
+            .counters()
+            .count_rays((size.0 * size.1) as u64);
+        self.profiler.record(FramePhase::Update, start.elapsed());
+        let start = Instant::now();
+        if self.hud.is_visible(STATS) {
+            if let Some(stats) = self.hud.get_mut::<StatsOverlay>(STATS) {
+                stats.refresh(&self.profiler);
+            }
+        }
+        self.hud.update(&self.cs, &self.camera, &self.scene);
+        self.hud.compose(self.canvas.frame_mut());
+        self.profiler.record(FramePhase::Draw, start.elapsed());
+        Ok(self.canvas.frame())
+    }
+
+    /// Shows `BAN` message for a second and stops the game, doesn't block the loop
+    pub fn ban(&mut self) {
+        self.hud.toast("BAN", Duration::from_secs(1));
+        self.hud.quit();
+    }
+
+    /// Snapshot of camera pose and states given by `AsScene::save` and `AsEventSys::save`
+    pub fn snapshot(&self) -> ReRes<SaveGame> {
+        Ok(SaveGame {
+            pos: self.camera.pos().clone(),
+            zenith: self.camera.zenith(),
+            azimuth: self.camera.azimuth(),
+            scene: self.scene.save()?,
+            events: self.es.save()?,
+        })
+    }
+
+    /// Restores snapshot into the running game, camera is turned to the nearest angles
+    /// available with `Conf::angle_discr`. States of the scene and the event system are
+    /// validated before any of them is restored, so invalid save leaves the game as it is
+    pub fn restore(&mut self, save: &SaveGame) -> ReRes<()> {
+        self.scene.validate_save(&save.scene)?;
+        self.es.validate_save(&save.events)?;
+        self.scene.restore(&save.scene)?;
+        self.es.restore(&save.events)?;
+        self.camera.set_pos(save.pos.clone());
+        self.camera.set_zenith(save.zenith);
+        self.camera.set_azimuth(save.azimuth);
         Ok(())
     }
 
-    /// Exits game process with printing useful message
-    pub fn ban(self) {
-        self.canvas.banner("BAN", Duration::from_secs(1)).ok();
-        std::process::exit(0)
+    /// Writes snapshot of the game to file, its format is chosen by extension
+    pub fn save(&self, path: impl AsRef<Path>) -> ReRes<()> {
+        self.snapshot()?.write(path)
     }
 
-    /// `Entity` in current game with appending it's `Uuid` into `IdPool`
+    /// Reads save from file and restores it into the running game
+    pub fn load(&mut self, path: impl AsRef<Path>) -> ReRes<()> {
+        let path = path.as_ref();
+        self.restore(&SaveGame::read(path)?)
+            .with_context(|| format!("failed to restore {}", path.display()))
+    }
+
+    /// `Entity` in current game with id generated by its `IdPool`
     pub fn entity(&mut self) -> Entity {
-        Entity::new(IdPool::get().generate())
+        Entity::new(lock(&self.ids).generate())
+    }
+
+    /// Releases id of entity, on the next tick its trigger and watched object are dropped
+    /// and `AsEventSys::despawn` is called. Returns whether the id was alive
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        lock(&self.ids).release(id)
+    }
+
+    /// Snapshot of pool of entity ids of current game, the pool isn't kept locked
+    pub fn ids(&self) -> IdPool {
+        lock(&self.ids).clone()
+    }
+
+    /// `Conf` of current game, it's updated by `Game::reconfigure`
+    pub fn conf(&self) -> &Conf {
+        &self.conf
+    }
+
+    /// Polls recorded so far if the game is recorded
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref().map(|(recording, _)| recording)
+    }
+
+    /// Source of randomness shared with the scene and the event system
+    pub fn random(&self) -> &Random {
+        &self.random
+    }
+
+    /// Timings and counters of the frames
+    pub fn profiler(&self) -> &Profiler {
+        &self.profiler
     }
 
     /// `Canvas` in current game
@@ -121,4 +534,83 @@ where
     pub fn camera(&self) -> &Camera {
         &self.camera
     }
+
+    /// Mutable `Camera` in current game
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Scene in current game
+    pub fn scene(&self) -> &Scn {
+        &self.scene
+    }
+
+    /// `Hud` drawn over the picture in current game
+    pub fn hud(&self) -> &Hud {
+        &self.hud
+    }
+
+    /// Held keys and frame time in current game
+    pub fn input(&self) -> &InputState {
+        &self.input
+    }
+
+    /// Physics world in current game if it's added with `Game::with_physics`
+    pub fn physics(&self) -> Option<&PhysicsWorld> {
+        self.physics.as_ref()
+    }
+
+    /// Mutable physics world in current game
+    pub fn physics_mut(&mut self) -> Option<&mut PhysicsWorld> {
+        self.physics.as_mut()
+    }
+
+    /// Triggers in current game
+    pub fn triggers(&self) -> &Triggers {
+        &self.triggers
+    }
+
+    /// Mutable triggers in current game
+    pub fn triggers_mut(&mut self) -> &mut Triggers {
+        &mut self.triggers
+    }
+
+    /// Mutable `Hud` in current game
+    pub fn hud_mut(&mut self) -> &mut Hud {
+        &mut self.hud
+    }
+
+    /// Backend in current game
+    pub fn backend(&self) -> &Bk {
+        &self.backend
+    }
+
+    /// Mutable backend in current game
+    pub fn backend_mut(&mut self) -> &mut Bk {
+        &mut self.backend
+    }
+}
+
+/// Camera at `Conf::initpt` with parameters of `Conf` for picture of the given size
+fn camera(conf: &Conf, size: (usize, usize)) -> Camera {
+    let hfov = match conf.hfov {
+        Some(val) => val,
+        None => (size.0 as f64) * conf.wfov / (size.1 as f64),
+    };
+    Camera::new(
+        conf.initpt.clone(),
+        conf.angle_discr,
+        conf.wfov * PI,
+        hfov * PI,
+        size,
+        conf.draw_dist,
+    )
+}
+
+/// Time between frames, `None` if frames are rendered on events only
+fn tick(conf: &Conf) -> Option<Duration> {
+    match conf.tick_rate > 0.0 {
+        true => Some(Duration::from_secs_f64(1.0 / conf.tick_rate)),
+        false => None,
+    }
 }
@@ -0,0 +1,254 @@
+use {
+    crate::{
+        engn::*,
+        errs::{GameErr::*, ReErr::*, ReRes},
+        math::*,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// Handle of node in `SceneGraph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(pub(crate) usize);
+
+/// Node of `SceneGraph` with position and basis relative to its parent
+/// or to the world if it's a root
+#[derive(Debug)]
+pub struct Node {
+    pos: Point,
+    basis: Basis,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    /// Bound game object and its direction relative to the node
+    object: Option<(Rc<RefCell<dyn AsGameObject>>, Matrix)>,
+    /// World transform, it's reset when the node or any of its ancestors changes
+    world: RefCell<Option<(Point, Basis)>>,
+}
+
+impl Node {
+    /// Position relative to parent
+    pub fn pos(&self) -> &Point {
+        &self.pos
+    }
+
+    /// Basis relative to parent
+    pub fn basis(&self) -> &Basis {
+        &self.basis
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    /// Game object bound to the node
+    pub fn object(&self) -> Option<&Rc<RefCell<dyn AsGameObject>>> {
+        self.object.as_ref().map(|(object, _)| object)
+    }
+}
+
+/// Hierarchy of transforms. Node is placed relatively to its parent, so moving or rotating
+/// the parent moves all its descendants. World transforms are computed on demand and cached
+/// until the node or one of its ancestors changes
+#[derive(Debug, Default)]
+pub struct SceneGraph {
+    nodes: Vec<Option<Node>>,
+}
+
+impl SceneGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds node with the given local transform as a child of `parent` or as a root
+    pub fn add(&mut self, parent: Option<NodeId>, pos: Point, basis: Basis) -> ReRes<NodeId> {
+        if let Some(parent) = parent {
+            self.get(parent)?;
+        }
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Node {
+            pos,
+            basis,
+            parent,
+            children: vec![],
+            object: None,
+            world: RefCell::new(None),
+        }));
+        if let Some(parent) = parent {
+            self.get_mut(parent)?.children.push(id);
+        }
+        Ok(id)
+    }
+
+    /// Removes the node with all its descendants, returns ids of removed nodes
+    pub fn remove(&mut self, id: NodeId) -> ReRes<Vec<NodeId>> {
+        if let Some(parent) = self.get(id)?.parent {
+            self.get_mut(parent)?.children.retain(|child| *child != id);
+        }
+        let mut removed = vec![id];
+        let mut i = 0;
+        while i < removed.len() {
+            if let Some(node) = self.nodes[removed[i].0].take() {
+                removed.extend(node.children);
+            }
+            i += 1;
+        }
+        Ok(removed)
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.0)?.as_ref()
+    }
+
+    /// Ids of nodes without parent
+    pub fn roots(&self) -> Vec<NodeId> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| matches!(node, Some(node) if node.parent.is_none()))
+            .map(|(i, _)| NodeId(i))
+            .collect()
+    }
+
+    /// Moves the node under another parent or makes it a root keeping its local transform
+    pub fn set_parent(&mut self, id: NodeId, parent: Option<NodeId>) -> ReRes<()> {
+        self.get(id)?;
+        if let Some(parent) = parent {
+            let mut ancestor = Some(parent);
+            while let Some(node) = ancestor {
+                if node == id {
+                    return Err(GameErr(CyclicNode { node: id, parent }));
+                }
+                ancestor = self.get(node)?.parent;
+            }
+        }
+        if let Some(old) = self.get(id)?.parent {
+            self.get_mut(old)?.children.retain(|child| *child != id);
+        }
+        if let Some(parent) = parent {
+            self.get_mut(parent)?.children.push(id);
+        }
+        self.get_mut(id)?.parent = parent;
+        self.invalidate(id);
+        Ok(())
+    }
+
+    pub fn set_pos(&mut self, id: NodeId, pos: Point) -> ReRes<()> {
+        self.get_mut(id)?.pos = pos;
+        self.invalidate(id);
+        Ok(())
+    }
+
+    pub fn set_basis(&mut self, id: NodeId, basis: Basis) -> ReRes<()> {
+        self.get_mut(id)?.basis = basis;
+        self.invalidate(id);
+        Ok(())
+    }
+
+    /// Moves the node by vector given in coordinates of its parent
+    pub fn translate(&mut self, id: NodeId, vec: &Vector) -> ReRes<()> {
+        self.get_mut(id)?.pos.mv_assign(vec)?;
+        self.invalidate(id);
+        Ok(())
+    }
+
+    /// Rotates the node around its own axes by Tait-Bryan angles
+    pub fn rotate_3d(&mut self, id: NodeId, x: f64, y: f64, z: f64) -> ReRes<()> {
+        let basis = &self.get(id)?.basis.basis;
+        let basis = basis.mul(&Matrix::teit_bryan_rotation(x, y, z));
+        self.set_basis(id, Basis::new(basis.to_multicol())?)
+    }
+
+    /// Rotates the node in plane of parent axes `from` and `to`
+    pub fn planar_rotate(&mut self, id: NodeId, from: usize, to: usize, angle: f64) -> ReRes<()> {
+        let basis = &self.get(id)?.basis.basis;
+        let basis = Matrix::rotation(from, to, angle, 3).mul(basis);
+        self.set_basis(id, Basis::new(basis.to_multicol())?)
+    }
+
+    /// Position of the node in the world
+    pub fn world_pos(&self, id: NodeId) -> ReRes<Point> {
+        Ok(self.world(id)?.0)
+    }
+
+    /// Basis of the node in the world
+    pub fn world_basis(&self, id: NodeId) -> ReRes<Basis> {
+        Ok(self.world(id)?.1)
+    }
+
+    /// Position and basis of the node in the world, computed from the parent ones
+    pub fn world(&self, id: NodeId) -> ReRes<(Point, Basis)> {
+        let node = self.get(id)?;
+        if let Some(world) = node.world.borrow().as_ref() {
+            return Ok(world.clone());
+        }
+        let world = match node.parent {
+            None => (node.pos.clone(), node.basis.clone()),
+            Some(parent) => {
+                let (pos, basis) = self.world(parent)?;
+                let offset = Vector {
+                    coord: node.pos.coord.mul_left(&basis.basis).to_col(),
+                };
+                let basis = Basis::new(basis.basis.mul(&node.basis.basis).to_multicol())?;
+                (pos.mv(&offset)?, basis)
+            }
+        };
+        *node.world.borrow_mut() = Some(world.clone());
+        Ok(world)
+    }
+
+    /// Binds game object to the node, `sync` places it at the node keeping its current
+    /// direction relative to the node
+    pub fn bind(&mut self, id: NodeId, object: Rc<RefCell<dyn AsGameObject>>) -> ReRes<()> {
+        let basis = self.world_basis(id)?;
+        let dir = object.borrow().dir().mul_left(&basis.inv);
+        self.get_mut(id)?.object = Some((object, dir));
+        Ok(())
+    }
+
+    /// Unbinds game object from the node
+    pub fn unbind(&mut self, id: NodeId) -> ReRes<Option<Rc<RefCell<dyn AsGameObject>>>> {
+        Ok(self.get_mut(id)?.object.take().map(|(object, _)| object))
+    }
+
+    /// Moves and rotates bound game objects according to world transforms of their nodes
+    pub fn sync(&self) -> ReRes<()> {
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some((object, dir)) = node.as_ref().and_then(|node| node.object.as_ref()) {
+                let (pos, basis) = self.world(NodeId(i))?;
+                let mut object = object.borrow_mut();
+                *object.pos_mut() = pos;
+                *object.dir_mut() = match dir.is_col() {
+                    true => dir.mul_left(&basis.basis).to_col(),
+                    false => dir.mul_left(&basis.basis).to_multicol(),
+                };
+            }
+        }
+        Ok(())
+    }
+
+    fn get(&self, id: NodeId) -> ReRes<&Node> {
+        self.node(id).ok_or(GameErr(NotExistingNode(id)))
+    }
+
+    fn get_mut(&mut self, id: NodeId) -> ReRes<&mut Node> {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(Option::as_mut)
+            .ok_or(GameErr(NotExistingNode(id)))
+    }
+
+    /// Resets cached world transforms of the node and its descendants
+    fn invalidate(&self, id: NodeId) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            if let Some(node) = self.node(id) {
+                *node.world.borrow_mut() = None;
+                stack.extend(&node.children);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use {
+    crate::{engn::*, errs::ReRes},
+    crossterm::event::Event,
+    std::{collections::VecDeque, time::Duration},
+};
+
+/// In-memory backend: events are given in advance and presented frames are collected.
+/// Its clock is virtual, each poll takes `step` of time unless polls are replayed with their
+/// recorded times. Polls may be idle, ie without event.
+/// It's closed as soon as all the given events and idle polls are obtained
+#[derive(Debug, Clone)]
+pub struct Headless {
+    pub(crate) size: (usize, usize),
+    pub(crate) events: VecDeque<Option<Event>>,
+    pub(crate) times: VecDeque<Duration>,
+    pub(crate) frames: Vec<Frame>,
+    pub(crate) keep_frames: bool,
+    pub(crate) step: Duration,
+    pub(crate) clock: Duration,
+    pub(crate) releases: bool,
+}
+
+impl Headless {
+    /// Backend with drawing area of the given size as (rows, cols) and without events,
+    /// each poll takes 1/30 of second
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            size,
+            events: VecDeque::new(),
+            times: VecDeque::new(),
+            frames: vec![],
+            keep_frames: true,
+            step: Duration::from_secs(1) / 30,
+            clock: Duration::ZERO,
+            releases: false,
+        }
+    }
+
+    /// Backend replaying polls of recording at their times, picture has the recorded size
+    pub fn replay(recording: &Recording) -> Self {
+        let mut backend = Self::new(recording.size);
+        backend.clock = recording.started();
+        for poll in &recording.polls {
+            backend.events.push_back(poll.event.clone());
+            backend.times.push_back(poll.at());
+        }
+        backend
+    }
+
+    /// Sets whether all the presented frames are kept or only the last one, eg for long replays
+    pub fn with_frames(mut self, keep: bool) -> Self {
+        self.keep_frames = keep;
+        self
+    }
+
+    /// Sets time that each poll takes
+    pub fn with_step(mut self, step: Duration) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Sets whether the given events include key releases, see `AsBackend::reports_releases`
+    pub fn with_releases(mut self, releases: bool) -> Self {
+        self.releases = releases;
+        self
+    }
+
+    /// Appends events that will be obtained by `Game` in the given order
+    pub fn with_events(mut self, events: impl IntoIterator<Item = Event>) -> Self {
+        self.events.extend(events.into_iter().map(Some));
+        self
+    }
+
+    /// Appends the given number of polls without events
+    pub fn idle(mut self, polls: usize) -> Self {
+        self.events.extend(std::iter::repeat(None).take(polls));
+        self
+    }
+
+    /// Appends event that will be obtained after all the already given ones
+    pub fn push(&mut self, event: Event) {
+        self.events.push_back(Some(event));
+    }
+
+    /// All the presented frames from the first to the last one
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// The last presented frame
+    pub fn last_frame(&self) -> Option<&Frame> {
+        self.frames.last()
+    }
+}
+
+impl Default for Headless {
+    fn default() -> Self {
+        Self::new((0, 0))
+    }
+}
+
+impl AsBackend for Headless {
+    fn init(&mut self) -> ReRes<(usize, usize)> {
+        Ok(self.size)
+    }
+
+    fn size(&self) -> ReRes<(usize, usize)> {
+        Ok(self.size)
+    }
+
+    fn present(&mut self, frame: &Frame) -> ReRes<()> {
+        if !self.keep_frames {
+            self.frames.clear();
+        }
+        self.frames.push(frame.clone());
+        Ok(())
+    }
+
+    fn poll(&mut self, _timeout: Option<Duration>) -> ReRes<Option<Event>> {
+        self.clock = match self.times.pop_front() {
+            Some(time) => time,
+            None => self.clock + self.step,
+        };
+        Ok(self.events.pop_front().flatten())
+    }
+
+    fn now(&self) -> Duration {
+        self.clock
+    }
+
+    fn reports_releases(&self) -> bool {
+        self.releases
+    }
+
+    fn is_closed(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    fn teardown(&mut self) -> ReRes<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use {
+    crate::engn::*,
+    crossterm::event::{Event, KeyEventKind},
+    std::{collections::HashMap, time::Duration},
+};
+
+/// Keys that are currently held and time passed since the previous handling.
+/// Times are measured with `AsBackend::now`.
+/// Terminals supporting kitty keyboard protocol report key releases, then key is held
+/// until it's released. Otherwise key is held while terminal repeats it: until
+/// `repeat_delay` after the press and `repeat_timeout` after each repetition.
+/// Keys are mapped to actions with `bindings`, default ones unless `Game` sets them from `Conf`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputState {
+    pub repeat_delay: Duration,
+    pub repeat_timeout: Duration,
+    pub bindings: Bindings,
+    held: HashMap<Key, Hold>,
+    releases: bool,
+    now: Duration,
+    dt: Duration,
+}
+
+impl InputState {
+    pub fn new(repeat_delay: Duration, repeat_timeout: Duration) -> Self {
+        Self {
+            repeat_delay,
+            repeat_timeout,
+            bindings: Bindings::default(),
+            held: HashMap::new(),
+            releases: false,
+            now: Duration::ZERO,
+            dt: Duration::ZERO,
+        }
+    }
+
+    /// Updates held keys with the event obtained at `now`
+    pub fn feed(&mut self, event: &Event, now: Duration) {
+        let event = match event {
+            Event::Key(event) => event,
+            Event::FocusLost => return self.release_all(),
+            _ => return,
+        };
+        let key = Key::from(event);
+        if event.kind == KeyEventKind::Release {
+            self.releases = true;
+            self.held.remove(&key);
+            return;
+        }
+        let hold = self.held.entry(key).or_insert(Hold {
+            pressed: now,
+            last: now,
+            repeated: false,
+        });
+        if hold.last < now || event.kind == KeyEventKind::Repeat {
+            hold.repeated = true;
+        }
+        hold.last = now;
+    }
+
+    /// Moves time to `now` and releases keys that aren't repeated anymore
+    pub fn advance(&mut self, now: Duration) {
+        self.dt = now.saturating_sub(self.now);
+        self.now = now;
+        if self.releases {
+            return;
+        }
+        let (delay, timeout) = (self.repeat_delay, self.repeat_timeout);
+        self.held.retain(|_, hold| {
+            let limit = if hold.repeated { timeout } else { delay };
+            now.saturating_sub(hold.last) < limit
+        });
+    }
+
+    /// Time of the last `InputState::advance`
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Time between the last two `InputState::advance` calls, ie frame time
+    pub fn dt(&self) -> Duration {
+        self.dt
+    }
+
+    /// Whether key is held
+    pub fn is_held(&self, key: &Key) -> bool {
+        self.held.contains_key(key)
+    }
+
+    /// How long key is held, `None` if it isn't held
+    pub fn held_for(&self, key: &Key) -> Option<Duration> {
+        let hold = self.held.get(key)?;
+        Some(self.now.saturating_sub(hold.pressed))
+    }
+
+    /// Whether key has been pressed since the previous `InputState::advance`
+    pub fn is_pressed(&self, key: &Key) -> bool {
+        let prev = self.now.saturating_sub(self.dt);
+        match self.held.get(key) {
+            Some(hold) => hold.pressed > prev || hold.pressed == self.now,
+            None => false,
+        }
+    }
+
+    /// Whether any key bound to the action is held
+    pub fn is_action_held(&self, bindings: &Bindings, action: &str) -> bool {
+        self.action_held_for(bindings, action).is_some()
+    }
+
+    /// The longest time any key bound to the action is held
+    pub fn action_held_for(&self, bindings: &Bindings, action: &str) -> Option<Duration> {
+        self.held
+            .keys()
+            .filter(|key| bindings.action(key) == Some(action))
+            .filter_map(|key| self.held_for(key))
+            .max()
+    }
+
+    /// Whether any key bound to the action has been pressed since the previous
+    /// `InputState::advance`
+    pub fn is_action_pressed(&self, bindings: &Bindings, action: &str) -> bool {
+        self.held
+            .keys()
+            .any(|key| bindings.action(key) == Some(action) && self.is_pressed(key))
+    }
+
+    /// All the held keys
+    pub fn held(&self) -> Vec<Key> {
+        self.held.keys().copied().collect()
+    }
+
+    /// Whether terminal has reported any key release, timeouts aren't used then
+    pub fn reports_releases(&self) -> bool {
+        self.releases
+    }
+
+    /// Makes keys held until they are released without timeouts
+    pub fn expect_releases(&mut self) {
+        self.releases = true
+    }
+
+    /// Releases all the keys, eg when console loses focus
+    pub fn release_all(&mut self) {
+        self.held.clear()
+    }
+}
+
+/// State of held key
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Hold {
+    pressed: Duration,
+    last: Duration,
+    repeated: bool,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(500), Duration::from_millis(100))
+    }
+}
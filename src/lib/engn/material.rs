@@ -18,58 +18,182 @@ use {
         collections::hash_map::{Entry, HashMap},
         ops::{Index, IndexMut},
         rc::Rc,
+        sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError},
     },
     uuid::Uuid,
 };
 
-/// Matrix of `Uuid` (standard v4) allocated in heap
+/// Handle of entity. Ids of released entities are reused with the next generation,
+/// so stale ids don't refer to new entities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId {
+    pub(crate) index: u32,
+    pub(crate) generation: u32,
+}
+
+impl EntityId {
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// Slot of `IdPool`, it's occupied when it has `Uuid`
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Slot {
+    generation: u32,
+    uuid: Option<Uuid>,
+}
+
+/// Registry of entity ids. Each alive id has `Uuid` (standard v4) that can be used
+/// to find it, released ids are recycled and reported by `IdPool::despawned`
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct IdPool {
-    pub(crate) ids: Vec<Rc<Uuid>>,
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    uuids: HashMap<Uuid, EntityId>,
+    despawned: Vec<EntityId>,
 }
 
 impl IdPool {
-    /// Empty constructor
-    pub fn get() -> &'static mut Self {
-        static mut IDPOOL: Option<IdPool> = None;
-        unsafe {
-            if let Some(id_pool) = IDPOOL.as_mut() {
-                id_pool
-            } else {
-                IDPOOL = Some(IdPool { ids: vec![] });
-                IDPOOL.as_mut().unwrap()
+    /// Empty pool that isn't shared
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pool shared by the whole process, `Game` and `EntityList` use it only if it's given
+    /// to their `with_ids`
+    pub fn shared() -> Arc<Mutex<Self>> {
+        SHARED.clone()
+    }
+
+    /// New id from the shared pool, the pool is locked only for the call
+    pub fn generate_shared() -> EntityId {
+        lock(&SHARED).generate()
+    }
+
+    /// Releases id into the shared pool, returns whether it was alive.
+    /// The pool is locked only for the call
+    pub fn release_shared(id: EntityId) -> bool {
+        lock(&SHARED).release(id)
+    }
+
+    /// New id reusing the slot of released one if there is any
+    pub fn generate(&mut self) -> EntityId {
+        self.occupy(Uuid::new_v4())
+    }
+
+    /// New id with the given `Uuid`, eg read from file. Returns `None` if the `Uuid` is taken
+    pub fn generate_with(&mut self, uuid: Uuid) -> Option<EntityId> {
+        match self.uuids.contains_key(&uuid) {
+            true => None,
+            false => Some(self.occupy(uuid)),
+        }
+    }
+
+    fn occupy(&mut self, uuid: Uuid) -> EntityId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot::default());
+                (self.slots.len() - 1) as u32
             }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.uuid = Some(uuid);
+        let id = EntityId {
+            index,
+            generation: slot.generation,
+        };
+        self.uuids.insert(uuid, id);
+        id
+    }
+
+    /// Releases id so its slot can be reused, returns whether it was alive
+    pub fn release(&mut self, id: EntityId) -> bool {
+        let Some(uuid) = self.uuid(id) else {
+            return false;
+        };
+        let slot = &mut self.slots[id.index as usize];
+        slot.uuid = None;
+        slot.generation += 1;
+        self.uuids.remove(&uuid);
+        self.free.push(id.index);
+        self.despawned.push(id);
+        true
+    }
+
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.uuid(id).is_some()
+    }
+
+    /// `Uuid` of alive id
+    pub fn uuid(&self, id: EntityId) -> Option<Uuid> {
+        match self.slots.get(id.index as usize) {
+            Some(slot) if slot.generation == id.generation => slot.uuid,
+            _ => None,
         }
     }
 
-    /// Method scoped in `engine` namespace, generates `Uuid` of v4
-    pub fn generate(&mut self) -> Rc<Uuid> {
-        self.ids.push(Rc::new(Uuid::new_v4()));
-        Rc::clone(self.ids.last().unwrap())
+    /// Alive id with the given `Uuid`
+    pub fn lookup(&self, uuid: &Uuid) -> Option<EntityId> {
+        self.uuids.get(uuid).copied()
+    }
+
+    /// All the alive ids
+    pub fn ids(&self) -> Vec<EntityId> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.uuid.is_some())
+            .map(|(index, slot)| EntityId {
+                index: index as u32,
+                generation: slot.generation,
+            })
+            .collect()
     }
 
+    /// Number of alive ids
     pub fn len(&self) -> usize {
-        self.ids.len()
+        self.uuids.len()
     }
-}
 
-impl Index<usize> for IdPool {
-    type Output = Uuid;
+    pub fn is_empty(&self) -> bool {
+        self.uuids.is_empty()
+    }
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.ids[index]
+    /// Takes ids released since the previous call
+    pub fn despawned(&mut self) -> Vec<EntityId> {
+        std::mem::take(&mut self.despawned)
+    }
+
+    /// Releases all the ids without despawn events
+    pub fn reset(&mut self) {
+        *self = Self::default();
     }
 }
 
+/// Locks the pool even if other thread panicked holding the lock, pool stays consistent
+/// since none of its methods panics in the middle of update
+pub(crate) fn lock(ids: &Mutex<IdPool>) -> MutexGuard<'_, IdPool> {
+    ids.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Pool returned by `IdPool::shared`
+static SHARED: LazyLock<Arc<Mutex<IdPool>>> = LazyLock::new(Default::default);
+
 /// Entity struct having `id` and properties map
 #[derive(Debug)]
 pub struct Entity {
-    pub(crate) id: Rc<Uuid>,
+    pub(crate) id: EntityId,
     pub(crate) props: HashMap<PropKey, PropVal>,
 }
 
 impl Entity {
-    pub fn new(id: Rc<Uuid>) -> Self {
+    pub fn new(id: EntityId) -> Self {
         Self {
             id,
             props: HashMap::new(),
@@ -78,8 +202,8 @@ impl Entity {
 }
 
 impl AsEntity for Entity {
-    fn id(&self) -> &Rc<Uuid> {
-        &self.id
+    fn id(&self) -> EntityId {
+        self.id
     }
 
     fn props(&self) -> &HashMap<PropKey, PropVal> {
@@ -91,15 +215,25 @@ impl AsEntity for Entity {
     }
 }
 
-///
+/// List of entities whose ids are released into `IdPool` when they are removed
 #[derive(Debug)]
 pub struct EntityList {
     pub(crate) entities: Vec<Rc<RefCell<dyn AsEntity>>>,
+    pub(crate) ids: Arc<Mutex<IdPool>>,
 }
 
 impl EntityList {
+    /// List of entities with ids from its own `IdPool`
     pub fn new() -> Self {
-        Self { entities: vec![] }
+        Self::with_ids(Arc::new(Mutex::new(IdPool::new())))
+    }
+
+    /// List of entities with ids from the given pool
+    pub fn with_ids(ids: Arc<Mutex<IdPool>>) -> Self {
+        Self {
+            entities: vec![],
+            ids,
+        }
     }
 }
 
@@ -111,17 +245,20 @@ impl AsEntityList for EntityList {
         self.entities.push(item);
     }
 
-    /// Removes entity from the list with the given `Uuid`
-    fn remove(&mut self, id: &Rc<Uuid>) {
-        self.entities
-            .retain(|entity| Rc::ptr_eq(entity.borrow().id(), id));
+    /// Removes entity with the given id from the list and releases the id
+    fn remove(&mut self, id: EntityId) {
+        let len = self.entities.len();
+        self.entities.retain(|entity| entity.borrow().id() != id);
+        if self.entities.len() < len {
+            lock(&self.ids).release(id);
+        }
     }
 
-    fn get(&self, id: &Rc<Uuid>) -> Option<&Self::Item> {
+    fn get(&self, id: EntityId) -> Option<&Self::Item> {
         if let Some(item) = self
             .entities
             .iter()
-            .find(|entity| Rc::ptr_eq(entity.borrow().id(), id))
+            .find(|entity| entity.borrow().id() == id)
         {
             Some(&item)
         } else {
@@ -146,6 +283,9 @@ pub struct HypePlane {
 }
 
 impl HypePlane {
+    /// Kind of the material in scene files
+    pub const KIND: &'static str = "plane";
+
     /// HypePlane constructor takes actual `Entity`, `Point` on plane and normal vector
     pub fn new(
         entity: Entity,
@@ -172,7 +312,7 @@ impl HypePlane {
 }
 
 impl AsEntity for HypePlane {
-    fn id(&self) -> &Rc<Uuid> {
+    fn id(&self) -> EntityId {
         self.entity.id()
     }
 
@@ -205,6 +345,14 @@ impl AsCollided for HypePlane {
             None
         }
     }
+
+    fn describe(&self) -> Option<MaterialDesc> {
+        let mut desc = MaterialDesc::new(Self::KIND);
+        desc.set_vector("pos", &self.initpt);
+        desc.set_vector("normal", &self.normal);
+        desc.set_charcoal(&self.charcoal);
+        Some(desc)
+    }
 }
 
 impl AsGameObject for HypePlane {
@@ -236,6 +384,9 @@ pub struct HypeEllipse {
 }
 
 impl HypeEllipse {
+    /// Kind of the material in scene files
+    pub const KIND: &'static str = "ellipse";
+
     /// Constructs new `HypeEllipse`
     pub fn new(
         entity: Entity,
@@ -266,7 +417,7 @@ impl HypeEllipse {
 }
 
 impl AsEntity for HypeEllipse {
-    fn id(&self) -> &Rc<Uuid> {
+    fn id(&self) -> EntityId {
         self.entity.id()
     }
 
@@ -316,6 +467,15 @@ impl AsCollided for HypeEllipse {
             None
         }
     }
+
+    fn describe(&self) -> Option<MaterialDesc> {
+        let mut desc = MaterialDesc::new(Self::KIND);
+        desc.set_vector("pos", &self.center);
+        desc.set_basis("basis", &self.basis);
+        desc.set_floats("semiaxes", &self.semiaxis);
+        desc.set_charcoal(&self.charcoal);
+        Some(desc)
+    }
 }
 
 impl AsGameObject for HypeEllipse {
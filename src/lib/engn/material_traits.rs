@@ -11,22 +11,24 @@ use {
     },
     either::Either,
     std::{
-        any::{Any, TypeId},
+        any::{type_name, Any, TypeId},
         cell::RefCell,
-        collections::hash_map::{Entry, HashMap},
+        collections::HashMap,
         ops::Index,
         rc::Rc,
     },
-    uuid::Uuid,
+    toml::Table,
 };
 
-pub type PropKey = &'static str;
+/// Owned key of property, so properties can be named at runtime, eg in configuration files
+pub type PropKey = String;
 pub type PropVal = Box<dyn Any>;
 
-/// For material that can be indexed inside the `Game` instance with `Uuid` and can store properties within `HashMap`
+/// For material that can be indexed inside the `Game` instance with `EntityId` and can store properties within `HashMap`.
+/// Typed access to properties is provided by `AsTypedProps`
 pub trait AsEntity {
-    /// UUID of entity
-    fn id(&self) -> &Rc<Uuid>;
+    /// Id of entity given by `IdPool`
+    fn id(&self) -> EntityId;
 
     /// Ref to map of properties
     fn props(&self) -> &HashMap<PropKey, PropVal>;
@@ -35,40 +37,79 @@ pub trait AsEntity {
     fn props_mut(&mut self) -> &mut HashMap<PropKey, PropVal>;
 
     /// Inserts new pair `key`: `val` into `props` field or replaces already existing
-    fn set_prop(&mut self, key: PropKey, val: PropVal) {
-        match self.props_mut().entry(key) {
-            Entry::Occupied(o) => *o.into_mut() = val,
-            Entry::Vacant(v) => {
-                let _ = v.insert(val);
+    fn set_prop(&mut self, key: &str, val: PropVal) {
+        match self.props_mut().get_mut(key) {
+            Some(prop) => *prop = val,
+            None => {
+                let _ = self.props_mut().insert(key.to_string(), val);
             }
         };
     }
 
     /// Returns `ReRes` with ref to requested `Box<dyn Any>` instance or meaningful error if key doesn't exist
-    fn get_prop(&self, key: PropKey) -> ReRes<&PropVal> {
-        if let Some(prop) = self.props().get(key) {
-            Ok(prop)
-        } else {
-            Err(GameErr(NotInitializedProp))
+    fn prop(&self, key: &str) -> ReRes<&PropVal> {
+        match self.props().get(key) {
+            Some(prop) => Ok(prop),
+            None => Err(GameErr(NotInitializedProp(key.to_string()))),
+        }
+    }
+
+    /// Mutable version of `AsEntity::prop`
+    fn prop_mut(&mut self, key: &str) -> ReRes<&mut PropVal> {
+        match self.props_mut().get_mut(key) {
+            Some(prop) => Ok(prop),
+            None => Err(GameErr(NotInitializedProp(key.to_string()))),
         }
     }
 
     /// Performs deleting value by the given `Prop` key
-    fn del_prop(&mut self, key: PropKey) {
+    fn del_prop(&mut self, key: &str) {
         self.props_mut().remove(key);
     }
 }
 
+/// Typed access to properties of any `AsEntity` including `dyn AsEntity`.
+/// Property of other type than requested gives `GameErr::InvalidPropType`
+pub trait AsTypedProps: AsEntity {
+    /// Ref to property downcasted to `T`
+    fn get_prop<T: Any>(&self, key: &str) -> ReRes<&T> {
+        self.prop(key)?
+            .downcast_ref()
+            .ok_or_else(|| invalid_prop_type::<T>(key))
+    }
+
+    /// Mutable ref to property downcasted to `T`
+    fn get_prop_mut<T: Any>(&mut self, key: &str) -> ReRes<&mut T> {
+        self.prop_mut(key)?
+            .downcast_mut()
+            .ok_or_else(|| invalid_prop_type::<T>(key))
+    }
+
+    /// Updates property of type `T` in place returning result of `f`
+    fn update_prop<T: Any, R>(&mut self, key: &str, f: impl FnOnce(&mut T) -> R) -> ReRes<R> {
+        Ok(f(self.get_prop_mut(key)?))
+    }
+}
+
+impl<E: AsEntity + ?Sized> AsTypedProps for E {}
+
+fn invalid_prop_type<T: Any>(key: &str) -> ReErr {
+    GameErr(InvalidPropType {
+        key: key.to_string(),
+        expected: type_name::<T>(),
+    })
+}
+
 impl std::fmt::Debug for dyn AsEntity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "UUID {:?}", self.id())
+        write!(f, "Id {:?}", self.id())
     }
 }
 
-impl Index<PropKey> for dyn AsEntity {
+impl Index<&str> for dyn AsEntity {
     type Output = PropVal;
 
-    fn index(&self, key: PropKey) -> &Self::Output {
+    fn index(&self, key: &str) -> &Self::Output {
         &self.props()[key]
     }
 }
@@ -78,11 +119,17 @@ pub trait AsCollided: AsEntity {
     fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64>;
 
     fn charmap(&self, dist: f64) -> Option<char>;
+
+    /// Description of the material written into scene files, materials without it can't be saved.
+    /// Id and properties are added by `SceneFile`
+    fn describe(&self) -> Option<MaterialDesc> {
+        None
+    }
 }
 
 impl std::fmt::Debug for dyn AsCollided {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "UUID {:?}", self.id())
+        write!(f, "Id {:?}", self.id())
     }
 }
 
@@ -136,7 +183,7 @@ impl std::fmt::Debug for dyn AsGameObject {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "UUID {:?}\n Position {:?}\n Direction {:?}",
+            "Id {:?}\n Position {:?}\n Direction {:?}",
             self.id(),
             self.pos(),
             self.dir()
@@ -152,10 +199,10 @@ pub trait AsEntityList {
     fn append(&mut self, item: Self::Item);
 
     /// Removes item with given id from the current list
-    fn remove(&mut self, id: &Rc<Uuid>);
+    fn remove(&mut self, id: EntityId);
 
     /// Returns ref to `Self::Item` if requested material exists
-    fn get(&self, id: &Rc<Uuid>) -> Option<&Self::Item>;
+    fn get(&self, id: EntityId) -> Option<&Self::Item>;
 
     /// Permorms closure to some subset of all the entities
     fn exec(&self, f: fn(&Self::Item));
@@ -166,4 +213,54 @@ pub trait AsScene {
     fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char>;
 
     fn validate_mv(&self, cs: &CoordSys, pos: &Point, mv: &mut Vector);
+
+    /// Coefficient of `dir` resizing to the nearest collision if it exists.
+    /// By default it's taken from `collide` if it returns distance
+    fn trace(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64> {
+        match self.collide(cs, inc, dir) {
+            Either::Left(dist) => validate_collision(dist),
+            Either::Right(_) => None,
+        }
+    }
+
+    /// The nearest collision with normal of the surface, it's used by `Collider` for sliding.
+    /// By default normal is estimated with `estimate_hit` from `trace` of nearby rays,
+    /// scenes that know their surfaces can give exact normals
+    fn hit(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<Hit> {
+        estimate_hit(self, cs, inc, dir)
+    }
+
+    /// Advances scene on `dt` seconds, `Game` calls it each tick while game isn't paused
+    fn tick(&mut self, _dt: f64) -> ReRes<()> {
+        Ok(())
+    }
+
+    /// Takes ids of entities released by the scene since the previous call, eg from its own
+    /// `IdPool`. `Game` handles them each tick as its despawned ids, by default there are none
+    fn despawned(&mut self) -> Vec<EntityId> {
+        vec![]
+    }
+
+    /// Takes `Random` of `Game` when the scene is given to it or reloaded, by default it's ignored
+    fn set_random(&mut self, _random: Random) {}
+
+    /// Takes `Counters` of `Profiler` of `Game` when the scene is given to it, reloaded or
+    /// the game is run. Scene counts its collision tests with them, by default it's ignored
+    fn set_counters(&mut self, _counters: Counters) {}
+
+    /// State of the scene written to `SaveGame`, by default nothing is saved
+    fn save(&self) -> ReRes<Table> {
+        Ok(Table::new())
+    }
+
+    /// Checks state given by `AsScene::save` without restoring it, `Game::restore` checks all
+    /// the states before any of them is restored. By default any state is valid
+    fn validate_save(&self, _state: &Table) -> ReRes<()> {
+        Ok(())
+    }
+
+    /// Restores state given by `AsScene::save` into the running scene, by default it's ignored
+    fn restore(&mut self, _state: &Table) -> ReRes<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,128 @@
+use {
+    crate::{engn::*, math::*},
+    std::f64::consts::{FRAC_PI_4, PI},
+};
+
+/// Name that `Minimap` is expected to have in `Hud`, eg `MovementEventSys` toggles it
+pub const MINIMAP: &str = "minimap";
+
+/// Chars showing camera facing, starting from +x axis counterclockwise with step of `PI / 4`
+const FACING: [char; 8] = ['>', '/', '^', '\\', '<', '/', 'v', '\\'];
+
+/// Top-down map of the scene around the camera. Columns go along +x axis and rows go along -y
+/// axis. Each cell is `scale` wide and `2 * scale` high since console chars are twice higher
+/// than wide. Scene is sampled with horizontal rays from the camera to each cell center
+/// via `AsScene::trace`: cells where rays hit are walls, cells reached by rays are floor,
+/// cells hidden behind walls stay unknown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Minimap {
+    pub(crate) size: (usize, usize),
+    pub(crate) scale: f64,
+    pub(crate) cells: Vec<Vec<char>>,
+    pub wall: char,
+    pub floor: char,
+    pub unknown: char,
+    pub border: bool,
+}
+
+impl Minimap {
+    /// Map of `size` as (rows, cols) without border, each cell is `scale` wide
+    pub fn new(size: (usize, usize), scale: f64) -> Self {
+        Self {
+            size,
+            scale,
+            cells: vec![vec![' '; size.1]; size.0],
+            wall: '#',
+            floor: '.',
+            unknown: ' ',
+            border: true,
+        }
+    }
+
+    /// Char at the given position of map without border
+    pub fn at(&self, r: usize, c: usize) -> char {
+        self.cells[r][c]
+    }
+
+    /// Offset of the cell center from the camera in the horizontal plane
+    fn offset(&self, r: usize, c: usize) -> (f64, f64) {
+        let dx = (c as f64 - (self.size.1 / 2) as f64) * self.scale;
+        let dy = ((self.size.0 / 2) as f64 - r as f64) * 2.0 * self.scale;
+        (dx, dy)
+    }
+
+    /// Cell containing the point given with its offset from the camera
+    fn cell(&self, dx: f64, dy: f64) -> Option<(usize, usize)> {
+        let c = (dx / self.scale).round() + (self.size.1 / 2) as f64;
+        let r = (self.size.0 / 2) as f64 - (dy / 2.0 / self.scale).round();
+        if r < 0.0 || c < 0.0 || r >= self.size.0 as f64 || c >= self.size.1 as f64 {
+            None
+        } else {
+            Some((r as usize, c as usize))
+        }
+    }
+
+    /// Surveys the scene around the camera
+    pub fn survey(&mut self, cs: &CoordSys, camera: &Camera, scene: &dyn AsScene) {
+        let mut cells = vec![vec![self.unknown; self.size.1]; self.size.0];
+        for r in 0..self.size.0 {
+            for c in 0..self.size.1 {
+                let (dx, dy) = self.offset(r, c);
+                if dx == 0.0 && dy == 0.0 {
+                    continue;
+                }
+                let dir = Vector::new(vec![dx, dy, 0.0]);
+                match scene.trace(cs, camera.pos(), &dir) {
+                    Some(t) if t <= 1.0 => {
+                        if let Some((hr, hc)) = self.cell(dx * t, dy * t) {
+                            cells[hr][hc] = self.wall
+                        }
+                    }
+                    _ => {
+                        if cells[r][c] != self.wall {
+                            cells[r][c] = self.floor
+                        }
+                    }
+                }
+            }
+        }
+        let dir = camera.dir();
+        let angle = dir.1.atan2(dir.0).rem_euclid(2.0 * PI);
+        let facing = (angle / FRAC_PI_4).round() as usize % FACING.len();
+        // map of zero size has no center
+        let center = cells.get_mut(self.size.0 / 2);
+        if let Some(center) = center.and_then(|row| row.get_mut(self.size.1 / 2)) {
+            *center = FACING[facing];
+        }
+        self.cells = cells;
+    }
+}
+
+impl AsWidget for Minimap {
+    fn size(&self) -> (usize, usize) {
+        match self.border {
+            true => (self.size.0 + 2, self.size.1 + 2),
+            false => self.size,
+        }
+    }
+
+    fn cell(&self, r: usize, c: usize) -> Option<char> {
+        if !self.border {
+            return Some(self.cells[r][c]);
+        }
+        let edge = (
+            r == 0 || r == self.size.0 + 1,
+            c == 0 || c == self.size.1 + 1,
+        );
+        match edge {
+            (true, true) => Some('+'),
+            (true, false) => Some('-'),
+            (false, true) => Some('|'),
+            (false, false) => Some(self.cells[r - 1][c - 1]),
+        }
+    }
+
+    fn update(&mut self, cs: &CoordSys, camera: &Camera, scene: &dyn AsScene) {
+        self.survey(cs, camera, scene)
+    }
+}
@@ -1,37 +1,109 @@
 //! `rustyengine` core!
 //! Has the following features:
-//! 1. Console drawing with ASCI characters that can be specified
+//! 1. Console drawing with ASCI characters that can be specified, drawing and listening to events
+//! are performed through pluggable backends: `Console` or in-memory `Headless`
 //! 2. Traits for materials that can be treaten as game entites, eg planes, ellipses, empty entities,
 //! that for example can keeps game state variables. Define yourself entities by implemeting provided traits.
-//! 3. Traits for materials stores that can be processed during event handling
-//! 4. Traits for events and event sustems as well as simple event queue
-//! 5. Game object defined with the given implementation of provided traits and parameters from
-//! `Conf` that stands for configuration read from `.toml` file
+//! Entities are identified by generational `EntityId`s from thread-safe `IdPool` that recycles released ids
+//! Scenes of materials are read from and written to TOML or JSON files with `SceneFile`,
+//! custom materials are loaded by kind registered in `Materials`
+//! 3. Traits for materials stores that can be processed during event handling, as well as
+//! typed entity component system `World` with dense component storages, queries and systems run
+//! each tick, it renders registered materials as scene
+//! 4. Traits for events and event sustems as well as simple event queue,
+//! keys are mapped to actions with `Bindings` that can be configured in `Conf`,
+//! mouse motion is turned into camera rotation with `MouseLook`, held keys are tracked
+//! with `InputState` so movement depends on frame time rather than on key repeat rate.
+//! `Controller` moves camera with velocity, gravity, jumping and crouching,
+//! `Collider` sweeps spheres and capsules through the scene sliding along walls and stepping up ledges.
+//! Optional `PhysicsWorld` moves rigid bodies with gravity and resolves collisions of spheres,
+//! boxes and planes with impulses each tick. `Triggers` emit enter, stay and exit events
+//! when camera or game objects get into box or sphere volumes. `SceneGraph` places nodes
+//! relatively to their parents so moving or rotating a parent carries its children along.
+//! `SaveGame` snapshots camera pose, scene and event system state into versioned file
+//! that is restored into running `Game`, `Recording` keeps timestamped events of the session
+//! that are played back with `Headless::replay`
+//! 5. Headless rendering of the scene into `Frame` that can be exported as text or image
+//! 6. `Hud` with widgets like labels, status bars, panels and crosshair drawn over the picture,
+//! as well as non-blocking toasts, confirmations and menus including pause menu.
+//! `Profiler` times phases of each frame and counts rays and collision tests,
+//! `StatsOverlay` shows them with graphs of FPS and frame time
+//! 7. Game object defined with the given implementation of provided traits and parameters from
+//! `Conf` that stands for configuration read from `.toml` file. `Conf` and scene files are
+//! watched by polling with `Watcher` and applied while the game runs. `Random` seeded from
+//! `Conf` gives the game, its scene and event system named streams of random numbers
 
+pub mod backend_traits;
+pub mod binding;
 pub mod camera;
 pub mod canvas;
 pub mod charcoal;
+pub mod collision;
 pub mod console;
+pub mod controller;
+pub mod dialog;
+pub mod ecs;
 pub mod event;
 pub mod event_traits;
+pub mod frame;
 pub mod game;
+pub mod graph;
+pub mod headless;
+pub mod input;
 pub mod material;
 pub mod material_traits;
+pub mod minimap;
+pub mod mouse;
+pub mod overlay;
+pub mod physics;
+pub mod profiler;
+pub mod random;
+pub mod record;
+pub mod reload;
+pub mod save;
+pub mod scene_file;
+pub mod trigger;
+mod vec3;
 
 #[cfg(test)]
 mod test;
 
 // re-exports in scope of namespace `engn`
 pub use {
+    backend_traits::AsBackend,
+    binding::{Bindings, Key},
     camera::Camera,
     canvas::Canvas,
     charcoal::Charcoal,
+    collision::{estimate_hit, Collider, Hit, Shape},
+    console::Console,
+    controller::{Controller, Intent, Motion},
+    dialog::{Answer, Dialog, PAUSE},
+    ecs::{Query, Render, Storage, System, World},
     event::{EventQueue, MovementEvent, MovementEventSys},
     event_traits::{AsEvent, AsEventSys},
+    frame::{gray_level, Frame, FrameFormat},
     game::Game,
-    material::{Entity, EntityList, HypeEllipse, HypePlane, IdPool},
+    graph::{Node, NodeId, SceneGraph},
+    headless::Headless,
+    input::InputState,
+    material::{Entity, EntityId, EntityList, HypeEllipse, HypePlane, IdPool},
     material_traits::{
-        validate_collision, AsCollided, AsEntity, AsEntityList, AsGameObject, AsScene, PropKey,
-        PropVal,
+        validate_collision, AsCollided, AsEntity, AsEntityList, AsGameObject, AsScene,
+        AsTypedProps, PropKey, PropVal,
     },
+    minimap::{Minimap, MINIMAP},
+    mouse::{MouseInput, MouseLook},
+    overlay::{paint, Anchor, AsWidget, Bar, Crosshair, Hud, Label, Panel},
+    physics::{BodyId, Contact, PhysicsWorld, RigidBody, Volume, MAX_SUBSTEPS},
+    profiler::{Counters, FramePhase, FrameStats, Profiler, StatsOverlay, HISTORY, STATS},
+    random::{Random, Stream, MAIN_STREAM},
+    record::{Poll, Recording, RECORDING_VERSION},
+    reload::{SceneLoader, Watcher, RELOAD_ERRORS, RELOAD_INTERVAL},
+    save::{SaveGame, SAVE_VERSION},
+    scene_file::{
+        load_scene, prop_to_value, props_to_table, save_scene, value_to_prop, MaterialDesc,
+        MaterialLoader, Materials, SceneFile, SceneFormat,
+    },
+    trigger::{Occupant, Phase, Trigger, TriggerCallback, TriggerEvent, Triggers},
 };
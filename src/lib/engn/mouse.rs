@@ -0,0 +1,95 @@
+use crossterm::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+
+/// Mouse input in terms convenient for event handlers, positions are given as (row, col)
+/// of the console cell under the pointer
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum MouseInput {
+    /// Pointer moved with or without pressed button
+    Move { pos: (u16, u16) },
+    /// Button pressed
+    Click {
+        button: MouseButton,
+        pos: (u16, u16),
+    },
+    /// Button released
+    Release {
+        button: MouseButton,
+        pos: (u16, u16),
+    },
+    /// Wheel scrolled on the given number of lines, positive upwards
+    Scroll { lines: i8, pos: (u16, u16) },
+}
+
+impl MouseInput {
+    /// Mouse input from the console event, other events give `None`
+    pub fn from_event(event: &Event) -> Option<Self> {
+        match event {
+            Event::Mouse(mouse) => Some(Self::from(mouse)),
+            _ => None,
+        }
+    }
+
+    /// Position of the pointer
+    pub fn pos(&self) -> (u16, u16) {
+        match self {
+            Self::Move { pos }
+            | Self::Click { pos, .. }
+            | Self::Release { pos, .. }
+            | Self::Scroll { pos, .. } => *pos,
+        }
+    }
+}
+
+impl From<&MouseEvent> for MouseInput {
+    fn from(event: &MouseEvent) -> Self {
+        let pos = (event.row, event.column);
+        match event.kind {
+            MouseEventKind::Moved | MouseEventKind::Drag(_) => Self::Move { pos },
+            MouseEventKind::Down(button) => Self::Click { button, pos },
+            MouseEventKind::Up(button) => Self::Release { button, pos },
+            MouseEventKind::ScrollUp => Self::Scroll { lines: 1, pos },
+            MouseEventKind::ScrollDown => Self::Scroll { lines: -1, pos },
+        }
+    }
+}
+
+/// Turns pointer positions into camera rotation steps. Console reports only absolute
+/// positions, so motion is measured relatively to the previous position.
+/// `sensitivity` is the number of rotation steps per cell, rows are counted twice
+/// since console chars are twice higher than wide. Fractions of steps are accumulated
+#[derive(Debug, Clone, PartialEq)]
+pub struct MouseLook {
+    pub sensitivity: f64,
+    last: Option<(u16, u16)>,
+    acc: (f64, f64),
+}
+
+impl MouseLook {
+    pub fn new(sensitivity: f64) -> Self {
+        Self {
+            sensitivity,
+            last: None,
+            acc: (0.0, 0.0),
+        }
+    }
+
+    /// Rotation steps as (down, right) for the pointer moved to `pos` given as (row, col).
+    /// The first position only is remembered
+    pub fn look(&mut self, pos: (u16, u16)) -> (isize, isize) {
+        let last = match self.last.replace(pos) {
+            Some(last) => last,
+            None => return (0, 0),
+        };
+        self.acc.0 += (pos.0 as f64 - last.0 as f64) * 2.0 * self.sensitivity;
+        self.acc.1 += (pos.1 as f64 - last.1 as f64) * self.sensitivity;
+        let steps = (self.acc.0.trunc(), self.acc.1.trunc());
+        self.acc = (self.acc.0 - steps.0, self.acc.1 - steps.1);
+        (steps.0 as isize, steps.1 as isize)
+    }
+
+    /// Forgets the previous position, eg when pointer leaves console
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.acc = (0.0, 0.0);
+    }
+}
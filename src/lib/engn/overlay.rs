@@ -0,0 +1,368 @@
+use {
+    crate::{
+        engn::{dialog::Dialogs, *},
+        math::*,
+    },
+    std::any::Any,
+};
+
+/// Point of the screen that widget is attached to, widget is placed inside the screen
+/// so that its corresponding point coincides with the anchor
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Position of top left corner of widget of `size` on the screen of `area` size
+    pub fn origin(&self, area: (usize, usize), size: (usize, usize)) -> (isize, isize) {
+        let free = (
+            area.0 as isize - size.0 as isize,
+            area.1 as isize - size.1 as isize,
+        );
+        let (r, c) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (0, 1),
+            Anchor::TopRight => (0, 2),
+            Anchor::Left => (1, 0),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (1, 2),
+            Anchor::BottomLeft => (2, 0),
+            Anchor::Bottom => (2, 1),
+            Anchor::BottomRight => (2, 2),
+        };
+        (free.0 * r / 2, free.1 * c / 2)
+    }
+}
+
+/// Access to widget as `Any`, implemented for every `'static` type, so `Overlay` can downcast
+/// widgets without trait upcasting
+pub trait AsAnyWidget {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAnyWidget for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Trait for things that can be drawn over the picture, sizes are given as (rows, cols)
+pub trait AsWidget: AsAnyWidget + 'static {
+    /// Size of rectangle that widget occupies
+    fn size(&self) -> (usize, usize);
+
+    /// Char at the given position inside widget rectangle, `None` leaves the picture as is
+    fn cell(&self, r: usize, c: usize) -> Option<char>;
+
+    /// Called each frame before composing, widget can look at the scene here
+    fn update(&mut self, _cs: &CoordSys, _camera: &Camera, _scene: &dyn AsScene) {}
+}
+
+/// Line of text, spaces are drawn as well
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub text: String,
+}
+
+impl Label {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+
+    /// Replaces the text
+    pub fn set(&mut self, text: impl Into<String>) {
+        self.text = text.into()
+    }
+}
+
+impl AsWidget for Label {
+    fn size(&self) -> (usize, usize) {
+        (1, self.text.chars().count())
+    }
+
+    fn cell(&self, _r: usize, c: usize) -> Option<char> {
+        self.text.chars().nth(c)
+    }
+}
+
+/// Status bar like `HP [#####     ]` filled proportionally to `value` out of `max`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    pub title: String,
+    pub value: f64,
+    pub max: f64,
+    pub width: usize,
+    pub fill: char,
+}
+
+impl Bar {
+    /// Bar with `width` chars between brackets
+    pub fn new(title: impl Into<String>, value: f64, max: f64, width: usize) -> Self {
+        Self {
+            title: title.into(),
+            value,
+            max,
+            width,
+            fill: '#',
+        }
+    }
+
+    /// Sets current value
+    pub fn set(&mut self, value: f64) {
+        self.value = value
+    }
+
+    /// Number of filled chars
+    pub fn filled(&self) -> usize {
+        if self.max <= 0.0 {
+            return 0;
+        }
+        let part = (self.value / self.max).clamp(0.0, 1.0);
+        (part * self.width as f64).round() as usize
+    }
+
+    /// Whole bar as text
+    pub fn text(&self) -> String {
+        let filled = self.filled();
+        format!(
+            "{} [{}{}]",
+            self.title,
+            self.fill.to_string().repeat(filled),
+            " ".repeat(self.width - filled)
+        )
+    }
+}
+
+impl AsWidget for Bar {
+    fn size(&self) -> (usize, usize) {
+        (1, self.title.chars().count() + self.width + 3)
+    }
+
+    fn cell(&self, _r: usize, c: usize) -> Option<char> {
+        self.text().chars().nth(c)
+    }
+}
+
+/// Rectangle of text lines, optionally surrounded with border
+#[derive(Debug, Clone, PartialEq)]
+pub struct Panel {
+    pub lines: Vec<String>,
+    pub border: bool,
+}
+
+impl Panel {
+    pub fn new(lines: Vec<String>, border: bool) -> Self {
+        Self { lines, border }
+    }
+
+    /// Replaces the lines
+    pub fn set(&mut self, lines: Vec<String>) {
+        self.lines = lines
+    }
+
+    /// Size of content without border
+    fn inner(&self) -> (usize, usize) {
+        let cols = self.lines.iter().map(|l| l.chars().count()).max();
+        (self.lines.len(), cols.unwrap_or(0))
+    }
+}
+
+impl AsWidget for Panel {
+    fn size(&self) -> (usize, usize) {
+        let (rows, cols) = self.inner();
+        match self.border {
+            true => (rows + 2, cols + 2),
+            false => (rows, cols),
+        }
+    }
+
+    fn cell(&self, r: usize, c: usize) -> Option<char> {
+        if !self.border {
+            return Some(self.lines[r].chars().nth(c).unwrap_or(' '));
+        }
+        let (rows, cols) = self.inner();
+        let edge = (r == 0 || r == rows + 1, c == 0 || c == cols + 1);
+        match edge {
+            (true, true) => Some('+'),
+            (true, false) => Some('-'),
+            (false, true) => Some('|'),
+            (false, false) => Some(self.lines[r - 1].chars().nth(c - 1).unwrap_or(' ')),
+        }
+    }
+}
+
+/// Single char that is usually placed in the center
+#[derive(Debug, Clone, PartialEq)]
+pub struct Crosshair {
+    pub ch: char,
+}
+
+impl Crosshair {
+    pub fn new(ch: char) -> Self {
+        Self { ch }
+    }
+}
+
+impl AsWidget for Crosshair {
+    fn size(&self) -> (usize, usize) {
+        (1, 1)
+    }
+
+    fn cell(&self, _r: usize, _c: usize) -> Option<char> {
+        Some(self.ch)
+    }
+}
+
+/// Widget placed on the screen
+struct Layer {
+    name: &'static str,
+    anchor: Anchor,
+    offset: (isize, isize),
+    visible: bool,
+    widget: Box<dyn AsWidget>,
+}
+
+/// Overlay layer that is composited over the picture after ray casting.
+/// Widgets are named, drawn in order of adding and can be updated from event handlers.
+/// Dialogs are drawn over all the widgets, see `dialog` module
+#[derive(Default)]
+pub struct Hud {
+    layers: Vec<Layer>,
+    pub(crate) dialogs: Dialogs,
+}
+
+impl Hud {
+    /// Hud without widgets
+    pub fn new() -> Self {
+        Self {
+            layers: vec![],
+            dialogs: Dialogs::default(),
+        }
+    }
+
+    /// Adds widget attached to `anchor` or replaces widget with the same name keeping its place
+    pub fn add(&mut self, name: &'static str, anchor: Anchor, widget: impl AsWidget) {
+        let widget = Box::new(widget);
+        match self.layers.iter_mut().find(|l| l.name == name) {
+            Some(layer) => {
+                layer.anchor = anchor;
+                layer.widget = widget;
+            }
+            None => self.layers.push(Layer {
+                name,
+                anchor,
+                offset: (0, 0),
+                visible: true,
+                widget,
+            }),
+        }
+    }
+
+    /// Removes widget with the given name if it exists
+    pub fn remove(&mut self, name: &'static str) {
+        self.layers.retain(|l| l.name != name)
+    }
+
+    /// Whether widget with the given name exists
+    pub fn contains(&self, name: &'static str) -> bool {
+        self.layers.iter().any(|l| l.name == name)
+    }
+
+    /// Shifts widget from its anchor on the given (rows, cols)
+    pub fn shift(&mut self, name: &'static str, offset: (isize, isize)) {
+        if let Some(layer) = self.layer_mut(name) {
+            layer.offset = offset
+        }
+    }
+
+    /// Ref to widget with the given name if it exists and has type `W`
+    pub fn get<W: AsWidget>(&self, name: &'static str) -> Option<&W> {
+        let layer = self.layers.iter().find(|l| l.name == name)?;
+        (*layer.widget).as_any().downcast_ref::<W>()
+    }
+
+    /// Mutable ref to widget with the given name if it exists and has type `W`
+    pub fn get_mut<W: AsWidget>(&mut self, name: &'static str) -> Option<&mut W> {
+        let layer = self.layer_mut(name)?;
+        (*layer.widget).as_any_mut().downcast_mut::<W>()
+    }
+
+    /// Shows or hides widget
+    pub fn show(&mut self, name: &'static str, visible: bool) {
+        if let Some(layer) = self.layer_mut(name) {
+            layer.visible = visible
+        }
+    }
+
+    /// Shows hidden widget or hides shown one
+    pub fn toggle(&mut self, name: &'static str) {
+        if let Some(layer) = self.layer_mut(name) {
+            layer.visible = !layer.visible
+        }
+    }
+
+    /// Whether widget exists and is shown
+    pub fn is_visible(&self, name: &'static str) -> bool {
+        self.layers.iter().any(|l| l.name == name && l.visible)
+    }
+
+    /// Updates all the visible widgets
+    pub fn update(&mut self, cs: &CoordSys, camera: &Camera, scene: &dyn AsScene) {
+        for layer in self.layers.iter_mut().filter(|l| l.visible) {
+            layer.widget.update(cs, camera, scene)
+        }
+    }
+
+    /// Draws all the visible widgets and then dialogs over the frame
+    pub fn compose(&self, frame: &mut Frame) {
+        for layer in self.layers.iter().filter(|l| l.visible) {
+            paint(frame, layer.widget.as_ref(), layer.anchor, layer.offset);
+        }
+        self.dialogs.compose(frame);
+    }
+
+    fn layer_mut(&mut self, name: &'static str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+}
+
+/// Draws widget over the frame at the given anchor shifted on `offset`, parts out of frame are cut
+pub fn paint(frame: &mut Frame, widget: &dyn AsWidget, anchor: Anchor, offset: (isize, isize)) {
+    let area = frame.size();
+    let size = widget.size();
+    let origin = anchor.origin(area, size);
+    let origin = (origin.0 + offset.0, origin.1 + offset.1);
+    for r in 0..size.0 {
+        for c in 0..size.1 {
+            let (fr, fc) = (origin.0 + r as isize, origin.1 + c as isize);
+            if fr < 0 || fc < 0 || fr >= area.0 as isize || fc >= area.1 as isize {
+                continue;
+            }
+            if let Some(ch) = widget.cell(r, c) {
+                *frame.at_mut(fr as usize, fc as usize) = ch;
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Hud {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.layers.iter().map(|l| l.name).collect::<Vec<_>>();
+        write!(f, "Hud {:?}", names)
+    }
+}
@@ -0,0 +1,446 @@
+use {
+    crate::{
+        engn::{vec3::*, *},
+        errs::ReRes,
+        math::*,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+/// Part of penetration that is corrected per step by moving bodies apart
+const CORRECTION: f64 = 0.8;
+
+/// Penetration that is allowed without correction, it prevents jitter of resting bodies
+const SLOP: f64 = 1e-3;
+
+/// Most substeps of `PhysicsWorld::step`, the rest of longer time is dropped,
+/// so a long gap between frames doesn't stall the game with simulation
+pub const MAX_SUBSTEPS: usize = 8;
+
+/// Volume occupied by body or trigger relatively to its position
+#[derive(Debug, Clone, PartialEq)]
+pub enum Volume {
+    /// Sphere centered at the position
+    Sphere { radius: f64 },
+    /// Box aligned to the axes with the given half sizes, it isn't turned by rotation of body
+    Cuboid { half: Vector },
+    /// Half-space behind the plane going through the position, `normal` looks outside
+    Plane { normal: Vector },
+}
+
+impl Volume {
+    /// Whether point `pt` is inside the volume placed at `pos`
+    pub fn contains(&self, pos: &Point, pt: &Point) -> bool {
+        let d = sub(arr(pt), arr(pos));
+        match self {
+            Self::Sphere { radius } => norm(d) <= *radius,
+            Self::Cuboid { half } => (0..3).all(|i| d[i].abs() <= half[i]),
+            Self::Plane { normal } => dot(d, arr(normal)) <= 0.0,
+        }
+    }
+
+    /// Inverse of the diagonal inertia tensor for unit mass. Boxes and planes aren't
+    /// turned by collisions since their volumes aren't rotated
+    fn inv_inertia(&self) -> [f64; 3] {
+        match self {
+            Self::Sphere { radius } if *radius > 0.0 => [2.5 / (radius * radius); 3],
+            _ => [0.0; 3],
+        }
+    }
+}
+
+/// Body moved by `PhysicsWorld`. Bodies with zero inverse mass are fixed:
+/// they collide with others but don't move. Body can be bound to `AsGameObject`
+/// that is moved and rotated along with it
+#[derive(Debug, Clone)]
+pub struct RigidBody {
+    pub volume: Volume,
+    pub pos: Point,
+    /// Velocity in units per second
+    pub velocity: Vector,
+    /// Angular velocity as rotation angles about the axes in radians per second
+    pub angular: Vector,
+    /// Part of normal velocity that remains after collision within [0, 1]
+    pub restitution: f64,
+    /// Coefficient of friction between colliding surfaces
+    pub friction: f64,
+    inv_mass: f64,
+    object: Option<Rc<RefCell<dyn AsGameObject>>>,
+}
+
+impl RigidBody {
+    /// Body of the given mass, non-positive or infinite mass makes body fixed
+    pub fn new(volume: Volume, pos: Point, mass: f64) -> Self {
+        Self {
+            volume,
+            pos,
+            velocity: Vector::new(vec![0.0; 3]),
+            angular: Vector::new(vec![0.0; 3]),
+            restitution: 0.5,
+            friction: 0.3,
+            inv_mass: match mass > 0.0 && mass.is_finite() {
+                true => 1.0 / mass,
+                false => 0.0,
+            },
+            object: None,
+        }
+    }
+
+    /// Fixed body, eg ground or walls
+    pub fn fixed(volume: Volume, pos: Point) -> Self {
+        Self::new(volume, pos, f64::INFINITY)
+    }
+
+    pub fn with_velocity(mut self, velocity: Vector) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_angular(mut self, angular: Vector) -> Self {
+        self.angular = angular;
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f64) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f64) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// Binds game object to the body, the object is placed at the body position
+    pub fn with_object(mut self, object: Rc<RefCell<dyn AsGameObject>>) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    /// Mass of body, infinite for fixed ones
+    pub fn mass(&self) -> f64 {
+        match self.is_fixed() {
+            true => f64::INFINITY,
+            false => 1.0 / self.inv_mass,
+        }
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.inv_mass == 0.0
+    }
+
+    /// Game object bound to the body
+    pub fn object(&self) -> Option<&Rc<RefCell<dyn AsGameObject>>> {
+        self.object.as_ref()
+    }
+
+    /// Changes velocities with impulse applied at point `at`, at the position if it's `None`
+    pub fn apply_impulse(&mut self, impulse: &Vector, at: Option<&Point>) {
+        let arm = match at {
+            Some(at) => sub(arr(at), arr(&self.pos)),
+            None => [0.0; 3],
+        };
+        self.push(arr(impulse), arm);
+    }
+
+    fn push(&mut self, impulse: [f64; 3], arm: [f64; 3]) {
+        if self.is_fixed() {
+            return;
+        }
+        let velocity = add(arr(&self.velocity), scale(impulse, self.inv_mass));
+        let angular = add(arr(&self.angular), self.turn(cross(arm, impulse)));
+        self.velocity = vector(velocity);
+        self.angular = vector(angular);
+    }
+
+    /// Change of angular velocity caused by the torque impulse
+    fn turn(&self, torque: [f64; 3]) -> [f64; 3] {
+        let inv = self.volume.inv_inertia();
+        [0, 1, 2].map(|i| torque[i] * inv[i] * self.inv_mass)
+    }
+
+    /// Velocity of the body point at `arm` from the position
+    fn velocity_at(&self, arm: [f64; 3]) -> [f64; 3] {
+        add(arr(&self.velocity), cross(arr(&self.angular), arm))
+    }
+
+    /// Moves and rotates the body and its object during `dt` seconds
+    fn integrate(&mut self, gravity: [f64; 3], dt: f64) -> ReRes<()> {
+        let velocity = add(arr(&self.velocity), scale(gravity, dt));
+        self.velocity = vector(velocity);
+        self.pos = vector(add(arr(&self.pos), scale(velocity, dt)));
+        if let Some(object) = &self.object {
+            let mut object = object.borrow_mut();
+            *object.pos_mut() = self.pos.clone();
+            let angle = scale(arr(&self.angular), dt);
+            if norm(angle) > EPS {
+                object.rotate_3d(angle[0], angle[1], angle[2])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Handle of body in `PhysicsWorld`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyId(pub(crate) usize);
+
+/// Contact of two bodies found during the last step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    pub a: BodyId,
+    pub b: BodyId,
+    /// Unit normal of contact looking from `a` to `b`
+    pub normal: Vector,
+    /// Depth of penetration
+    pub depth: f64,
+    pub point: Point,
+}
+
+/// Optional physics world that `Game` steps each tick: bodies are moved with gravity
+/// and their velocities, then collisions of spheres, boxes and planes are detected
+/// and resolved with impulses
+#[derive(Debug, Clone)]
+pub struct PhysicsWorld {
+    /// Acceleration of all the bodies that aren't fixed
+    pub gravity: Vector,
+    /// Longest time step, longer steps are split into the equal ones up to `MAX_SUBSTEPS`
+    pub max_step: f64,
+    bodies: Vec<Option<RigidBody>>,
+    contacts: Vec<Contact>,
+}
+
+impl PhysicsWorld {
+    pub fn new() -> Self {
+        Self {
+            gravity: Vector::new(vec![0.0, 0.0, -9.81]),
+            max_step: 1.0 / 120.0,
+            bodies: vec![],
+            contacts: vec![],
+        }
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_max_step(mut self, max_step: f64) -> Self {
+        self.max_step = max_step;
+        self
+    }
+
+    /// Adds body to the world returning its handle
+    pub fn add(&mut self, body: RigidBody) -> BodyId {
+        self.bodies.push(Some(body));
+        BodyId(self.bodies.len() - 1)
+    }
+
+    /// Removes body from the world, its handle isn't reused
+    pub fn remove(&mut self, id: BodyId) -> Option<RigidBody> {
+        self.bodies.get_mut(id.0)?.take()
+    }
+
+    pub fn body(&self, id: BodyId) -> Option<&RigidBody> {
+        self.bodies.get(id.0)?.as_ref()
+    }
+
+    pub fn body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody> {
+        self.bodies.get_mut(id.0)?.as_mut()
+    }
+
+    /// All the bodies with their handles
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyId, &RigidBody)> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(i, body)| Some((BodyId(i), body.as_ref()?)))
+    }
+
+    /// Contacts found during the last step
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    /// Simulates `dt` seconds but not more than `MAX_SUBSTEPS` of `max_step`
+    pub fn step(&mut self, dt: f64) -> ReRes<()> {
+        if dt <= 0.0 {
+            return Ok(());
+        }
+        let (n, dt) = match self.max_step > 0.0 {
+            true => {
+                let dt = dt.min(self.max_step * MAX_SUBSTEPS as f64);
+                ((dt / self.max_step).ceil().max(1.0) as usize, dt)
+            }
+            false => (1, dt),
+        };
+        self.contacts.clear();
+        for _ in 0..n {
+            self.substep(dt / n as f64)?;
+        }
+        Ok(())
+    }
+
+    fn substep(&mut self, dt: f64) -> ReRes<()> {
+        let gravity = arr(&self.gravity);
+        for body in self.bodies.iter_mut().flatten() {
+            if !body.is_fixed() {
+                body.integrate(gravity, dt)?;
+            }
+        }
+        for i in 0..self.bodies.len() {
+            for j in i + 1..self.bodies.len() {
+                let contact = match (&self.bodies[i], &self.bodies[j]) {
+                    (Some(a), Some(b)) if !(a.is_fixed() && b.is_fixed()) => contact(a, b),
+                    _ => None,
+                };
+                if let Some((normal, depth, point)) = contact {
+                    self.resolve(i, j, normal, depth, point);
+                    let (a, b) = (BodyId(i), BodyId(j));
+                    self.contacts.retain(|c| (c.a, c.b) != (a, b));
+                    self.contacts.push(Contact {
+                        a,
+                        b,
+                        normal: vector(normal),
+                        depth,
+                        point: vector(point),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies impulses of collision and friction to bodies `i < j` and moves them apart
+    fn resolve(&mut self, i: usize, j: usize, n: [f64; 3], depth: f64, point: [f64; 3]) {
+        let (head, tail) = self.bodies.split_at_mut(j);
+        let (a, b) = match (head[i].as_mut(), tail[0].as_mut()) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return,
+        };
+        let ra = sub(point, arr(&a.pos));
+        let rb = sub(point, arr(&b.pos));
+        let rel = sub(b.velocity_at(rb), a.velocity_at(ra));
+        let vn = dot(rel, n);
+        if vn < 0.0 {
+            let e = a.restitution.max(b.restitution);
+            let jn = -(1.0 + e) * vn / inv_mass(a, ra, b, rb, n);
+            a.push(scale(n, -jn), ra);
+            b.push(scale(n, jn), rb);
+
+            let rel = sub(b.velocity_at(rb), a.velocity_at(ra));
+            if let Some(t) = unit(sub(rel, scale(n, dot(rel, n)))) {
+                let mu = (a.friction * b.friction).sqrt();
+                let jt = (-dot(rel, t) / inv_mass(a, ra, b, rb, t)).clamp(-mu * jn, mu * jn);
+                a.push(scale(t, -jt), ra);
+                b.push(scale(t, jt), rb);
+            }
+        }
+
+        let total = a.inv_mass + b.inv_mass;
+        let shift = (depth - SLOP).max(0.0) * CORRECTION / total;
+        a.pos = vector(sub(arr(&a.pos), scale(n, shift * a.inv_mass)));
+        b.pos = vector(add(arr(&b.pos), scale(n, shift * b.inv_mass)));
+        for body in [a, b] {
+            if let Some(object) = &body.object {
+                *object.borrow_mut().pos_mut() = body.pos.clone();
+            }
+        }
+    }
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Effective inverse mass of the pair of bodies pushed at `ra` and `rb` along `dir`
+fn inv_mass(a: &RigidBody, ra: [f64; 3], b: &RigidBody, rb: [f64; 3], dir: [f64; 3]) -> f64 {
+    let ta = cross(a.turn(cross(ra, dir)), ra);
+    let tb = cross(b.turn(cross(rb, dir)), rb);
+    a.inv_mass + b.inv_mass + dot(add(ta, tb), dir)
+}
+
+/// Normal from `a` to `b`, depth and point of contact if bodies overlap
+fn contact(a: &RigidBody, b: &RigidBody) -> Option<([f64; 3], f64, [f64; 3])> {
+    let (pa, pb) = (arr(&a.pos), arr(&b.pos));
+    match (&a.volume, &b.volume) {
+        (Volume::Sphere { radius: ra }, Volume::Sphere { radius: rb }) => {
+            let d = sub(pb, pa);
+            let dist = norm(d);
+            if dist >= ra + rb {
+                return None;
+            }
+            let n = unit(d).unwrap_or([0.0, 0.0, 1.0]);
+            Some((n, ra + rb - dist, add(pa, scale(n, *ra))))
+        }
+        (Volume::Sphere { radius }, Volume::Plane { normal }) => {
+            let normal = unit(arr(normal))?;
+            let dist = dot(sub(pa, pb), normal) - radius;
+            let point = sub(pa, scale(normal, *radius));
+            (dist < 0.0).then_some((neg(normal), -dist, point))
+        }
+        (Volume::Cuboid { half }, Volume::Plane { normal }) => {
+            let normal = unit(arr(normal))?;
+            let half = arr(half);
+            let corner = [0, 1, 2].map(|i| pa[i] - half[i] * normal[i].signum());
+            let dist = dot(sub(corner, pb), normal);
+            // contact point is moved to the middle of the lowest face or edge
+            let point = [0, 1, 2].map(|i| match normal[i].abs() > EPS {
+                true => corner[i],
+                false => pa[i],
+            });
+            (dist < 0.0).then_some((neg(normal), -dist, point))
+        }
+        (Volume::Sphere { radius }, Volume::Cuboid { half }) => {
+            let half = arr(half);
+            let local = sub(pa, pb);
+            let closest = [0, 1, 2].map(|i| local[i].clamp(-half[i], half[i]));
+            let d = sub(local, closest);
+            let dist = norm(d);
+            if dist >= *radius {
+                return None;
+            }
+            match unit(d) {
+                // normal looks from the box to the sphere, it's reversed
+                Some(n) => Some((neg(n), radius - dist, add(pb, closest))),
+                None => {
+                    let (n, depth) = min_axis(local, half);
+                    Some((neg(n), depth + radius, pa))
+                }
+            }
+        }
+        (Volume::Cuboid { half: ha }, Volume::Cuboid { half: hb }) => {
+            let (ha, hb) = (arr(ha), arr(hb));
+            let d = sub(pb, pa);
+            let overlap = [0, 1, 2].map(|i| ha[i] + hb[i] - d[i].abs());
+            if overlap.iter().any(|o| *o <= 0.0) {
+                return None;
+            }
+            let axis = (0..3)
+                .min_by(|i, j| overlap[*i].total_cmp(&overlap[*j]))
+                .unwrap();
+            let mut n = [0.0; 3];
+            n[axis] = if d[axis] < 0.0 { -1.0 } else { 1.0 };
+            let point = [0, 1, 2].map(|i| {
+                let lo = (pa[i] - ha[i]).max(pb[i] - hb[i]);
+                let hi = (pa[i] + ha[i]).min(pb[i] + hb[i]);
+                (lo + hi) / 2.0
+            });
+            Some((n, overlap[axis], point))
+        }
+        (Volume::Plane { .. }, Volume::Plane { .. }) => None,
+        _ => contact(b, a).map(|(n, depth, point)| (neg(n), depth, point)),
+    }
+}
+
+/// Axis of the nearest box face to the point inside it: normal looking outside and distance
+fn min_axis(local: [f64; 3], half: [f64; 3]) -> ([f64; 3], f64) {
+    let axis = (0..3)
+        .min_by(|i, j| (half[*i] - local[*i].abs()).total_cmp(&(half[*j] - local[*j].abs())))
+        .unwrap();
+    let mut n = [0.0; 3];
+    n[axis] = if local[axis] < 0.0 { -1.0 } else { 1.0 };
+    (n, half[axis] - local[axis].abs())
+}
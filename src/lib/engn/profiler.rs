@@ -0,0 +1,327 @@
+use {
+    crate::{
+        engn::*,
+        errs::{inaccessible, ReRes},
+    },
+    std::{
+        collections::VecDeque,
+        fs,
+        path::Path,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
+    toml::{Table, Value},
+};
+
+/// Name that `StatsOverlay` is expected to have in `Hud`, eg `MovementEventSys` toggles it
+pub const STATS: &str = "stats";
+
+/// Number of the last frames kept by `Profiler`
+pub const HISTORY: usize = 120;
+
+/// Chars of graphs from the lowest value to the highest one
+const LEVELS: [char; 8] = ['_', '.', ',', '-', '=', '+', '*', '#'];
+
+/// Rays and collision tests counted since the last frame of `Profiler`, clones share the counts.
+/// `Game` gives counters of its profiler to the scene with `AsScene::set_counters`
+#[derive(Debug, Clone, Default)]
+pub struct Counters(Arc<Mutex<(u64, u64)>>);
+
+impl Counters {
+    /// Adds rays cast to the counter of the current frame, `Game` counts rays of `Canvas`
+    pub fn count_rays(&self, n: u64) {
+        self.0.lock().unwrap().0 += n
+    }
+
+    /// Adds ray and material collision tests to the counter of the current frame,
+    /// `World` counts them itself while custom scenes may count theirs in `AsScene::collide`
+    pub fn count_collisions(&self, n: u64) {
+        self.0.lock().unwrap().1 += n
+    }
+
+    /// Counted rays and collision tests, counters are reset
+    fn take(&self) -> (u64, u64) {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+/// Phases of `Game` loop timed by `Profiler`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FramePhase {
+    /// Feeding `InputState` and dispatching events, waiting for them isn't counted
+    Input,
+    /// Physics, scene tick, `AsEventSys::handle_all` and triggers
+    Handle,
+    /// Ray casting in `Canvas::update`
+    Update,
+    /// Updating and composing `Hud` and presenting the frame with backend
+    Draw,
+}
+
+impl FramePhase {
+    pub const ALL: [Self; 4] = [Self::Input, Self::Handle, Self::Update, Self::Draw];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Input => "input",
+            Self::Handle => "handle",
+            Self::Update => "update",
+            Self::Draw => "draw",
+        }
+    }
+
+    fn idx(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Timings and counters of a single frame
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameStats {
+    /// Time spent in each `FramePhase`
+    pub phases: [Duration; 4],
+    /// Time since the end of the previous frame including waiting for events
+    pub interval: Duration,
+    pub rays: u64,
+    pub collisions: u64,
+}
+
+impl FrameStats {
+    pub fn phase(&self, phase: FramePhase) -> Duration {
+        self.phases[phase.idx()]
+    }
+
+    /// Time spent in all the phases
+    pub fn busy(&self) -> Duration {
+        self.phases.iter().sum()
+    }
+
+    /// Frames per second if all the frames took as long as this one
+    pub fn fps(&self) -> f64 {
+        match self.interval.is_zero() {
+            true => 0.0,
+            false => 1.0 / self.interval.as_secs_f64(),
+        }
+    }
+}
+
+/// Collects `FrameStats` of each frame: keeps the last `HISTORY` frames for graphs
+/// and sums and maximums of the whole run for `Profiler::summary`
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    current: FrameStats,
+    history: VecDeque<FrameStats>,
+    total: FrameStats,
+    max: FrameStats,
+    frames: u64,
+    last: Instant,
+    counters: Counters,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::starting(Instant::now())
+    }
+
+    /// Profiler whose first frame interval is counted from `now`
+    pub fn starting(now: Instant) -> Self {
+        Self {
+            current: FrameStats::default(),
+            history: VecDeque::with_capacity(HISTORY),
+            total: FrameStats::default(),
+            max: FrameStats::default(),
+            frames: 0,
+            last: now,
+            counters: Counters::default(),
+        }
+    }
+
+    /// Adds time spent in phase of the current frame
+    pub fn record(&mut self, phase: FramePhase, elapsed: Duration) {
+        self.current.phases[phase.idx()] += elapsed
+    }
+
+    /// Ends the current frame at `now` taking counted rays and collision tests
+    pub fn finish(&mut self, now: Instant) {
+        let mut frame = std::mem::take(&mut self.current);
+        (frame.rays, frame.collisions) = self.counters.take();
+        frame.interval = now.saturating_duration_since(self.last);
+        self.last = now;
+
+        for phase in FramePhase::ALL {
+            let (idx, time) = (phase.idx(), frame.phase(phase));
+            self.total.phases[idx] += time;
+            self.max.phases[idx] = self.max.phases[idx].max(time);
+        }
+        self.total.interval += frame.interval;
+        self.total.rays += frame.rays;
+        self.total.collisions += frame.collisions;
+        self.max.interval = self.max.interval.max(frame.interval);
+        self.max.rays = self.max.rays.max(frame.rays);
+        self.max.collisions = self.max.collisions.max(frame.collisions);
+        self.frames += 1;
+
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(frame);
+    }
+
+    /// Counters of the current frame, their clones count into this profiler only
+    pub fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
+    /// Number of finished frames
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The last finished frame
+    pub fn last(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    /// The last `HISTORY` frames from the oldest one
+    pub fn history(&self) -> impl Iterator<Item = &FrameStats> {
+        self.history.iter()
+    }
+
+    /// Sums of all the frames
+    pub fn total(&self) -> &FrameStats {
+        &self.total
+    }
+
+    /// Frames per second over the kept history
+    pub fn fps(&self) -> f64 {
+        let time: Duration = self.history.iter().map(|frame| frame.interval).sum();
+        match time.is_zero() {
+            true => 0.0,
+            false => self.history.len() as f64 / time.as_secs_f64(),
+        }
+    }
+
+    /// Statistics of the whole run: number of frames, mean FPS, rays and collision tests
+    /// per frame and mean and max time in milliseconds of each phase and of whole frames
+    pub fn summary(&self) -> Table {
+        let frames = self.frames.max(1) as f64;
+        let ms = |time: Duration| time.as_secs_f64() * 1e3;
+        let timing = |total: Duration, max: Duration| {
+            let mut table = Table::new();
+            table.insert("mean_ms".to_string(), Value::Float(ms(total) / frames));
+            table.insert("max_ms".to_string(), Value::Float(ms(max)));
+            Value::Table(table)
+        };
+
+        let mut phases = Table::new();
+        for phase in FramePhase::ALL {
+            let timing = timing(self.total.phase(phase), self.max.phase(phase));
+            phases.insert(phase.name().to_string(), timing);
+        }
+        let seconds = self.total.interval.as_secs_f64();
+        let fps = match seconds > 0.0 {
+            true => self.frames as f64 / seconds,
+            false => 0.0,
+        };
+
+        let mut summary = Table::new();
+        summary.insert("frames".to_string(), Value::Integer(self.frames as i64));
+        summary.insert("seconds".to_string(), Value::Float(seconds));
+        summary.insert("fps".to_string(), Value::Float(fps));
+        let rays = self.total.rays as f64 / frames;
+        summary.insert("rays_per_frame".to_string(), Value::Float(rays));
+        let collisions = self.total.collisions as f64 / frames;
+        summary.insert("collisions_per_frame".to_string(), Value::Float(collisions));
+        summary.insert(
+            "frame".to_string(),
+            timing(self.total.interval, self.max.interval),
+        );
+        summary.insert("phases".to_string(), Value::Table(phases));
+        summary
+    }
+
+    /// Writes `Profiler::summary` to TOML file
+    pub fn write(&self, path: impl AsRef<Path>) -> ReRes<()> {
+        let path = path.as_ref();
+        fs::write(path, self.summary().to_string()).map_err(|err| inaccessible(path, err))
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Overlay with FPS, phase timings, counters and graphs of FPS and frame time
+/// over the last `width` frames, `Game` refreshes it each frame while it's shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsOverlay {
+    pub(crate) panel: Panel,
+    pub width: usize,
+}
+
+impl StatsOverlay {
+    /// Overlay with graphs `width` chars wide
+    pub fn new(width: usize) -> Self {
+        Self {
+            panel: Panel::new(vec![], true),
+            width,
+        }
+    }
+
+    /// Lines shown inside border
+    pub fn lines(&self) -> &[String] {
+        &self.panel.lines
+    }
+
+    /// Takes the statistics of the last frames
+    pub fn refresh(&mut self, profiler: &Profiler) {
+        let ms = |time: Duration| time.as_secs_f64() * 1e3;
+        let skip = profiler.history.len().saturating_sub(self.width);
+        let frames = profiler.history().skip(skip).collect::<Vec<_>>();
+        let last = profiler.last().copied().unwrap_or_default();
+
+        let mut lines = vec![format!(
+            "FPS {:.1}  frame {:.1}ms",
+            profiler.fps(),
+            ms(last.busy())
+        )];
+        lines.extend(
+            FramePhase::ALL
+                .iter()
+                .map(|phase| format!("{:<6} {:.2}ms", phase.name(), ms(last.phase(*phase)))),
+        );
+        lines.push(format!("rays {}  hits {}", last.rays, last.collisions));
+        let fps = frames.iter().map(|frame| frame.fps()).collect::<Vec<_>>();
+        lines.push(format!("fps {}", graph(&fps, self.width)));
+        let busy = frames
+            .iter()
+            .map(|frame| ms(frame.busy()))
+            .collect::<Vec<_>>();
+        lines.push(format!("ms  {}", graph(&busy, self.width)));
+        self.panel.set(lines);
+    }
+}
+
+/// Line of `width` chars with levels of values scaled to their maximum, aligned to the right
+fn graph(values: &[f64], width: usize) -> String {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let top = (LEVELS.len() - 1) as f64;
+    let level = |value: f64| match max > 0.0 {
+        true => LEVELS[(value / max * top).round().clamp(0.0, top) as usize],
+        false => LEVELS[0],
+    };
+    let pad = " ".repeat(width.saturating_sub(values.len()));
+    pad + &values.iter().map(|value| level(*value)).collect::<String>()
+}
+
+impl AsWidget for StatsOverlay {
+    fn size(&self) -> (usize, usize) {
+        self.panel.size()
+    }
+
+    fn cell(&self, r: usize, c: usize) -> Option<char> {
+        self.panel.cell(r, c)
+    }
+}
@@ -0,0 +1,147 @@
+use {
+    rand::{Error, Rng, RngCore, SeedableRng},
+    rand_chacha::ChaCha8Rng,
+    std::{
+        collections::HashMap,
+        fmt::{self, Debug},
+        sync::{Arc, Mutex, MutexGuard, PoisonError},
+    },
+};
+
+/// Name of the stream that `Random` draws from when it's used as `RngCore` itself
+pub const MAIN_STREAM: &str = "main";
+
+/// Seeded source of randomness of `Game` shared with its scene and event system.
+/// Numbers are drawn from named streams, each one is seeded with the seed and its name,
+/// so drawing from one stream doesn't shift the others. Clones share the streams.
+/// Streams are `ChaCha8Rng`, their numbers don't change with versions of `rand`,
+/// so recordings stay valid
+#[derive(Clone)]
+pub struct Random {
+    seed: u64,
+    streams: Arc<Mutex<HashMap<String, ChaCha8Rng>>>,
+}
+
+impl Random {
+    /// Random with the given seed or with a random one, random seeds are below `2^63`
+    /// so they fit into TOML integers of `Conf` and `Recording`
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen_range(0..=i64::MAX as u64));
+        Self {
+            seed,
+            streams: Arc::default(),
+        }
+    }
+
+    /// Seed that the streams are derived from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Stream with the given name, it starts on the first draw and its clones continue it
+    pub fn stream(&self, name: &str) -> Stream {
+        Stream {
+            name: name.to_string(),
+            random: self.clone(),
+        }
+    }
+
+    /// Starts all the streams anew
+    pub fn reset(&self) {
+        self.lock().clear()
+    }
+
+    /// Draws from stream with the given name
+    fn draw<T>(&self, name: &str, f: impl FnOnce(&mut ChaCha8Rng) -> T) -> T {
+        let mut streams = self.lock();
+        if !streams.contains_key(name) {
+            let rng = ChaCha8Rng::seed_from_u64(derive(self.seed, name));
+            streams.insert(name.to_string(), rng);
+        }
+        f(streams.get_mut(name).unwrap())
+    }
+
+    /// Streams are consistent even if other thread panicked drawing from them
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, ChaCha8Rng>> {
+        self.streams.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl Debug for Random {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut streams = self.lock().keys().cloned().collect::<Vec<_>>();
+        streams.sort();
+        f.debug_struct("Random")
+            .field("seed", &self.seed)
+            .field("streams", &streams)
+            .finish()
+    }
+}
+
+/// Draws from `MAIN_STREAM`
+impl RngCore for Random {
+    fn next_u32(&mut self) -> u32 {
+        self.draw(MAIN_STREAM, |rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draw(MAIN_STREAM, |rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draw(MAIN_STREAM, |rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.draw(MAIN_STREAM, |rng| rng.try_fill_bytes(dest))
+    }
+}
+
+/// Named stream of `Random`, see `Random::stream`
+#[derive(Debug, Clone)]
+pub struct Stream {
+    name: String,
+    random: Random,
+}
+
+impl Stream {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl RngCore for Stream {
+    fn next_u32(&mut self) -> u32 {
+        self.random.draw(&self.name, |rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.random.draw(&self.name, |rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.random.draw(&self.name, |rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.random.draw(&self.name, |rng| rng.try_fill_bytes(dest))
+    }
+}
+
+/// Seed of stream: FNV-1a hash of its name mixed with the seed by SplitMix64,
+/// unlike `DefaultHasher` it doesn't change between Rust releases
+fn derive(seed: u64, name: &str) -> u64 {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    let mut z = (seed ^ hash).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,140 @@
+use {
+    crate::{
+        engn::*,
+        errs::{inaccessible, GameErr::*, ReErr::*, ReRes},
+    },
+    crossterm::event::Event,
+    serde::{Deserialize, Serialize},
+    std::{fs, path::Path, time::Duration},
+    toml::Value,
+};
+
+/// Version of recording files written by `Recording`, files of other versions are rejected
+pub const RECORDING_VERSION: i64 = 1;
+
+const VERSION_KEY: &str = "version";
+
+/// Name used in errors when recording isn't read from file
+const NO_FILE: &str = "<string>";
+
+/// Poll of backend by `Game`: time of backend clock right after it and the obtained event if any.
+/// Polls without events are kept since they advance time of held keys and physics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Poll {
+    /// Nanoseconds of `AsBackend::now`
+    pub time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<Event>,
+}
+
+impl Poll {
+    pub fn new(time: Duration, event: Option<Event>) -> Self {
+        Self {
+            time: time.as_nanos() as u64,
+            event,
+        }
+    }
+
+    /// Time of backend clock as `Duration`
+    pub fn at(&self) -> Duration {
+        Duration::from_nanos(self.time)
+    }
+}
+
+/// Session recorded by `Game::with_recording`: size of the picture, seed of randomness of the game,
+/// backend clock at start and each poll of backend. Events are recorded before routing so
+/// dialogs get them as well as `AsEventSys::push`. Played back with `Headless::replay` it
+/// reproduces the session as long as the game takes randomness from `seed` only.
+/// It's written to versioned TOML or JSON file, see `SceneFormat`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Recording {
+    pub version: i64,
+    /// Seed that the game was started with, it should fit into TOML integer, ie be below `2^63`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Size of the picture as (rows, cols)
+    pub size: (usize, usize),
+    /// Nanoseconds of `AsBackend::now` when the game started
+    pub start: u64,
+    pub polls: Vec<Poll>,
+}
+
+impl Recording {
+    /// Empty recording of game with picture of `size` started with `seed`
+    pub fn new(size: (usize, usize), seed: Option<u64>) -> Self {
+        Self {
+            version: RECORDING_VERSION,
+            seed,
+            size,
+            start: 0,
+            polls: vec![],
+        }
+    }
+
+    /// Time of backend clock when the game started as `Duration`
+    pub fn started(&self) -> Duration {
+        Duration::from_nanos(self.start)
+    }
+
+    /// Recorded events in order of obtaining
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.polls.iter().filter_map(|poll| poll.event.as_ref())
+    }
+
+    /// Reads recording from file, its format is chosen by extension
+    pub fn read(path: impl AsRef<Path>) -> ReRes<Self> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let content = fs::read_to_string(path).map_err(|err| inaccessible(path, err))?;
+        Self::parse_named(&content, SceneFormat::from_path(path), &file)
+    }
+
+    /// Parses recording from string in the given format
+    pub fn parse(content: &str, format: SceneFormat) -> ReRes<Self> {
+        Self::parse_named(content, format, NO_FILE)
+    }
+
+    fn parse_named(content: &str, format: SceneFormat, file: &str) -> ReRes<Self> {
+        let invalid = |reason: String| {
+            GameErr(InvalidRecording {
+                file: file.to_string(),
+                reason,
+            })
+        };
+        let root = format.parse(content, file)?;
+        match root.get(VERSION_KEY) {
+            Some(Value::Integer(RECORDING_VERSION)) => (),
+            Some(Value::Integer(version)) => {
+                return Err(GameErr(UnsupportedRecordingVersion(*version)))
+            }
+            _ => return Err(invalid(format!("missing integer `{}`", VERSION_KEY))),
+        }
+        Value::Table(root)
+            .try_into()
+            .map_err(|err: toml::de::Error| invalid(err.message().to_string()))
+    }
+
+    /// Recording in the given format
+    pub fn to_string(&self, format: SceneFormat) -> ReRes<String> {
+        let invalid = |reason: String| {
+            GameErr(InvalidRecording {
+                file: NO_FILE.to_string(),
+                reason,
+            })
+        };
+        match Value::try_from(self) {
+            Ok(Value::Table(root)) => format.render(&root).map_err(invalid),
+            Ok(_) => unreachable!("recording is serialized as table"),
+            Err(err) => Err(invalid(err.to_string())),
+        }
+    }
+
+    /// Writes recording to file, its format is chosen by extension
+    pub fn write(&self, path: impl AsRef<Path>) -> ReRes<()> {
+        let path = path.as_ref();
+        let content = self.to_string(SceneFormat::from_path(path))?;
+        fs::write(path, content).map_err(|err| inaccessible(path, err))
+    }
+}
@@ -0,0 +1,105 @@
+use {
+    crate::{engn::*, errs::ReRes},
+    std::{
+        fmt::{self, Debug},
+        fs,
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    },
+};
+
+/// Name of `Hud` panel listing errors of hot reload
+pub const RELOAD_ERRORS: &str = "reload_errors";
+
+/// Time between polls of watched files
+pub const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Loader that replaces state of the scene with the content of changed file
+pub type SceneLoader<Scn> = Box<dyn FnMut(&mut Scn, &Path) -> ReRes<()>>;
+
+/// Watches files by polling their modification times and sizes,
+/// missing file is a change as well as its appearance
+#[derive(Debug, Clone, Default)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl Watcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts watching file, its current state isn't a change
+    pub fn watch(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref().to_path_buf();
+        let stamp = stamp(&path);
+        self.files.push((path, stamp));
+    }
+
+    /// Watched files
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|(path, _)| path.as_path())
+    }
+
+    /// Whether any of the files has changed since the previous call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, old) in &mut self.files {
+            let new = stamp(path);
+            changed |= *old != new;
+            *old = new;
+        }
+        changed
+    }
+}
+
+/// Modification time and size of file
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Files watched by `Game` with errors of their last reading
+pub(crate) struct HotReload<Scn> {
+    pub(crate) conf: Watcher,
+    pub(crate) conf_err: Option<String>,
+    pub(crate) scenes: Vec<(Watcher, SceneLoader<Scn>, Option<String>)>,
+    pub(crate) next: Duration,
+}
+
+impl<Scn> HotReload<Scn> {
+    pub(crate) fn new() -> Self {
+        Self {
+            conf: Watcher::new(),
+            conf_err: None,
+            scenes: vec![],
+            next: Duration::ZERO,
+        }
+    }
+
+    /// Lines of `RELOAD_ERRORS` panel
+    pub(crate) fn errors(&self) -> Vec<String> {
+        let scenes = self.scenes.iter().map(|(_, _, err)| err);
+        Some(&self.conf_err)
+            .into_iter()
+            .chain(scenes)
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+impl<Scn> Debug for HotReload<Scn> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scenes = self
+            .scenes
+            .iter()
+            .map(|(watcher, _, _)| watcher)
+            .collect::<Vec<_>>();
+        f.debug_struct("HotReload")
+            .field("conf", &self.conf)
+            .field("scenes", &scenes)
+            .field("errors", &self.errors())
+            .finish()
+    }
+}
@@ -0,0 +1,150 @@
+use {
+    crate::{
+        engn::{
+            scene_file::{to_float, to_floats},
+            *,
+        },
+        errs::{
+            inaccessible,
+            GameErr::*,
+            ReErr::{self, *},
+            ReRes,
+        },
+        math::*,
+    },
+    std::{fs, path::Path},
+    toml::{Table, Value},
+};
+
+/// Version of save files written by `SaveGame`, files of other versions are rejected
+pub const SAVE_VERSION: i64 = 1;
+
+const VERSION_KEY: &str = "version";
+const CAMERA_KEY: &str = "camera";
+const POS_KEY: &str = "pos";
+const ZENITH_KEY: &str = "zenith";
+const AZIMUTH_KEY: &str = "azimuth";
+const SCENE_KEY: &str = "scene";
+const EVENTS_KEY: &str = "events";
+
+/// Name used in errors when save isn't read from file
+const NO_FILE: &str = "<string>";
+
+/// Snapshot of running `Game`: camera pose, state given by `AsScene::save` and by
+/// `AsEventSys::save`. It's written to versioned TOML or JSON file, see `SceneFormat`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveGame {
+    pub pos: Point,
+    /// Angle of view direction above the horizon in radians
+    pub zenith: f64,
+    /// Angle of view direction from axis x towards axis y in radians
+    pub azimuth: f64,
+    pub scene: Table,
+    pub events: Table,
+}
+
+impl SaveGame {
+    /// Reads save from file, its format is chosen by extension
+    pub fn read(path: impl AsRef<Path>) -> ReRes<Self> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let content = fs::read_to_string(path).map_err(|err| inaccessible(path, err))?;
+        Self::parse_named(&content, SceneFormat::from_path(path), &file)
+    }
+
+    /// Parses save from string in the given format
+    pub fn parse(content: &str, format: SceneFormat) -> ReRes<Self> {
+        Self::parse_named(content, format, NO_FILE)
+    }
+
+    fn parse_named(content: &str, format: SceneFormat, file: &str) -> ReRes<Self> {
+        let invalid = |reason: String| {
+            GameErr(InvalidSaveFile {
+                file: file.to_string(),
+                reason,
+            })
+        };
+        let mut root = format.parse(content, file)?;
+        match root.remove(VERSION_KEY) {
+            Some(Value::Integer(SAVE_VERSION)) => (),
+            Some(Value::Integer(version)) => return Err(GameErr(UnsupportedSaveVersion(version))),
+            _ => return Err(invalid(format!("missing integer `{}`", VERSION_KEY))),
+        }
+        let camera = take_table(&mut root, CAMERA_KEY, file)?;
+        let scene = take_table(&mut root, SCENE_KEY, file)?;
+        let events = take_table(&mut root, EVENTS_KEY, file)?;
+        if let Some(key) = root.keys().next() {
+            return Err(invalid(format!("unknown field `{}`", key)));
+        }
+        let known = [POS_KEY, ZENITH_KEY, AZIMUTH_KEY];
+        if let Some(key) = camera.keys().find(|key| !known.contains(&key.as_str())) {
+            return Err(invalid(format!("unknown field `{}.{}`", CAMERA_KEY, key)));
+        }
+
+        let pos = camera
+            .get(POS_KEY)
+            .and_then(to_floats)
+            .filter(|pos| pos.len() == 3)
+            .ok_or_else(|| invalid_value(POS_KEY, "expected array of 3 numbers"))?;
+        let angle = |key: &str| {
+            camera
+                .get(key)
+                .and_then(to_float)
+                .ok_or_else(|| invalid_value(key, "expected number"))
+        };
+        Ok(Self {
+            pos: Point::new(pos),
+            zenith: angle(ZENITH_KEY)?,
+            azimuth: angle(AZIMUTH_KEY)?,
+            scene,
+            events,
+        })
+    }
+
+    /// Save in the given format
+    pub fn to_string(&self, format: SceneFormat) -> ReRes<String> {
+        let mut camera = Table::new();
+        let pos = (0..3).map(|i| Value::Float(self.pos[i])).collect();
+        camera.insert(POS_KEY.to_string(), Value::Array(pos));
+        camera.insert(ZENITH_KEY.to_string(), Value::Float(self.zenith));
+        camera.insert(AZIMUTH_KEY.to_string(), Value::Float(self.azimuth));
+
+        let mut root = Table::new();
+        root.insert(VERSION_KEY.to_string(), Value::Integer(SAVE_VERSION));
+        root.insert(CAMERA_KEY.to_string(), Value::Table(camera));
+        root.insert(SCENE_KEY.to_string(), Value::Table(self.scene.clone()));
+        root.insert(EVENTS_KEY.to_string(), Value::Table(self.events.clone()));
+        format.render(&root).map_err(|reason| {
+            GameErr(InvalidSaveFile {
+                file: NO_FILE.to_string(),
+                reason,
+            })
+        })
+    }
+
+    /// Writes save to file, its format is chosen by extension
+    pub fn write(&self, path: impl AsRef<Path>) -> ReRes<()> {
+        let path = path.as_ref();
+        let content = self.to_string(SceneFormat::from_path(path))?;
+        fs::write(path, content).map_err(|err| inaccessible(path, err))
+    }
+}
+
+/// Removes table with the given key from root, missing table is empty
+fn take_table(root: &mut Table, key: &str, file: &str) -> ReRes<Table> {
+    match root.remove(key) {
+        None => Ok(Table::new()),
+        Some(Value::Table(table)) => Ok(table),
+        Some(_) => Err(GameErr(InvalidSaveFile {
+            file: file.to_string(),
+            reason: format!("`{}` must be table", key),
+        })),
+    }
+}
+
+fn invalid_value(key: &str, reason: &str) -> ReErr {
+    GameErr(InvalidSaveValue {
+        field: format!("{}.{}", CAMERA_KEY, key),
+        reason: reason.to_string(),
+    })
+}
@@ -0,0 +1,564 @@
+use {
+    crate::{
+        engn::*,
+        errs::{
+            inaccessible, toml_syntax,
+            GameErr::*,
+            Location,
+            ReErr::{self, *},
+            ReRes,
+        },
+        grid::*,
+        math::*,
+    },
+    std::{
+        collections::HashMap,
+        fmt::{self, Debug},
+        fs,
+        path::Path,
+    },
+    toml::{Table, Value},
+    uuid::Uuid,
+};
+
+const MATERIALS_KEY: &str = "materials";
+const KIND_KEY: &str = "kind";
+const ID_KEY: &str = "id";
+const PROPS_KEY: &str = "props";
+const CHARCOAL_KEY: &str = "charcoal";
+const CHARS_KEY: &str = "chars";
+const DRAW_DIST_KEY: &str = "draw_dist";
+
+/// Name used in errors when scene isn't read from file
+const NO_FILE: &str = "<string>";
+
+/// Format of scene file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    Toml,
+    Json,
+}
+
+impl SceneFormat {
+    /// Format by extension of the path, `.json` files are JSON and all the others are TOML
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+
+    /// Root table parsed from the content, `file` names it in `GameErr::InvalidSyntax`
+    pub fn parse(&self, content: &str, file: &str) -> ReRes<Table> {
+        let syntax = |location, reason| {
+            GameErr(InvalidSyntax {
+                file: file.to_string(),
+                location,
+                reason,
+            })
+        };
+        match self {
+            Self::Toml => content
+                .parse::<Table>()
+                .map_err(|err| toml_syntax(file, &err, content)),
+            Self::Json => match serde_json::from_str::<Value>(content) {
+                Ok(Value::Table(table)) => Ok(table),
+                Ok(_) => Err(syntax(None, "expected object".to_string())),
+                Err(err) => {
+                    let location = Location {
+                        line: err.line(),
+                        column: err.column(),
+                    };
+                    let reason = err.to_string();
+                    let suffix = format!(" at line {} column {}", location.line, location.column);
+                    let reason = reason.strip_suffix(&suffix).unwrap_or(&reason);
+                    Err(syntax(Some(location), reason.to_string()))
+                }
+            },
+        }
+    }
+
+    /// Root table written in this format, error is given as its description
+    pub fn render(&self, root: &Table) -> Result<String, String> {
+        match self {
+            Self::Toml => toml::to_string(root).map_err(|err| err.to_string()),
+            Self::Json => serde_json::to_string_pretty(root).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// Description of material in scene file: its kind, geometry, charcoal and properties.
+/// Accessors return errors naming the file and the field
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaterialDesc {
+    kind: String,
+    table: Table,
+    file: String,
+    index: usize,
+}
+
+impl MaterialDesc {
+    /// Empty description of material of the given kind
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            table: Table::new(),
+            file: NO_FILE.to_string(),
+            index: 0,
+        }
+    }
+
+    /// Kind of material that selects loader in `Materials`
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.table.get(key)
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.table.contains_key(key)
+    }
+
+    /// Sets raw value of the field
+    pub fn set(&mut self, key: &str, value: Value) {
+        self.table.insert(key.to_string(), value);
+    }
+
+    /// Error about the field of this material
+    pub fn err(&self, key: &str, reason: impl ToString) -> ReErr {
+        let mut field = format!("{}[{}]", MATERIALS_KEY, self.index);
+        if !key.is_empty() {
+            field = format!("{}.{}", field, key);
+        }
+        GameErr(InvalidSceneValue {
+            file: self.file.clone(),
+            field,
+            reason: reason.to_string(),
+        })
+    }
+
+    fn require(&self, key: &str) -> ReRes<&Value> {
+        self.get(key).ok_or_else(|| self.err(key, "missing field"))
+    }
+
+    pub fn float(&self, key: &str) -> ReRes<f64> {
+        to_float(self.require(key)?).ok_or_else(|| self.err(key, "expected number"))
+    }
+
+    pub fn string(&self, key: &str) -> ReRes<&str> {
+        match self.require(key)? {
+            Value::String(string) => Ok(string),
+            _ => Err(self.err(key, "expected string")),
+        }
+    }
+
+    /// Array of numbers
+    pub fn floats(&self, key: &str) -> ReRes<Vec<f64>> {
+        to_floats(self.require(key)?).ok_or_else(|| self.err(key, "expected array of numbers"))
+    }
+
+    pub fn point(&self, key: &str) -> ReRes<Point> {
+        Ok(Point::new(self.floats(key)?))
+    }
+
+    pub fn vector(&self, key: &str) -> ReRes<Vector> {
+        Ok(Vector::new(self.floats(key)?))
+    }
+
+    /// Basis given as array of its vectors
+    pub fn basis(&self, key: &str) -> ReRes<Basis> {
+        let vectors = match self.require(key)? {
+            Value::Array(array) => array.iter().map(to_floats).collect::<Option<Vec<_>>>(),
+            _ => None,
+        };
+        let vectors = vectors.ok_or_else(|| self.err(key, "expected array of vectors"))?;
+        if vectors.is_empty() || vectors.iter().any(|vec| vec.len() != vectors.len()) {
+            return Err(self.err(key, "expected square matrix"));
+        }
+        let matrix = Matrix::from_double(vectors).raw_transpose().to_multicol();
+        Basis::new(matrix).map_err(|err| self.err(key, err))
+    }
+
+    /// Optional `[charcoal]` table with `chars` and `draw_dist`
+    pub fn charcoal(&self) -> ReRes<Option<Charcoal>> {
+        let table = match self.get(CHARCOAL_KEY) {
+            Some(Value::Table(table)) => table,
+            Some(_) => return Err(self.err(CHARCOAL_KEY, "expected table")),
+            None => return Ok(None),
+        };
+        let chars = match table.get(CHARS_KEY) {
+            Some(Value::String(chars)) if !chars.is_empty() => chars.clone(),
+            _ => return Err(self.err(CHARCOAL_KEY, "expected non-empty string `chars`")),
+        };
+        match table.get(DRAW_DIST_KEY).and_then(to_float) {
+            Some(dist) if dist > 0.0 => Ok(Some(Charcoal::new(chars, dist))),
+            _ => Err(self.err(CHARCOAL_KEY, "expected positive `draw_dist`")),
+        }
+    }
+
+    pub fn set_float(&mut self, key: &str, float: f64) {
+        self.set(key, Value::Float(float));
+    }
+
+    pub fn set_floats(&mut self, key: &str, floats: &[f64]) {
+        self.set(
+            key,
+            Value::Array(floats.iter().map(|f| Value::Float(*f)).collect()),
+        );
+    }
+
+    pub fn set_vector(&mut self, key: &str, vec: &Vector) {
+        let floats = (0..vec.dim()).map(|i| vec[i]).collect::<Vec<_>>();
+        self.set_floats(key, &floats);
+    }
+
+    pub fn set_basis(&mut self, key: &str, basis: &Basis) {
+        let dim = basis.basis.cols();
+        let vectors = (0..dim)
+            .map(|i| {
+                let vec = (0..dim).map(|j| Value::Float(*basis.basis.att(i, j)));
+                Value::Array(vec.collect())
+            })
+            .collect();
+        self.set(key, Value::Array(vectors));
+    }
+
+    pub fn set_charcoal(&mut self, charcoal: &Option<Charcoal>) {
+        let Some(charcoal) = charcoal else {
+            self.table.remove(CHARCOAL_KEY);
+            return;
+        };
+        let mut table = Table::new();
+        let chars = charcoal.charmap.iter().collect::<String>();
+        table.insert(CHARS_KEY.to_string(), Value::String(chars));
+        table.insert(
+            DRAW_DIST_KEY.to_string(),
+            Value::Float(charcoal.charmap.len() as f64 / charcoal.coef),
+        );
+        self.set(CHARCOAL_KEY, Value::Table(table));
+    }
+
+    /// Entity with properties from `props` table, see `value_to_prop`
+    fn entity(&self, id: EntityId) -> ReRes<Entity> {
+        let mut entity = Entity::new(id);
+        let props = match self.get(PROPS_KEY) {
+            Some(Value::Table(props)) => props,
+            Some(_) => return Err(self.err(PROPS_KEY, "expected table")),
+            None => return Ok(entity),
+        };
+        for (key, value) in props {
+            let prop = value_to_prop(value).ok_or_else(|| {
+                let field = format!("{}.{}", PROPS_KEY, key);
+                self.err(&field, "unsupported type of property")
+            })?;
+            entity.set_prop(key, prop);
+        }
+        Ok(entity)
+    }
+
+    /// Writes properties of the entity that have supported types, others are skipped
+    fn set_props(&mut self, entity: &dyn AsEntity) {
+        let props = props_to_table(entity);
+        if !props.is_empty() {
+            self.set(PROPS_KEY, Value::Table(props));
+        }
+    }
+}
+
+/// Loader of material from its description and entity with id and properties already set
+pub type MaterialLoader = Box<dyn Fn(&MaterialDesc, Entity) -> ReRes<Box<dyn AsCollided>>>;
+
+/// Loaders of materials by kind, `plane` and `ellipse` are registered by default
+pub struct Materials {
+    loaders: HashMap<String, MaterialLoader>,
+}
+
+impl Materials {
+    pub fn new() -> Self {
+        let mut materials = Self {
+            loaders: HashMap::new(),
+        };
+        materials.register(HypePlane::KIND, |desc, entity| {
+            let plane = HypePlane::new(
+                entity,
+                desc.point("pos")?,
+                desc.vector("normal")?,
+                desc.charcoal()?,
+            )?;
+            Ok(Box::new(plane))
+        });
+        materials.register(HypeEllipse::KIND, |desc, entity| {
+            let ellipse = HypeEllipse::new(
+                entity,
+                desc.point("pos")?,
+                desc.basis("basis")?,
+                desc.floats("semiaxes")?,
+                desc.charcoal()?,
+            )?;
+            Ok(Box::new(ellipse))
+        });
+        materials
+    }
+
+    /// Registers loader of custom material replacing the one of the same kind
+    pub fn register(
+        &mut self,
+        kind: &str,
+        loader: impl Fn(&MaterialDesc, Entity) -> ReRes<Box<dyn AsCollided>> + 'static,
+    ) {
+        self.loaders.insert(kind.to_string(), Box::new(loader));
+    }
+
+    pub fn is_registered(&self, kind: &str) -> bool {
+        self.loaders.contains_key(kind)
+    }
+
+    fn load(&self, desc: &MaterialDesc, entity: Entity) -> ReRes<Box<dyn AsCollided>> {
+        let loader = self
+            .loaders
+            .get(&desc.kind)
+            .ok_or_else(|| desc.err(KIND_KEY, format!("unknown kind {:?}", desc.kind)))?;
+        loader(desc, entity).map_err(|err| match err {
+            GameErr(InvalidSceneValue { .. }) => err,
+            err => desc.err("", err),
+        })
+    }
+}
+
+impl Default for Materials {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Materials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut kinds = self.loaders.keys().collect::<Vec<_>>();
+        kinds.sort();
+        f.debug_struct("Materials").field("kinds", &kinds).finish()
+    }
+}
+
+/// Contents of scene file: list of `[[materials]]` that can be loaded into `World`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneFile {
+    pub materials: Vec<MaterialDesc>,
+}
+
+impl SceneFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads scene from TOML or JSON file depending on its extension
+    pub fn read(path: impl AsRef<Path>) -> ReRes<Self> {
+        let path = path.as_ref();
+        let file = path.display().to_string();
+        let content = fs::read_to_string(path).map_err(|err| inaccessible(path, err))?;
+        Self::parse_named(&content, SceneFormat::from_path(path), &file)
+    }
+
+    /// Parses scene from string in the given format
+    pub fn parse(content: &str, format: SceneFormat) -> ReRes<Self> {
+        Self::parse_named(content, format, NO_FILE)
+    }
+
+    fn parse_named(content: &str, format: SceneFormat, file: &str) -> ReRes<Self> {
+        Self::from_table_named(format.parse(content, file)?, file)
+    }
+
+    /// Scene from table of `SceneFile::to_table`, eg kept in `SaveGame`
+    pub fn from_table(root: Table) -> ReRes<Self> {
+        Self::from_table_named(root, NO_FILE)
+    }
+
+    fn from_table_named(mut root: Table, file: &str) -> ReRes<Self> {
+        let invalid = |reason: String| {
+            GameErr(InvalidSceneFile {
+                file: file.to_string(),
+                reason,
+            })
+        };
+        let materials = match root.remove(MATERIALS_KEY) {
+            Some(Value::Array(materials)) => materials,
+            Some(_) => return Err(invalid(format!("`{}` must be array", MATERIALS_KEY))),
+            None => vec![],
+        };
+        if let Some(key) = root.keys().next() {
+            return Err(invalid(format!("unknown field `{}`", key)));
+        }
+        let materials = materials
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let mut desc = MaterialDesc {
+                    kind: String::new(),
+                    table: Table::new(),
+                    file: file.to_string(),
+                    index,
+                };
+                let Value::Table(mut table) = value else {
+                    return Err(desc.err("", "expected table"));
+                };
+                desc.kind = match table.remove(KIND_KEY) {
+                    Some(Value::String(kind)) => kind,
+                    _ => return Err(desc.err(KIND_KEY, "expected string")),
+                };
+                desc.table = table;
+                Ok(desc)
+            })
+            .collect::<ReRes<_>>()?;
+        Ok(Self { materials })
+    }
+
+    /// Describes all the `Render` components of the world, fails if any of them can't be described
+    pub fn from_world(world: &World) -> ReRes<Self> {
+        let mut file = Self::new();
+        let Some(storage) = world.storage::<Render>() else {
+            return Ok(file);
+        };
+        for (id, render) in storage.iter() {
+            let mut desc = render.0.describe().ok_or(GameErr(UnsavableMaterial))?;
+            if let Some(uuid) = world.ids().uuid(id) {
+                desc.set(ID_KEY, Value::String(uuid.to_string()));
+            }
+            desc.set_props(render.0.as_ref());
+            desc.index = file.materials.len();
+            file.materials.push(desc);
+        }
+        Ok(file)
+    }
+
+    /// Table of the scene as it's written to file
+    pub fn to_table(&self) -> Table {
+        let materials = self
+            .materials
+            .iter()
+            .map(|desc| {
+                let mut table = desc.table.clone();
+                table.insert(KIND_KEY.to_string(), Value::String(desc.kind.clone()));
+                Value::Table(table)
+            })
+            .collect();
+        let mut root = Table::new();
+        root.insert(MATERIALS_KEY.to_string(), Value::Array(materials));
+        root
+    }
+
+    /// Scene in the given format
+    pub fn to_string(&self, format: SceneFormat) -> ReRes<String> {
+        format.render(&self.to_table()).map_err(|reason| {
+            GameErr(InvalidSceneFile {
+                file: NO_FILE.to_string(),
+                reason,
+            })
+        })
+    }
+
+    /// Writes scene into TOML or JSON file depending on its extension
+    pub fn write(&self, path: impl AsRef<Path>) -> ReRes<()> {
+        let path = path.as_ref();
+        let content = self.to_string(SceneFormat::from_path(path))?;
+        fs::write(path, content).map_err(|err| inaccessible(path, err))
+    }
+
+    /// Loads materials into new `World`
+    pub fn load(&self, materials: &Materials) -> ReRes<World> {
+        let mut world = World::new();
+        self.load_into(&mut world, materials)?;
+        Ok(world)
+    }
+
+    /// Spawns entity with `Render` component for each material, entities get ids with
+    /// `Uuid`s from the file if they are given
+    pub fn load_into(&self, world: &mut World, materials: &Materials) -> ReRes<Vec<EntityId>> {
+        let mut ids = vec![];
+        for desc in &self.materials {
+            let id = match desc.get(ID_KEY) {
+                None => world.spawn(),
+                Some(Value::String(uuid)) => {
+                    let uuid = Uuid::parse_str(uuid).map_err(|err| desc.err(ID_KEY, err))?;
+                    world
+                        .ids_mut()
+                        .generate_with(uuid)
+                        .ok_or_else(|| desc.err(ID_KEY, "id is already used"))?
+                }
+                Some(_) => return Err(desc.err(ID_KEY, "expected UUID string")),
+            };
+            let material = desc
+                .entity(id)
+                .and_then(|entity| materials.load(desc, entity));
+            match material {
+                Ok(material) => world.insert(id, Render(material))?,
+                Err(err) => {
+                    world.despawn(id);
+                    return Err(err);
+                }
+            };
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+}
+
+/// Reads scene file with default `Materials` into `World` that can be passed to `Game::new`
+pub fn load_scene(path: impl AsRef<Path>) -> ReRes<World> {
+    SceneFile::read(path)?.load(&Materials::new())
+}
+
+/// Writes materials of the world into scene file
+pub fn save_scene(world: &World, path: impl AsRef<Path>) -> ReRes<()> {
+    SceneFile::from_world(world)?.write(path)
+}
+
+pub(crate) fn to_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(int) => Some(*int as f64),
+        Value::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+pub(crate) fn to_floats(value: &Value) -> Option<Vec<f64>> {
+    match value {
+        Value::Array(array) => array.iter().map(to_float).collect(),
+        _ => None,
+    }
+}
+
+/// Value of property if it's of serializable type: `i64`, `f64`, `bool` or `String`
+pub fn prop_to_value(prop: &PropVal) -> Option<Value> {
+    if let Some(int) = prop.downcast_ref::<i64>() {
+        Some(Value::Integer(*int))
+    } else if let Some(float) = prop.downcast_ref::<f64>() {
+        Some(Value::Float(*float))
+    } else if let Some(bool) = prop.downcast_ref::<bool>() {
+        Some(Value::Boolean(*bool))
+    } else {
+        prop.downcast_ref::<String>()
+            .map(|string| Value::String(string.clone()))
+    }
+}
+
+/// Property made of value: integers become `i64`, floats `f64`, booleans `bool`
+/// and strings `String`, other values aren't supported
+pub fn value_to_prop(value: &Value) -> Option<PropVal> {
+    match value {
+        Value::Integer(int) => Some(Box::new(*int)),
+        Value::Float(float) => Some(Box::new(*float)),
+        Value::Boolean(bool) => Some(Box::new(*bool)),
+        Value::String(string) => Some(Box::new(string.clone())),
+        _ => None,
+    }
+}
+
+/// Properties of the entity that have serializable types, others are skipped
+pub fn props_to_table(entity: &dyn AsEntity) -> Table {
+    entity
+        .props()
+        .iter()
+        .filter_map(|(key, prop)| Some((key.clone(), prop_to_value(prop)?)))
+        .collect()
+}
@@ -0,0 +1,106 @@
+use {
+    super::{super::*, WallScene},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers},
+};
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    Key::new(code, modifiers)
+}
+
+#[test]
+fn parse_char() {
+    assert_eq!(
+        Key::parse("w"),
+        Some(key(KeyCode::Char('w'), KeyModifiers::NONE))
+    );
+}
+
+#[test]
+fn parse_named_with_modifiers() {
+    let mods = KeyModifiers::CONTROL | KeyModifiers::ALT;
+    assert_eq!(
+        Key::parse("Ctrl+alt+PageUp"),
+        Some(key(KeyCode::PageUp, mods))
+    );
+    assert_eq!(
+        Key::parse("space"),
+        Some(key(KeyCode::Char(' '), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        Key::parse("F12"),
+        Some(key(KeyCode::F(12), KeyModifiers::NONE))
+    );
+}
+
+#[test]
+fn parse_plus() {
+    assert_eq!(
+        Key::parse("ctrl++"),
+        Some(key(KeyCode::Char('+'), KeyModifiers::CONTROL))
+    );
+}
+
+#[test]
+fn parse_invalid() {
+    assert_eq!(Key::parse(""), None);
+    assert_eq!(Key::parse("F25"), None);
+    assert_eq!(Key::parse("meta+w"), None);
+    assert_eq!(Key::parse("nokey"), None);
+}
+
+#[test]
+fn shift_is_kept_in_case() {
+    assert_eq!(
+        Key::parse("shift+w"),
+        Some(key(KeyCode::Char('W'), KeyModifiers::NONE))
+    );
+    assert_eq!(
+        Key::parse("shift+Tab"),
+        Some(key(KeyCode::Tab, KeyModifiers::SHIFT))
+    );
+}
+
+#[test]
+fn bindings_on_event() {
+    let mut bindings = Bindings::new();
+    bindings.bind(key(KeyCode::Char('z'), KeyModifiers::NONE), "move_forward");
+    let press = Event::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE));
+    let release = Event::Key(KeyEvent::new_with_kind_and_state(
+        KeyCode::Char('z'),
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+        KeyEventState::NONE,
+    ));
+    assert_eq!(bindings.on(&press), Some("move_forward"));
+    assert_eq!(bindings.on(&release), None);
+    assert_eq!(bindings.on(&Event::FocusGained), None);
+}
+
+#[test]
+fn default_bindings_of_movement() {
+    let ev = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert!(matches!(
+        MovementEvent::<WallScene>::from(ev),
+        MovementEvent::Quit
+    ));
+    let ev = Event::Key(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+    assert!(matches!(
+        MovementEvent::<WallScene>::from(ev),
+        MovementEvent::RotateUp
+    ));
+}
+
+#[test]
+fn movement_event_with_given_bindings() {
+    let mut bindings = Bindings::new();
+    bindings.bind(key(KeyCode::Char('z'), KeyModifiers::NONE), "move_forward");
+    let ev = Event::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE));
+    assert!(matches!(
+        MovementEvent::<WallScene>::bound(ev.clone(), &bindings),
+        MovementEvent::MoveForward
+    ));
+    assert!(matches!(
+        MovementEvent::<WallScene>::from(ev),
+        MovementEvent::None(_)
+    ));
+}
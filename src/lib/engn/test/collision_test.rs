@@ -0,0 +1,188 @@
+use {
+    super::{
+        super::{
+            vec3::{across, dot},
+            *,
+        },
+        assert_vec, WallScene,
+    },
+    crate::math::*,
+    either::Either,
+};
+
+/// Floor `z = 0` with ledge of height 0.3 at `x >= 2`
+struct StepScene;
+
+impl AsScene for StepScene {
+    fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char> {
+        Either::Left(self.trace(cs, inc, dir).unwrap_or(-1.0))
+    }
+
+    fn validate_mv(&self, _cs: &CoordSys, _pos: &Point, _mv: &mut Vector) {}
+
+    fn trace(&self, _cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64> {
+        let mut hits = vec![];
+        if dir[0] > 0.0 && inc[0] < 2.0 {
+            let t = (2.0 - inc[0]) / dir[0];
+            let z = inc[2] + t * dir[2];
+            if (0.0..=0.3).contains(&z) {
+                hits.push(t);
+            }
+        }
+        if dir[2] < 0.0 {
+            let t = (0.3 - inc[2]) / dir[2];
+            if inc[2] >= 0.3 && inc[0] + t * dir[0] >= 2.0 {
+                hits.push(t);
+            }
+            let t = -inc[2] / dir[2];
+            if inc[0] + t * dir[0] < 2.0 {
+                hits.push(t);
+            }
+        }
+        hits.into_iter().filter(|t| *t >= 0.0).reduce(f64::min)
+    }
+}
+
+/// Walls `x = 3` and `y = 3`
+struct CornerScene(WallScene, HypePlane);
+
+impl CornerScene {
+    fn new() -> Self {
+        let wall = HypePlane::new(
+            Entity::new(IdPool::generate_shared()),
+            Point::new(vec![0.0, 3.0, 0.0]),
+            Vector::new(vec![0.0, 1.0, 0.0]),
+            None,
+        )
+        .unwrap();
+        Self(WallScene::new(), wall)
+    }
+}
+
+impl AsScene for CornerScene {
+    fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char> {
+        let dist = [self.0.trace(cs, inc, dir), self.1.collide(cs, inc, dir)]
+            .into_iter()
+            .flatten()
+            .reduce(f64::min);
+        Either::Left(dist.unwrap_or(-1.0))
+    }
+
+    fn validate_mv(&self, _cs: &CoordSys, _pos: &Point, _mv: &mut Vector) {}
+}
+
+fn cs() -> CoordSys {
+    set_biform_identity();
+    CoordSys::new(
+        Point::default(),
+        Basis::new(Matrix::identity(3).to_multicol()).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn estimated_normal() {
+    let (cs, scene) = (cs(), WallScene::new());
+    let hit = scene
+        .hit(&cs, &Point::default(), &Vector::new(vec![1.0, 1.0, 0.5]))
+        .unwrap();
+    assert!(aeq(&hit.t, &3.0));
+    assert_vec(&hit.normal, [-1.0, 0.0, 0.0]);
+    assert!(scene
+        .hit(&cs, &Point::default(), &Vector::new(vec![-1.0, 0.0, 0.0]))
+        .is_none());
+}
+
+#[test]
+fn across_unit() {
+    for d in [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0]] {
+        let (u, v) = across(d).unwrap();
+        assert_eq!((dot(u, d), dot(v, d), dot(u, v)), (0.0, 0.0, 0.0));
+    }
+    assert_eq!(across([f64::NAN, 0.0, 0.0]), None);
+}
+
+#[test]
+fn sweep_stops_before_wall() {
+    let (cs, scene) = (cs(), WallScene::new());
+    let collider = Collider::new(Shape::Sphere { radius: 1.0 });
+    let hit = collider
+        .sweep(
+            &scene,
+            &cs,
+            &Point::default(),
+            &Vector::new(vec![4.0, 0.0, 0.0]),
+        )
+        .unwrap();
+    assert!((hit.t - 1.99 / 4.0).abs() < 1e-9);
+    assert_vec(&hit.normal, [-1.0, 0.0, 0.0]);
+    assert!(collider
+        .sweep(
+            &scene,
+            &cs,
+            &Point::default(),
+            &Vector::new(vec![1.0, 0.0, 0.0])
+        )
+        .is_none());
+}
+
+#[test]
+fn slides_along_wall() {
+    let (cs, scene) = (cs(), WallScene::new());
+    let collider = Collider::new(Shape::Sphere { radius: 1.0 });
+    let mv = Vector::new(vec![4.0, 4.0, 0.0]);
+    let slid = collider.slide(&scene, &cs, &Point::default(), &mv);
+    assert_vec(&slid, [1.99, 4.0, 0.0]);
+
+    let stuck = collider
+        .with_max_slides(0)
+        .slide(&scene, &cs, &Point::default(), &mv);
+    assert_vec(&stuck, [1.99, 1.99, 0.0]);
+}
+
+#[test]
+fn stops_in_corner() {
+    let (cs, scene) = (cs(), CornerScene::new());
+    let collider = Collider::new(Shape::Capsule {
+        radius: 1.0,
+        height: 2.0,
+    });
+    let slid = collider.slide(
+        &scene,
+        &cs,
+        &Point::default(),
+        &Vector::new(vec![4.0, 6.0, 0.0]),
+    );
+    assert_vec(&slid, [1.99, 1.99, 0.0]);
+}
+
+#[test]
+fn steps_up_ledge() {
+    let (cs, scene) = (cs(), StepScene);
+    let pos = Point::new(vec![0.0, 0.0, 0.21]);
+    let mv = Vector::new(vec![3.0, 0.0, 0.0]);
+    let collider = Collider::new(Shape::Sphere { radius: 0.2 });
+    assert_vec(
+        &collider.move_and_slide(&scene, &cs, &pos, &mv),
+        [1.79, 0.0, 0.0],
+    );
+    assert_vec(
+        &collider
+            .with_step_height(0.5)
+            .move_and_slide(&scene, &cs, &pos, &mv),
+        [3.0, 0.0, 0.3],
+    );
+}
+
+#[test]
+fn doesnt_step_up_walls() {
+    let (cs, scene) = (cs(), WallScene::new());
+    let collider = Collider::new(Shape::Sphere { radius: 1.0 }).with_step_height(0.5);
+    let moved = collider.move_and_slide(
+        &scene,
+        &cs,
+        &Point::default(),
+        &Vector::new(vec![4.0, 0.0, 0.0]),
+    );
+    assert_vec(&moved, [1.99, 0.0, 0.0]);
+}
@@ -0,0 +1,185 @@
+use {
+    super::{super::*, WallScene},
+    crate::{conf::*, math::*},
+    either::Either,
+};
+
+/// Scene with floor `z = 0`, optional step `z = 1` where `x >= 1` and optional ceiling `z = 4`
+struct FloorScene {
+    ceiling: bool,
+    step: bool,
+}
+
+impl AsScene for FloorScene {
+    fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char> {
+        match self.trace(cs, inc, dir) {
+            Some(dist) => Either::Left(dist),
+            None => Either::Left(-1.0),
+        }
+    }
+
+    fn validate_mv(&self, _cs: &CoordSys, _pos: &Point, _mv: &mut Vector) {}
+
+    fn trace(&self, _cs: &CoordSys, inc: &Point, dir: &Vector) -> Option<f64> {
+        let floor = match self.step && inc[0] >= 1.0 {
+            true => 1.0,
+            false => 0.0,
+        };
+        if dir[2] < 0.0 {
+            Some((floor - inc[2]) / dir[2])
+        } else if dir[2] > 0.0 && self.ceiling {
+            Some((4.0 - inc[2]) / dir[2])
+        } else {
+            None
+        }
+    }
+}
+
+fn game(z: f64) -> Game<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless> {
+    let mut conf = Conf::default();
+    conf.initpt = Point::new(vec![0.0, 0.0, z]);
+    Game::headless(conf, WallScene::new(), MovementEventSys::new(1.0), (3, 5)).unwrap()
+}
+
+fn simulate(
+    game: &mut Game<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless>,
+    controller: &mut Controller,
+    scene: &FloorScene,
+    intent: Intent,
+    frames: usize,
+) {
+    for _ in 0..frames {
+        controller
+            .step(&game.cs, &mut game.camera, scene, intent, 0.05)
+            .unwrap();
+    }
+}
+
+#[test]
+fn falls_to_ground() {
+    let mut game = game(5.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: false,
+        step: false,
+    };
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 40);
+    assert!(controller.is_grounded());
+    assert!(aeq(&game.camera().pos()[2], &2.0));
+    assert_eq!(controller.velocity()[2], 0.0);
+}
+
+#[test]
+fn falls_without_ground() {
+    let mut game = game(5.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: false,
+        step: false,
+    };
+    let pos = game.camera().pos().clone();
+    controller
+        .step(&game.cs, &mut game.camera, &scene, Intent::default(), 0.05)
+        .unwrap();
+    assert!(!controller.is_grounded());
+    assert!(game.camera().pos()[2] < pos[2]);
+}
+
+#[test]
+fn jumps_and_lands() {
+    let mut game = game(2.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: false,
+        step: false,
+    };
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 1);
+    let jump = Intent {
+        jump: true,
+        ..Intent::default()
+    };
+    simulate(&mut game, &mut controller, &scene, jump, 1);
+    assert!(!controller.is_grounded());
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 5);
+    assert!(game.camera().pos()[2] > 3.0);
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 40);
+    assert!(controller.is_grounded());
+    assert!(aeq(&game.camera().pos()[2], &2.0));
+}
+
+#[test]
+fn jump_stops_at_ceiling() {
+    let mut game = game(2.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: true,
+        step: false,
+    };
+    let jump = Intent {
+        jump: true,
+        ..Intent::default()
+    };
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 1);
+    simulate(&mut game, &mut controller, &scene, jump, 10);
+    assert!(game.camera().pos()[2] < 4.0);
+}
+
+#[test]
+fn speed_is_limited_and_friction_stops() {
+    let mut game = game(2.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: false,
+        step: false,
+    };
+    let forward = Intent {
+        forward: 1.0,
+        ..Intent::default()
+    };
+    simulate(&mut game, &mut controller, &scene, forward, 40);
+    assert!(aeq(&controller.velocity()[0], &6.0));
+    assert!(game.camera().pos()[0] > 5.0);
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 40);
+    assert!(controller.velocity()[0].abs() < 1e-3);
+}
+
+#[test]
+fn crouch_lowers_camera() {
+    let mut game = game(2.0);
+    let mut controller = Controller::new(Motion::default());
+    let scene = FloorScene {
+        ceiling: false,
+        step: false,
+    };
+    let crouch = Intent {
+        crouch: true,
+        ..Intent::default()
+    };
+    simulate(&mut game, &mut controller, &scene, crouch, 2);
+    assert!(controller.is_crouching());
+    assert!(aeq(&game.camera().pos()[2], &1.0));
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 2);
+    assert!(aeq(&game.camera().pos()[2], &2.0));
+}
+
+#[test]
+fn ground_is_taken_after_move() {
+    let scene = FloorScene {
+        ceiling: false,
+        step: true,
+    };
+    let mut game = game(2.0);
+    game.camera.mv(&Vector::new(vec![0.9, 0.0, 0.0])).unwrap();
+    let mut controller = Controller::new(Motion::default());
+    controller.set_velocity(Vector::new(vec![6.0, 0.0, 0.0]));
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 1);
+    assert!(game.camera().pos()[0] > 1.0);
+    assert!(controller.is_grounded());
+    assert!(aeq(&game.camera().pos()[2], &3.0));
+
+    controller.set_velocity(Vector::new(vec![-6.0, 0.0, 0.0]));
+    simulate(&mut game, &mut controller, &scene, Intent::default(), 1);
+    assert!(game.camera().pos()[0] < 1.0);
+    assert!(!controller.is_grounded());
+    assert!(game.camera().pos()[2] < 3.0);
+}
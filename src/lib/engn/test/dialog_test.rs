@@ -0,0 +1,160 @@
+use {
+    super::{super::*, key, wall_game, WallGame, WallScene},
+    crate::conf::*,
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers},
+    std::time::Duration,
+};
+
+#[test]
+fn input_without_modal_is_not_taken() {
+    let mut hud = Hud::new();
+    assert!(!hud.input(&key(KeyCode::Enter)));
+}
+
+#[test]
+fn confirm_toggle_and_enter() {
+    let mut hud = Hud::new();
+    hud.confirm("exit", "Exit?");
+    assert!(hud.is_modal());
+    assert!(hud.input(&key(KeyCode::Right)));
+    assert_eq!(hud.answer("exit"), None);
+    hud.input(&key(KeyCode::Enter));
+    assert!(!hud.is_modal());
+    assert_eq!(hud.answer("exit"), Some(Answer::Confirmed(false)));
+    assert_eq!(hud.answer("exit"), None);
+}
+
+#[test]
+fn confirm_ignores_release() {
+    let mut hud = Hud::new();
+    hud.confirm("exit", "Exit?");
+    let release = KeyEvent::new_with_kind_and_state(
+        KeyCode::Char('n'),
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+        KeyEventState::NONE,
+    );
+    assert!(hud.input(&Event::Key(release)));
+    assert!(hud.is_open("exit"));
+}
+
+#[test]
+fn menu_wraps_selection() {
+    let mut hud = Hud::new();
+    hud.menu("menu", "Menu", vec!["a".into(), "b".into(), "c".into()]);
+    hud.input(&key(KeyCode::Up));
+    hud.input(&key(KeyCode::Enter));
+    assert_eq!(hud.answer("menu"), Some(Answer::Chosen(2)));
+}
+
+#[test]
+fn menu_dismissed() {
+    let mut hud = Hud::new();
+    hud.menu("menu", "Menu", vec!["a".into()]);
+    hud.input(&key(KeyCode::Esc));
+    assert_eq!(hud.answer("menu"), Some(Answer::Dismissed));
+}
+
+#[test]
+fn empty_menu_is_only_dismissed() {
+    let mut hud = Hud::new();
+    hud.menu("menu", "Menu", vec![]);
+    for code in [KeyCode::Up, KeyCode::Down, KeyCode::Enter] {
+        assert!(hud.input(&key(code)));
+    }
+    assert!(hud.is_open("menu"));
+    hud.input(&key(KeyCode::Esc));
+    assert_eq!(hud.answer("menu"), Some(Answer::Dismissed));
+}
+
+#[test]
+fn menu_panel() {
+    let dialog = Dialog::Menu {
+        title: "Pause".into(),
+        items: vec!["Resume".into(), "Quit".into()],
+        selected: 1,
+    };
+    assert_eq!(
+        dialog.panel().lines,
+        vec!["Pause", "", "  Resume", "> Quit"]
+    );
+}
+
+#[test]
+fn toast_expires() {
+    let mut hud = Hud::new();
+    hud.toast("hi", Duration::from_secs(60));
+    hud.toast("bye", Duration::ZERO);
+    assert!(hud.has_dialogs());
+    assert_eq!(hud.deadline(), Some(Duration::ZERO));
+    hud.expire(Duration::from_secs(1));
+    assert!(hud.has_dialogs());
+    assert!(!hud.is_modal());
+    let mut frame = Frame::new((3, 6));
+    hud.compose(&mut frame);
+    assert_eq!(frame.to_text(), " +--+ \n |hi| \n +--+ ");
+}
+
+#[test]
+fn modal_takes_input_from_game() {
+    let mut game = wall_game((9, 21), vec![key(KeyCode::Char('s'))]);
+    game.hud_mut().confirm("exit", "Exit?");
+    let pos = game.camera().pos.clone();
+    game.run().unwrap();
+    assert_eq!(game.camera().pos, pos);
+    assert!(game.hud().is_modal());
+}
+
+#[test]
+fn pause_menu_quits() {
+    let mut game = wall_game(
+        (9, 21),
+        vec![
+            key(KeyCode::Esc),
+            key(KeyCode::Down),
+            key(KeyCode::Enter),
+            key(KeyCode::Char('s')),
+        ],
+    );
+    game.run().unwrap();
+    assert!(game.hud().is_quitting());
+    assert!(!game.backend().is_closed());
+}
+
+#[test]
+fn pause_menu_resumes() {
+    let mut game = wall_game((9, 21), vec![key(KeyCode::Esc)]);
+    game.run().unwrap();
+    assert!(game.hud().is_open(PAUSE));
+    let text = game.backend().last_frame().unwrap().to_text();
+    assert!(text.contains("> Resume"));
+    game.hud_mut().input(&key(KeyCode::Enter));
+    assert!(!game.hud().is_modal());
+    assert!(!game.hud().is_quitting());
+}
+
+#[test]
+fn ban_does_not_block() {
+    let mut game = wall_game((9, 21), vec![]);
+    game.ban();
+    assert!(game.hud().is_quitting());
+    assert!(game.render().unwrap().to_text().contains("BAN"));
+}
+
+#[test]
+fn toast_expires_by_backend_clock() {
+    let backend = Headless::new((9, 21))
+        .with_step(Duration::from_millis(100))
+        .idle(20);
+    let mut game = WallGame::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap();
+    game.ban();
+    game.run().unwrap();
+    assert!(!game.backend().is_closed());
+    assert_eq!(game.backend().now(), Duration::from_secs(1));
+}
@@ -0,0 +1,207 @@
+use {
+    super::super::*,
+    crate::{
+        conf::*,
+        errs::{GameErr::*, ReErr::*},
+        math::*,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Pos(f64);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Vel(f64);
+
+#[derive(Debug, Clone, PartialEq)]
+struct Name(&'static str);
+
+#[test]
+fn components_of_entities() {
+    let mut world = World::new();
+    let a = world.spawn();
+    let b = world.spawn();
+    assert_eq!(world.insert(a, Pos(1.0)), Ok(None));
+    assert_eq!(world.insert(a, Pos(2.0)), Ok(Some(Pos(1.0))));
+    world.insert(b, Pos(3.0)).unwrap();
+    world.insert(b, Vel(1.0)).unwrap();
+
+    assert_eq!(*world.get::<Pos>(a).unwrap(), Pos(2.0));
+    assert!(world.has::<Vel>(b));
+    assert!(!world.has::<Vel>(a));
+    world.get_mut::<Vel>(b).unwrap().0 = 5.0;
+    assert_eq!(world.remove::<Vel>(b), Some(Vel(5.0)));
+    assert_eq!(world.remove::<Vel>(b), None);
+    assert_eq!(world.remove::<Name>(b), None);
+}
+
+#[test]
+fn despawned_ids_are_stale() {
+    let mut world = World::new();
+    let a = world.spawn();
+    world.insert(a, Pos(1.0)).unwrap();
+    assert!(world.despawn(a));
+    assert!(!world.despawn(a));
+    assert!(!world.is_alive(a));
+
+    let b = world.spawn();
+    assert_eq!(b.index(), a.index());
+    assert_eq!(b.generation(), a.generation() + 1);
+    assert!(world.get::<Pos>(b).is_none());
+    assert!(world.get::<Pos>(a).is_none());
+    assert_eq!(world.insert(a, Pos(2.0)), Err(GameErr(NotAliveEntity(a))));
+    assert_eq!(world.entities(), vec![b]);
+}
+
+#[test]
+fn storage_stays_dense() {
+    let mut world = World::new();
+    let ids = (0..4).map(|_| world.spawn()).collect::<Vec<_>>();
+    for (i, id) in ids.iter().enumerate() {
+        world.insert(*id, Pos(i as f64)).unwrap();
+    }
+    world.despawn(ids[1]);
+    let storage = world.storage::<Pos>().unwrap();
+    assert_eq!(storage.len(), 3);
+    assert_eq!(storage.get(ids[3]), Some(&Pos(3.0)));
+    assert_eq!(storage.get(ids[0]), Some(&Pos(0.0)));
+    assert_eq!(storage.get(ids[1]), None);
+}
+
+#[test]
+fn query_tuples() {
+    let mut world = World::new();
+    for i in 0..3 {
+        let id = world.spawn();
+        world.insert(id, Pos(i as f64)).unwrap();
+        if i > 0 {
+            world.insert(id, Vel(10.0)).unwrap();
+        }
+    }
+    assert_eq!(world.count::<(&Pos,)>(), 3);
+    assert_eq!(world.count::<(&Pos, &Vel)>(), 2);
+    assert_eq!(world.count::<(&Pos, &Name)>(), 0);
+
+    world.query::<(&mut Pos, &Vel)>(|_, (pos, vel)| pos.0 += vel.0);
+    let mut sum = 0.0;
+    world.query::<&Pos>(|_, pos| sum += pos.0);
+    assert_eq!(sum, 23.0);
+}
+
+#[test]
+#[should_panic]
+fn query_same_component_twice() {
+    let mut world = World::new();
+    let id = world.spawn();
+    world.insert(id, Pos(0.0)).unwrap();
+    world.query::<(&mut Pos, &Pos)>(|_, _| ());
+}
+
+#[test]
+fn systems_run_in_order() {
+    let mut world = World::new();
+    let id = world.spawn();
+    world.insert(id, Pos(0.0)).unwrap();
+    world.insert(id, Vel(2.0)).unwrap();
+    world.add_system("move", |world, dt| {
+        world.query::<(&mut Pos, &Vel)>(|_, (pos, vel)| pos.0 += vel.0 * dt);
+        Ok(())
+    });
+    world.add_system("stop", |world, _| {
+        world.query::<(&Pos, &mut Vel)>(|_, (pos, vel)| {
+            if pos.0 >= 2.0 {
+                vel.0 = 0.0
+            }
+        });
+        Ok(())
+    });
+    for _ in 0..4 {
+        world.run_systems(0.5).unwrap();
+    }
+    assert_eq!(*world.get::<Pos>(id).unwrap(), Pos(2.0));
+
+    assert!(world.remove_system("stop"));
+    assert!(!world.remove_system("stop"));
+    world.add_system("fail", move |_, _| Err(GameErr(NotAliveEntity(id))));
+    let err = world.run_systems(0.5).unwrap_err();
+    assert_eq!(err.kind(), &GameErr(NotAliveEntity(id)));
+    assert!(err.report().starts_with("system \"fail\" failed: entity"));
+}
+
+fn wall() -> HypePlane {
+    HypePlane::new(
+        Entity::new(IdPool::generate_shared()),
+        Point::new(vec![3.0, 0.0, 0.0]),
+        Vector::new(vec![1.0, 0.0, 0.0]),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn world_renders_materials() {
+    set_biform_identity();
+    let cs = CoordSys::new(
+        Point::default(),
+        Basis::new(Matrix::identity(3).to_multicol()).unwrap(),
+    )
+    .unwrap();
+    let mut world = World::new();
+    let dir = Vector::new(vec![1.0, 0.0, 0.0]);
+    assert!(world.trace(&cs, &Point::default(), &dir).is_none());
+    let id = world.register(wall());
+    assert!(world.has::<Render>(id));
+    assert_eq!(world.trace(&cs, &Point::default(), &dir), Some(3.0));
+    assert!(world
+        .trace(&cs, &Point::default(), &Vector::new(vec![-1.0, 0.0, 0.0]))
+        .is_none());
+}
+
+#[test]
+fn game_ticks_world() {
+    set_biform_identity();
+    let ticks = Rc::new(RefCell::new(0));
+    let mut world = World::new();
+    world.register(wall());
+    let counter = ticks.clone();
+    world.add_system("count", move |_, _| {
+        *counter.borrow_mut() += 1;
+        Ok(())
+    });
+    let mut game = Game::with_backend(
+        Conf::default(),
+        world,
+        EventQueue::<MovementEvent<World>, World>::new(),
+        Headless::new((3, 5)).idle(3),
+    )
+    .unwrap();
+    game.run().unwrap();
+    assert_eq!(*ticks.borrow(), 3);
+}
+
+#[test]
+fn game_forgets_despawned_entities() {
+    let mut world = World::new();
+    let id = world.spawn();
+    world.add_system("despawn", move |world, _| {
+        world.despawn(id);
+        Ok(())
+    });
+    let mut game = Game::with_backend(
+        Conf::default(),
+        world,
+        EventQueue::<MovementEvent<World>, World>::new(),
+        Headless::new((3, 5)).idle(2),
+    )
+    .unwrap();
+    let trigger = Trigger::new(
+        id,
+        Volume::Sphere { radius: 1.0 },
+        Point::new(vec![0.0, 0.0, 0.0]),
+    );
+    game.triggers_mut().add(trigger);
+    game.run().unwrap();
+    assert!(game.triggers().get(id).is_none());
+    assert!(game.scene.ids_mut().despawned().is_empty());
+}
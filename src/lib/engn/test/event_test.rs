@@ -1,28 +1,29 @@
 use {
-    super::super::*,
+    super::{super::*, WallScene},
     crate::{conf::*, math::*},
+    crossterm::event::Event,
 };
 
 struct EmtpyEvent(i8);
 
-impl From<console::Event> for EmtpyEvent {
-    fn from(value: console::Event) -> Self {
+impl From<Event> for EmtpyEvent {
+    fn from(value: Event) -> Self {
         EmtpyEvent(0)
     }
 }
 
-impl AsEvent<EntityList> for EmtpyEvent {}
+impl AsEvent<WallScene> for EmtpyEvent {}
 
 #[test]
 fn push_to_event_queue() {
-    let mut q = EventQueue::new();
+    let mut q = EventQueue::<EmtpyEvent, WallScene>::new();
     q.push(EmtpyEvent(0));
     assert_eq!(q.events.len(), 1);
 }
 
 #[test]
 fn push_push_to_event_queue() {
-    let mut q = EventQueue::new();
+    let mut q = EventQueue::<EmtpyEvent, WallScene>::new();
     q.push(EmtpyEvent(0));
     q.push(EmtpyEvent(1));
     assert_eq!(q.events.len(), 2);
@@ -30,24 +31,46 @@ fn push_push_to_event_queue() {
 
 #[test]
 fn handle_all_to_event_queue() {
-    let mut game = Game::<EmtpyEvent, EventQueue<EmtpyEvent, EntityList>, EntityList>::default();
-    game.set_entities(EntityList::new());
+    let mut game = Game::headless(
+        Conf::default(),
+        WallScene::new(),
+        EventQueue::<EmtpyEvent, WallScene>::new(),
+        (5, 5),
+    )
+    .unwrap();
     let mut q = EventQueue::new();
     q.push(EmtpyEvent(0));
     q.push(EmtpyEvent(1));
     assert!(q
-        .handle_all(&mut game.camera, game.scene.as_mut().unwrap())
+        .handle_all(
+            &game.cs,
+            &mut game.camera,
+            &mut game.scene,
+            &mut game.hud,
+            &game.input,
+        )
         .is_ok());
 }
 
 #[test]
 fn handle_all_count_to_event_queue() {
-    let mut game = Game::<EmtpyEvent, EventQueue<EmtpyEvent, EntityList>, EntityList>::default();
-    game.set_entities(EntityList::new());
+    let mut game = Game::headless(
+        Conf::default(),
+        WallScene::new(),
+        EventQueue::<EmtpyEvent, WallScene>::new(),
+        (5, 5),
+    )
+    .unwrap();
     let mut q = EventQueue::new();
     q.push(EmtpyEvent(0));
     q.push(EmtpyEvent(1));
-    q.handle_all(&mut game.camera, game.scene.as_mut().unwrap())
-        .unwrap();
+    q.handle_all(
+        &game.cs,
+        &mut game.camera,
+        &mut game.scene,
+        &mut game.hud,
+        &game.input,
+    )
+    .unwrap();
     assert_eq!(q.events.len(), 0);
 }
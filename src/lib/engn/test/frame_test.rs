@@ -0,0 +1,91 @@
+use {
+    super::{super::*, wall_game, WallGame},
+    crate::{conf::*, math::*},
+};
+
+/// `wall_game` whose wall is drawn with `#+-` up to 6 units
+fn charcoal_game() -> WallGame {
+    let mut game = wall_game((3, 5), vec![]);
+    let mut conf = Conf::default();
+    conf.charmap = "#+-".to_string();
+    conf.draw_dist = 6.0;
+    game.reconfigure(conf).unwrap();
+    game
+}
+
+#[test]
+fn headless_frame_size() {
+    let mut game = charcoal_game();
+    assert_eq!(game.render().unwrap().size(), (3, 5));
+}
+
+#[test]
+fn headless_frame_center() {
+    let mut game = charcoal_game();
+    assert_eq!(game.render().unwrap().at(1, 2), '+');
+}
+
+#[test]
+fn headless_frame_sky() {
+    let mut game = charcoal_game();
+    game.camera_mut().rotate_left(12);
+    assert_eq!(game.render().unwrap().to_text(), "-----\n-----\n-----");
+}
+
+#[test]
+fn shoot_as_canvas_does() {
+    let mut game = charcoal_game();
+    let frame = Frame::shoot(
+        game.camera(),
+        &game.cs,
+        game.scene(),
+        game.canvas().charcoal(),
+    );
+    assert_eq!(&frame, game.render().unwrap());
+}
+
+#[test]
+fn frame_to_text() {
+    let mut frame = Frame::new((2, 3));
+    *frame.at_mut(0, 1) = '$';
+    assert_eq!(frame.to_text(), " $ \n   ");
+}
+
+#[test]
+fn frame_to_ansi() {
+    let mut frame = Frame::new((1, 2));
+    *frame.at_mut(0, 1) = '$';
+    assert_eq!(frame.to_ansi(), "\x1b[38;5;232m \x1b[38;5;255m$\x1b[0m");
+}
+
+#[test]
+fn frame_gray_levels() {
+    assert_eq!(
+        (gray_level(' '), gray_level('$'), gray_level('~')),
+        (0, 255, 128)
+    );
+}
+
+#[test]
+fn frame_to_ppm() {
+    let mut frame = Frame::new((1, 2));
+    *frame.at_mut(0, 1) = '$';
+    let ppm = frame.to_ppm(1);
+    assert_eq!(
+        ppm,
+        b"P6\n2 2\n255\n\0\0\0\xff\xff\xff\0\0\0\xff\xff\xff".to_vec()
+    );
+}
+
+#[test]
+fn frame_to_png_header() {
+    let png = Frame::new((2, 3)).to_png(2);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[16..24], &[0, 0, 0, 6, 0, 0, 0, 8]);
+}
+
+#[test]
+fn frame_to_png_checksums() {
+    let png = Frame::new((1, 1)).to_png(1);
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+}
@@ -1,21 +1,31 @@
 use {
-    super::super::*,
+    super::{super::*, WallScene},
     crate::{conf::*, math::*},
+    std::f64::consts::PI,
 };
 
 #[test]
 fn given_vert_fov() {
     let mut conf = Conf::default();
     conf.hfov = Some(1.0);
-    let game = Game::<ConsoleEvent, EventQueue<ConsoleEvent, EntityList>, EntityList>::new(conf);
-    assert_eq!(game.unwrap().camera.hfov, 1.0);
+    let game = Game::<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless>::headless(
+        conf,
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        (5, 5),
+    );
+    assert_eq!(game.unwrap().camera.hfov, PI);
 }
 
 #[test]
 fn computed_vert_fov() {
     let mut conf = Conf::default();
-    conf.wfov = 2.0;
-    conf.wscr = 200;
-    conf.hscr = 100;
-    assert_eq!(conf.comp_hfov(), 1.0);
+    conf.wfov = 0.5;
+    let game = Game::<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless>::headless(
+        conf,
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        (3, 3),
+    );
+    assert!(aeq(&game.unwrap().camera.hfov, &(0.5 * PI)));
 }
@@ -0,0 +1,150 @@
+use {
+    super::{super::*, assert_vec},
+    crate::{
+        errs::{GameErr::*, ReErr::*},
+        math::*,
+    },
+    std::{cell::RefCell, f64::consts::PI, rc::Rc},
+};
+
+fn pt(coord: [f64; 3]) -> Point {
+    Point::new(coord.to_vec())
+}
+
+#[test]
+fn children_follow_parent() {
+    set_biform_identity();
+    let mut graph = SceneGraph::new();
+    let root = graph
+        .add(None, pt([1.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    let child = graph
+        .add(Some(root), pt([0.0, 2.0, 0.0]), Basis::default())
+        .unwrap();
+    let leaf = graph
+        .add(Some(child), pt([0.0, 0.0, 3.0]), Basis::default())
+        .unwrap();
+    assert_vec(&graph.world_pos(leaf).unwrap(), [1.0, 2.0, 3.0]);
+
+    graph
+        .translate(root, &Vector::new(vec![-1.0, 0.0, 1.0]))
+        .unwrap();
+    assert_vec(&graph.world_pos(child).unwrap(), [0.0, 2.0, 1.0]);
+    assert_vec(&graph.world_pos(leaf).unwrap(), [0.0, 2.0, 4.0]);
+    assert_vec(graph.node(leaf).unwrap().pos(), [0.0, 0.0, 3.0]);
+}
+
+#[test]
+fn rotation_of_parent_turns_children() {
+    set_biform_identity();
+    let mut graph = SceneGraph::new();
+    let root = graph
+        .add(None, pt([5.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    let child = graph
+        .add(Some(root), pt([1.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    let leaf = graph
+        .add(Some(child), pt([1.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    graph.planar_rotate(root, 0, 1, PI / 2.0).unwrap();
+    assert_vec(&graph.world_pos(child).unwrap(), [5.0, 1.0, 0.0]);
+    assert_vec(&graph.world_pos(leaf).unwrap(), [5.0, 2.0, 0.0]);
+
+    graph.planar_rotate(child, 0, 1, PI / 2.0).unwrap();
+    assert_vec(&graph.world_pos(leaf).unwrap(), [4.0, 1.0, 0.0]);
+    let basis = graph.world_basis(leaf).unwrap();
+    assert_vec(&basis.decompose(&pt([1.0, 0.0, 0.0])), [-1.0, 0.0, 0.0]);
+}
+
+#[test]
+fn reparenting_keeps_local_transform() {
+    set_biform_identity();
+    let mut graph = SceneGraph::new();
+    let a = graph
+        .add(None, pt([1.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    let b = graph
+        .add(None, pt([0.0, 1.0, 0.0]), Basis::default())
+        .unwrap();
+    let child = graph
+        .add(Some(a), pt([0.0, 0.0, 1.0]), Basis::default())
+        .unwrap();
+    assert_vec(&graph.world_pos(child).unwrap(), [1.0, 0.0, 1.0]);
+
+    graph.set_parent(child, Some(b)).unwrap();
+    assert_vec(&graph.world_pos(child).unwrap(), [0.0, 1.0, 1.0]);
+    assert!(graph.node(a).unwrap().children().is_empty());
+    assert_eq!(graph.node(b).unwrap().children(), &[child]);
+
+    graph.set_parent(child, None).unwrap();
+    assert_vec(&graph.world_pos(child).unwrap(), [0.0, 0.0, 1.0]);
+    assert_eq!(graph.roots(), vec![a, b, child]);
+}
+
+#[test]
+fn invalid_hierarchy() {
+    let mut graph = SceneGraph::new();
+    let root = graph.add(None, pt([0.0; 3]), Basis::default()).unwrap();
+    let child = graph
+        .add(Some(root), pt([0.0; 3]), Basis::default())
+        .unwrap();
+    assert_eq!(
+        graph.set_parent(root, Some(child)),
+        Err(GameErr(CyclicNode {
+            node: root,
+            parent: child
+        }))
+    );
+    assert_eq!(
+        graph.set_parent(root, Some(root)),
+        Err(GameErr(CyclicNode {
+            node: root,
+            parent: root
+        }))
+    );
+
+    assert_eq!(graph.remove(root), Ok(vec![root, child]));
+    assert!(graph.node(child).is_none());
+    assert_eq!(
+        graph.add(Some(root), pt([0.0; 3]), Basis::default()),
+        Err(GameErr(NotExistingNode(root)))
+    );
+    assert_eq!(graph.world_pos(child), Err(GameErr(NotExistingNode(child))));
+}
+
+#[test]
+fn bound_object_is_synced() {
+    set_biform_identity();
+    let object = Rc::new(RefCell::new(
+        HypeEllipse::new(
+            Entity::new(IdPool::generate_shared()),
+            Point::default(),
+            Basis::default(),
+            vec![1.0; 3],
+            None,
+        )
+        .unwrap(),
+    ));
+    let mut graph = SceneGraph::new();
+    let root = graph.add(None, pt([0.0; 3]), Basis::default()).unwrap();
+    let arm = graph
+        .add(Some(root), pt([2.0, 0.0, 0.0]), Basis::default())
+        .unwrap();
+    graph.bind(arm, object.clone()).unwrap();
+    graph.sync().unwrap();
+    assert_vec(object.borrow().pos(), [2.0, 0.0, 0.0]);
+
+    graph.planar_rotate(root, 0, 1, PI).unwrap();
+    graph.sync().unwrap();
+    assert_vec(object.borrow().pos(), [-2.0, 0.0, 0.0]);
+    let basis = Basis::new(object.borrow().dir().clone()).unwrap();
+    assert_vec(&basis.decompose(&pt([1.0, 1.0, 1.0])), [-1.0, -1.0, 1.0]);
+
+    assert!(graph.unbind(arm).unwrap().is_some());
+    graph
+        .translate(root, &Vector::new(vec![0.0, 0.0, 1.0]))
+        .unwrap();
+    graph.sync().unwrap();
+    assert_vec(object.borrow().pos(), [-2.0, 0.0, 0.0]);
+}
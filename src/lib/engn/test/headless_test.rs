@@ -0,0 +1,99 @@
+use {
+    super::{super::*, key, wall_game, WallScene},
+    crate::{
+        conf::*,
+        errs::{GameErr::*, ReErr::*},
+        math::*,
+    },
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers},
+    std::time::Duration,
+};
+
+fn release(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new_with_kind_and_state(
+        code,
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+        KeyEventState::NONE,
+    ))
+}
+
+#[test]
+fn headless_without_events_is_closed() {
+    assert!(Headless::new((3, 5)).is_closed());
+}
+
+#[test]
+fn headless_size() {
+    let mut backend = Headless::new((3, 5));
+    assert_eq!(backend.init().unwrap(), (3, 5));
+}
+
+#[test]
+fn headless_polls_in_order() {
+    let mut backend = Headless::new((3, 5)).with_events(vec![key(KeyCode::Up)]);
+    backend.push(key(KeyCode::Down));
+    assert_eq!(backend.poll(None).unwrap(), Some(key(KeyCode::Up)));
+    assert_eq!(backend.poll(None).unwrap(), Some(key(KeyCode::Down)));
+    assert_eq!(backend.poll(None).unwrap(), None);
+}
+
+#[test]
+fn run_presents_frame_per_event() {
+    let mut game = wall_game((3, 5), vec![key(KeyCode::Left), key(KeyCode::Right)]);
+    game.run().unwrap();
+    assert_eq!(game.backend().frames().len(), 3);
+}
+
+#[test]
+fn empty_screen_is_error() {
+    for size in [(0, 5), (3, 0)] {
+        let game = Game::<MovementEvent<WallScene>, _, _, _>::with_backend(
+            Conf::default(),
+            WallScene::new(),
+            MovementEventSys::new(1.0),
+            Headless::new(size),
+        );
+        assert!(matches!(game.err(), Some(GameErr(EmptyScreen(_)))));
+    }
+}
+
+#[test]
+fn run_moves_camera() {
+    let backend = Headless::new((3, 5))
+        .with_step(Duration::from_millis(500))
+        .with_releases(true)
+        .with_events(vec![key(KeyCode::Char('s'))])
+        .idle(1)
+        .with_events(vec![release(KeyCode::Char('s'))])
+        .idle(1);
+    let mut game = Game::<MovementEvent<WallScene>, _, _, _>::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap();
+    game.run().unwrap();
+    assert_eq!(game.camera().pos(), &Point::new(vec![-1.0, 0.0, 0.0]));
+}
+
+#[test]
+fn headless_clock() {
+    let mut backend = Headless::new((3, 5))
+        .with_step(Duration::from_millis(10))
+        .idle(2);
+    backend.poll(None).unwrap();
+    assert_eq!(backend.now(), Duration::from_millis(10));
+    assert!(!backend.is_closed());
+    assert_eq!(backend.poll(None).unwrap(), None);
+    assert!(backend.is_closed());
+}
+
+#[test]
+fn run_presents_rendered_frame() {
+    let mut game = wall_game((3, 5), vec![key(KeyCode::Char('w'))]);
+    game.run().unwrap();
+    let last = game.backend().last_frame().unwrap().clone();
+    assert_eq!(&last, game.render().unwrap());
+}
@@ -0,0 +1,135 @@
+use {
+    super::{super::*, WallScene},
+    crate::{conf::*, math::*},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers},
+    std::time::Duration,
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn press(ch: char) -> Event {
+    Event::Key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE))
+}
+
+fn release(ch: char) -> Event {
+    Event::Key(KeyEvent::new_with_kind_and_state(
+        KeyCode::Char(ch),
+        KeyModifiers::NONE,
+        KeyEventKind::Release,
+        KeyEventState::NONE,
+    ))
+}
+
+fn w() -> Key {
+    Key::new(KeyCode::Char('w'), KeyModifiers::NONE)
+}
+
+#[test]
+fn held_until_repeat_delay() {
+    let mut input = InputState::new(ms(500), ms(100));
+    input.feed(&press('w'), ms(100));
+    input.advance(ms(100));
+    assert!(input.is_pressed(&w()));
+    input.advance(ms(500));
+    assert!(input.is_held(&w()));
+    assert!(!input.is_pressed(&w()));
+    assert_eq!(input.held_for(&w()), Some(ms(400)));
+    input.advance(ms(600));
+    assert!(!input.is_held(&w()));
+}
+
+#[test]
+fn held_while_repeated() {
+    let mut input = InputState::new(ms(500), ms(100));
+    input.feed(&press('w'), ms(0));
+    input.feed(&press('w'), ms(450));
+    input.advance(ms(540));
+    assert!(input.is_held(&w()));
+    input.advance(ms(550));
+    assert!(!input.is_held(&w()));
+}
+
+#[test]
+fn held_until_release() {
+    let mut input = InputState::default();
+    input.feed(&press('a'), ms(0));
+    input.feed(&release('a'), ms(10));
+    input.feed(&press('w'), ms(20));
+    input.advance(ms(5000));
+    assert!(input.reports_releases());
+    assert!(input.is_held(&w()));
+    input.feed(&release('w'), ms(5010));
+    assert!(!input.is_held(&w()));
+}
+
+#[test]
+fn frame_time() {
+    let mut input = InputState::default();
+    input.advance(ms(10));
+    input.advance(ms(35));
+    assert_eq!(input.dt(), ms(25));
+    assert_eq!(input.now(), ms(35));
+}
+
+#[test]
+fn focus_lost_releases() {
+    let mut input = InputState::default();
+    input.feed(&press('w'), ms(0));
+    input.feed(&Event::FocusLost, ms(1));
+    assert!(input.held().is_empty());
+}
+
+#[test]
+fn action_held() {
+    let mut input = InputState::default();
+    input.feed(&press('w'), ms(0));
+    input.advance(ms(200));
+    let bindings = Bindings::default();
+    assert!(input.is_action_held(&bindings, "move_forward"));
+    assert!(!input.is_action_held(&bindings, "move_back"));
+    assert_eq!(
+        input.action_held_for(&bindings, "move_forward"),
+        Some(ms(200))
+    );
+}
+
+#[test]
+fn movement_without_releases_stops_after_delay() {
+    let backend = Headless::new((3, 5))
+        .with_step(ms(250))
+        .with_events(vec![press('s')])
+        .idle(3);
+    let mut game = Game::<MovementEvent<WallScene>, _, _, _>::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap();
+    game.run().unwrap();
+    assert_eq!(game.camera().pos(), &Point::new(vec![-0.5, 0.0, 0.0]));
+}
+
+#[test]
+fn rotation_key_tap_is_single_step() {
+    let backend = Headless::new((3, 5))
+        .with_step(ms(100))
+        .with_events(vec![Event::Key(KeyEvent::new(
+            KeyCode::Left,
+            KeyModifiers::NONE,
+        ))])
+        .idle(4);
+    let mut game = Game::<MovementEvent<WallScene>, _, _, _>::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap();
+    game.run().unwrap();
+    let dir = game.camera().dir();
+    let angle = std::f64::consts::PI / 12.0;
+    assert!(aeq(&dir.0, &angle.cos()) && aeq(&dir.1, &angle.sin()));
+}
@@ -1,89 +1,198 @@
 use std::cell::RefCell;
 use {
     super::super::*,
-    crate::{conf::*, engn::*, math::*},
-    std::{any::Any, rc::Rc},
+    crate::{
+        conf::*,
+        engn::*,
+        errs::{GameErr::*, ReErr::*},
+        math::*,
+    },
+    std::{
+        any::Any,
+        rc::Rc,
+        sync::{Arc, Mutex},
+    },
 };
 
 #[test]
 fn id_generate() {
-    let mut id_pool = IdPool::new();
-    id_pool.generate();
-    assert_eq!(id_pool.len(), 1);
+    let mut ids = IdPool::new();
+    let id = ids.generate();
+    assert_ne!(id, ids.generate());
+    assert_eq!(ids.len(), 2);
+}
+
+#[test]
+fn id_recycle() {
+    let mut ids = IdPool::new();
+    let a = ids.generate();
+    let b = ids.generate();
+    assert!(ids.release(a));
+    assert!(!ids.release(a));
+    assert!(!ids.is_alive(a));
+
+    let c = ids.generate();
+    assert_eq!(c.index(), a.index());
+    assert_eq!(c.generation(), a.generation() + 1);
+    assert_ne!(c, a);
+    assert_eq!(ids.ids(), vec![c, b]);
+    assert_eq!(ids.despawned(), vec![a]);
+    assert!(ids.despawned().is_empty());
+
+    ids.reset();
+    assert!(ids.is_empty());
+    assert!(!ids.is_alive(b));
+}
+
+#[test]
+fn id_lookup() {
+    let mut ids = IdPool::new();
+    let id = ids.generate();
+    let uuid = ids.uuid(id).unwrap();
+    assert_eq!(ids.lookup(&uuid), Some(id));
+    ids.release(id);
+    assert_eq!(ids.uuid(id), None);
+    assert_eq!(ids.lookup(&uuid), None);
+}
+
+#[test]
+fn id_pool_is_shared() {
+    let handles = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                (0..100)
+                    .map(|_| IdPool::generate_shared())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut all = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect::<Vec<_>>();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), 400);
+}
+
+#[test]
+fn shared_ids_are_released() {
+    let (a, b) = (IdPool::generate_shared(), IdPool::generate_shared());
+    let mut list = EntityList::with_ids(IdPool::shared());
+    list.append(Rc::new(RefCell::new(Entity::new(a))));
+    list.remove(a);
+    assert!(IdPool::release_shared(b));
+    assert!(!IdPool::release_shared(a));
+    let ids = IdPool::shared();
+    let ids = ids.lock().unwrap();
+    assert!(!ids.is_alive(a) && !ids.is_alive(b));
 }
 
 #[test]
 fn entity_first_prop() {
-    let mut id_pool = IdPool::new();
-    let mut core = Entity::new(id_pool.generate());
+    let mut core = Entity::new(IdPool::generate_shared());
     core.set_prop("drawdist", Box::new(10.0));
-    assert_eq!(
-        core.get_prop("drawdist")
-            .unwrap()
-            .downcast_ref::<f64>()
-            .unwrap(),
-        &10.0
-    );
+    assert_eq!(core.get_prop::<f64>("drawdist").unwrap(), &10.0);
 }
 
 #[test]
 fn entity_second_prop() {
-    let mut id_pool = IdPool::new();
-    let mut core = Entity::new(id_pool.generate());
+    let mut core = Entity::new(IdPool::generate_shared());
     core.set_prop("drawdist", Box::new(10.0));
     core.set_prop("drawdist", Box::new(20.0));
+    assert_eq!(core.get_prop::<f64>("drawdist").unwrap(), &20.0);
+}
+
+#[test]
+fn entity_prop_errors() {
+    let mut core = Entity::new(IdPool::generate_shared());
+    assert_eq!(
+        core.get_prop::<f64>("hp"),
+        Err(GameErr(NotInitializedProp("hp".to_string())))
+    );
+    core.set_prop("hp", Box::new(10_u32));
     assert_eq!(
-        core.get_prop("drawdist")
-            .unwrap()
-            .downcast_ref::<f64>()
-            .unwrap(),
-        &20.0
+        core.get_prop::<f64>("hp"),
+        Err(GameErr(InvalidPropType {
+            key: "hp".to_string(),
+            expected: "f64"
+        }))
     );
+    core.del_prop("hp");
+    assert!(core.prop("hp").is_err());
+}
+
+#[test]
+fn entity_update_prop() {
+    let mut core = Entity::new(IdPool::generate_shared());
+    let key = String::from("name");
+    core.set_prop(&key, Box::new(String::from("wall")));
+    core.get_prop_mut::<String>(&key).unwrap().push('s');
+    let len = core
+        .update_prop(&key, |name: &mut String| {
+            name.insert_str(0, "big ");
+            name.len()
+        })
+        .unwrap();
+    assert_eq!(len, 9);
+
+    let core: Box<dyn AsEntity> = Box::new(core);
+    assert_eq!(core.get_prop::<String>("name").unwrap(), "big walls");
+    assert!(core["name"].is::<String>());
 }
 
 #[test]
 fn entity_list_get() {
     set_biform_identity();
     let mut list = EntityList::new();
-    let id = IdPool::new().generate();
+    let id = IdPool::generate_shared();
     list.append(Rc::new(RefCell::new(
         HypePlane::new(
-            Entity::new(id.clone()),
+            Entity::new(id),
             Point::new(vec![1.0, 1.0, 1.0]),
             Vector::new(vec![1.0, 1.0, 1.0]),
+            None,
         )
         .unwrap(),
     )));
-    assert_eq!(
-        list.get(&id).unwrap().borrow().collide(
-            &CoordSys::default(),
-            &Point::new(vec![2.0, 2.0, 2.0]),
-            &Vector::new(vec![-1.0, -1.0, -1.0])
-        ),
-        1.0
-    );
+    assert_eq!(list.get(id).unwrap().borrow().id(), id);
+}
+
+#[test]
+fn entity_list_remove() {
+    let ids = Arc::new(Mutex::new(IdPool::new()));
+    let mut list = EntityList::with_ids(ids.clone());
+    let a = ids.lock().unwrap().generate();
+    let b = ids.lock().unwrap().generate();
+    list.append(Rc::new(RefCell::new(Entity::new(a))));
+    list.append(Rc::new(RefCell::new(Entity::new(b))));
+
+    list.remove(a);
+    assert!(list.get(a).is_none());
+    assert!(list.get(b).is_some());
+    let mut ids = ids.lock().unwrap();
+    assert!(!ids.is_alive(a));
+    assert_eq!(ids.despawned(), vec![a]);
 }
 
 #[test]
 fn hype_plane_pos() {
-    let mut id_pool = IdPool::new();
-    let mut core = Entity::new(id_pool.generate());
+    let mut core = Entity::new(IdPool::generate_shared());
 
     let pos = Point::new(vec![1.0, 1.0, 1.0]);
     let dir = Vector::new(vec![1.0, 1.0, 1.0]);
-    let mut hype = HypePlane::new(core, pos, dir).unwrap();
+    let mut hype = HypePlane::new(core, pos, dir, None).unwrap();
     let pos = Point::new(vec![1.0, 1.0, 1.0]);
     assert_eq!(hype.initpt, pos);
 }
 
 #[test]
 fn hype_plane_mv_pos() {
-    let mut id_pool = IdPool::new();
-    let mut core = Entity::new(id_pool.generate());
+    let mut core = Entity::new(IdPool::generate_shared());
 
     let pos = Point::new(vec![1.0, 1.0, 1.0]);
     let dir = Vector::new(vec![1.0, 1.0, 1.0]);
-    let mut hype = HypePlane::new(core, pos, dir).unwrap();
+    let mut hype = HypePlane::new(core, pos, dir, None).unwrap();
     let mv = Vector::new(vec![2.0, 2.0, 2.0]);
     hype.mv(&mv).unwrap();
 
@@ -94,54 +203,54 @@ fn hype_plane_mv_pos() {
 #[test]
 fn plane_straight_collision() {
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let plane = HypePlane::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Vector::new(vec![1.0, 0.0, 0.0]),
+        None,
     )
     .unwrap();
     let dist = plane.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 0.0, 0.0]));
-    assert_eq!(dist, 3.0);
+    assert_eq!(dist, Some(3.0));
 }
 
 #[test]
 fn curve_plane_straight_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let plane = HypePlane::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Vector::new(vec![1.0, 1.0, 0.0]),
+        None,
     )
     .unwrap();
     let dist = plane.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 0.0, 0.0]));
-    assert_eq!(dist, 3.0);
+    assert_eq!(dist, Some(3.0));
 }
 
 #[test]
 fn straight_plane_curve_collision() {
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let plane = HypePlane::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Vector::new(vec![1.0, 0.0, 0.0]),
+        None,
     )
     .unwrap();
     let dist = plane.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 1.0, 0.0]));
-    assert_eq!(dist, 3.0);
+    assert_eq!(dist, Some(3.0));
 }
 
 #[test]
 fn horizontal_plane_curve_collision() {
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let plane = HypePlane::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::default(),
         Vector::new(vec![0.0, 0.0, 1.0]),
+        None,
     )
     .unwrap();
     let dist = plane.collide(
@@ -149,17 +258,17 @@ fn horizontal_plane_curve_collision() {
         &Point::new(vec![0.0, 0.0, 1.0]),
         &Vector::new(vec![3.0, -1.0, -2.0]),
     );
-    assert_eq!(dist, 0.5);
+    assert_eq!(dist, Some(0.5));
 }
 
 #[test]
 fn horizontal_plane_no_collision() {
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let plane = HypePlane::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::default(),
         Vector::new(vec![0.0, 0.0, 1.0]),
+        None,
     )
     .unwrap();
     let dist = plane.collide(
@@ -167,83 +276,83 @@ fn horizontal_plane_no_collision() {
         &Point::new(vec![0.0, 0.0, 1.0]),
         &Vector::new(vec![3.0, -1.0, 2.0]),
     );
-    assert_eq!(dist, -1.0);
+    assert_eq!(dist, None);
 }
 
 #[test]
 fn hype_ellipse_sphere_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![1.0, 2.0, 3.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 0.0, 0.0]));
-    assert_eq!(dist, 2.0);
+    assert_eq!(dist, Some(2.0));
 }
 
 #[test]
 fn hype_ellipse_sphere_inception_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![3.0, 3.0, 3.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(&cs, &Point::default(), &Vector::new(vec![0.0, 1.0, 0.0]));
-    assert_eq!(dist, 0.0);
+    assert_eq!(dist, Some(0.0));
 }
 
 #[test]
 fn hype_ellipse_sphere_no_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![2.0, 2.0, 2.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 1.0, 0.0]));
-    assert_eq!(dist, -1.0);
+    assert_eq!(dist, None);
 }
 
 #[test]
 fn hype_ellipse_hot_dog_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![4.0, 1.0, 1.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(&cs, &Point::default(), &Vector::new(vec![1.0, 0.0, 0.0]));
-    assert_eq!(dist, 7.0);
+    assert_eq!(dist, Some(7.0));
 }
 
 #[test]
 fn hype_ellipse_hot_dog_curve_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![4.0, 1.0, 1.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(
@@ -251,19 +360,19 @@ fn hype_ellipse_hot_dog_curve_collision() {
         &Point::new(vec![3.0, 2.0, 2.0]),
         &Vector::new(vec![0.0, -1.0, -1.0]),
     );
-    assert_eq!(dist, (8.0_f64.sqrt() - 1.0) / 2.0_f64.sqrt());
+    assert_eq!(dist, Some((8.0_f64.sqrt() - 1.0) / 2.0_f64.sqrt()));
 }
 
 #[test]
 fn hype_ellipse_hot_dog_angled_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![4.0, 1.0, 1.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(
@@ -271,19 +380,19 @@ fn hype_ellipse_hot_dog_angled_collision() {
         &Point::new(vec![3.0, 1.0, 2.0]),
         &Vector::new(vec![0.0, -1.0, -1.0]),
     );
-    assert_eq!(dist, 1.0);
+    assert_eq!(dist, Some(1.0));
 }
 
 #[test]
 fn hype_ellipse_hot_dog_no_collision() {
     set_biform_identity();
     let cs = CoordSys::default();
-    let mut id_pool = IdPool::new();
     let ellipse = HypeEllipse::new(
-        Entity::new(id_pool.generate()),
+        Entity::new(IdPool::generate_shared()),
         Point::new(vec![3.0, 0.0, 0.0]),
         Basis::default(),
         vec![4.0, 1.0, 1.0],
+        None,
     )
     .unwrap();
     let dist = ellipse.collide(
@@ -291,5 +400,5 @@ fn hype_ellipse_hot_dog_no_collision() {
         &Point::new(vec![3.0, 1.0, 2.0]),
         &Vector::new(vec![0.0, -1.0, 1.0]),
     );
-    assert_eq!(dist, -1.0);
+    assert_eq!(dist, None);
 }
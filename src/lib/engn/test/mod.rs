@@ -1,8 +1,113 @@
 #[cfg(test)]
+mod binding_test;
+#[cfg(test)]
 mod camera_test;
 #[cfg(test)]
+mod collision_test;
+#[cfg(test)]
+mod controller_test;
+#[cfg(test)]
+mod dialog_test;
+#[cfg(test)]
+mod ecs_test;
+#[cfg(test)]
 mod event_test;
 #[cfg(test)]
+mod frame_test;
+#[cfg(test)]
 mod game_test;
 #[cfg(test)]
+mod graph_test;
+#[cfg(test)]
+mod headless_test;
+#[cfg(test)]
+mod input_test;
+#[cfg(test)]
 mod material_test;
+#[cfg(test)]
+mod mouse_test;
+#[cfg(test)]
+mod overlay_test;
+#[cfg(test)]
+mod physics_test;
+#[cfg(test)]
+mod profiler_test;
+#[cfg(test)]
+mod random_test;
+#[cfg(test)]
+mod record_test;
+#[cfg(test)]
+mod reload_test;
+#[cfg(test)]
+mod save_test;
+#[cfg(test)]
+mod scene_file_test;
+#[cfg(test)]
+mod trigger_test;
+
+use {
+    super::*,
+    crate::{conf::*, math::*},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    either::Either,
+};
+
+pub(crate) type WallGame = Game<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless>;
+
+/// Scene with the only wall that is plane `x = 3`
+pub(crate) struct WallScene {
+    pub(crate) wall: HypePlane,
+}
+
+impl WallScene {
+    pub(crate) fn new() -> Self {
+        set_biform_identity();
+        Self {
+            wall: HypePlane::new(
+                Entity::new(IdPool::generate_shared()),
+                Point::new(vec![3.0, 0.0, 0.0]),
+                Vector::new(vec![1.0, 0.0, 0.0]),
+                None,
+            )
+            .unwrap(),
+        }
+    }
+}
+
+impl AsScene for WallScene {
+    fn collide(&self, cs: &CoordSys, inc: &Point, dir: &Vector) -> Either<f64, char> {
+        match self.wall.collide(cs, inc, dir) {
+            Some(dist) => Either::Left(dist),
+            None => Either::Left(-1.0),
+        }
+    }
+
+    fn validate_mv(&self, _cs: &CoordSys, _pos: &Point, _mv: &mut Vector) {}
+}
+
+/// Game in `WallScene` with picture of `size` obtaining the given events
+pub(crate) fn wall_game(size: (usize, usize), events: Vec<Event>) -> WallGame {
+    Game::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        Headless::new(size).with_events(events),
+    )
+    .unwrap()
+}
+
+/// Press of key without modifiers
+pub(crate) fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+pub(crate) fn assert_vec(vec: &Vector, expected: [f64; 3]) {
+    for i in 0..3 {
+        assert!(
+            (vec[i] - expected[i]).abs() < 1e-6,
+            "{:?} != {:?}",
+            vec,
+            expected
+        );
+    }
+}
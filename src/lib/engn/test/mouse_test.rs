@@ -0,0 +1,88 @@
+use {
+    super::{super::*, WallScene},
+    crate::conf::*,
+    crossterm::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+};
+
+fn mouse(kind: MouseEventKind, row: u16, column: u16) -> Event {
+    Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    })
+}
+
+#[test]
+fn mouse_input_from_events() {
+    let ev = mouse(MouseEventKind::Down(MouseButton::Left), 2, 3);
+    assert_eq!(
+        MouseInput::from_event(&ev),
+        Some(MouseInput::Click {
+            button: MouseButton::Left,
+            pos: (2, 3)
+        })
+    );
+    let ev = mouse(MouseEventKind::ScrollDown, 2, 3);
+    assert_eq!(
+        MouseInput::from_event(&ev),
+        Some(MouseInput::Scroll {
+            lines: -1,
+            pos: (2, 3)
+        })
+    );
+    let ev = mouse(MouseEventKind::Drag(MouseButton::Right), 4, 5);
+    assert_eq!(MouseInput::from_event(&ev).unwrap().pos(), (4, 5));
+    assert_eq!(MouseInput::from_event(&Event::FocusLost), None);
+}
+
+#[test]
+fn look_relative_motion() {
+    let mut look = MouseLook::new(1.0);
+    assert_eq!(look.look((5, 5)), (0, 0));
+    assert_eq!(look.look((4, 8)), (-2, 3));
+    look.reset();
+    assert_eq!(look.look((0, 0)), (0, 0));
+}
+
+#[test]
+fn look_accumulates_fractions() {
+    let mut look = MouseLook::new(0.4);
+    look.look((0, 0));
+    assert_eq!(look.look((0, 2)), (0, 0));
+    assert_eq!(look.look((0, 3)), (0, 1));
+    assert_eq!(look.look((0, 1)), (0, 0));
+}
+
+#[test]
+fn mouse_rotates_camera() {
+    let mut game = Game::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        Headless::new((3, 5)).with_events(vec![
+            mouse(MouseEventKind::Moved, 1, 10),
+            mouse(MouseEventKind::Moved, 1, 13),
+        ]),
+    )
+    .unwrap();
+    game.run().unwrap();
+    let dir = game.camera().dir();
+    assert!(dir.0 > 0.0 && dir.1 < 0.0);
+}
+
+#[test]
+fn zero_sensitivity_disables_look() {
+    let mut game = Game::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0).with_sensitivity(0.0),
+        Headless::new((3, 5)).with_events(vec![
+            mouse(MouseEventKind::Moved, 1, 10),
+            mouse(MouseEventKind::Moved, 1, 13),
+        ]),
+    )
+    .unwrap();
+    game.run().unwrap();
+    assert_eq!(game.camera().dir(), (1.0, 0.0));
+}
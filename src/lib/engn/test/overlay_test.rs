@@ -0,0 +1,155 @@
+use {
+    super::{super::*, WallScene},
+    crate::{conf::*, math::*},
+};
+
+fn frame_with(anchor: Anchor, widget: impl AsWidget) -> String {
+    let mut hud = Hud::new();
+    hud.add("widget", anchor, widget);
+    let mut frame = Frame::new((3, 5));
+    hud.compose(&mut frame);
+    frame.to_text()
+}
+
+#[test]
+fn anchor_origins() {
+    let area = (5, 9);
+    assert_eq!(Anchor::TopLeft.origin(area, (1, 3)), (0, 0));
+    assert_eq!(Anchor::Center.origin(area, (1, 3)), (2, 3));
+    assert_eq!(Anchor::BottomRight.origin(area, (1, 3)), (4, 6));
+}
+
+#[test]
+fn label_top_left() {
+    assert_eq!(
+        frame_with(Anchor::TopLeft, Label::new("ab")),
+        "ab   \n     \n     "
+    );
+}
+
+#[test]
+fn label_bottom() {
+    assert_eq!(
+        frame_with(Anchor::Bottom, Label::new("ab")),
+        "     \n     \n ab  "
+    );
+}
+
+#[test]
+fn label_cut_by_frame() {
+    assert_eq!(
+        frame_with(Anchor::Right, Label::new("abcdefg")),
+        "     \ncdefg\n     "
+    );
+}
+
+#[test]
+fn crosshair_center() {
+    assert_eq!(
+        frame_with(Anchor::Center, Crosshair::new('+')),
+        "     \n  +  \n     "
+    );
+}
+
+#[test]
+fn bar_filled() {
+    assert_eq!(Bar::new("HP", 3.0, 4.0, 4).text(), "HP [### ]");
+}
+
+#[test]
+fn bar_overflow() {
+    assert_eq!(Bar::new("HP", 7.0, 4.0, 2).text(), "HP [##]");
+}
+
+#[test]
+fn panel_with_border() {
+    assert_eq!(
+        frame_with(Anchor::TopLeft, Panel::new(vec!["a".to_string()], true)),
+        "+-+  \n|a|  \n+-+  "
+    );
+}
+
+#[test]
+fn hidden_widget_not_drawn() {
+    let mut hud = Hud::new();
+    hud.add("label", Anchor::TopLeft, Label::new("ab"));
+    hud.toggle("label");
+    let mut frame = Frame::new((1, 2));
+    hud.compose(&mut frame);
+    assert_eq!(frame.to_text(), "  ");
+}
+
+#[test]
+fn shifted_widget() {
+    let mut hud = Hud::new();
+    hud.add("label", Anchor::TopLeft, Label::new("a"));
+    hud.shift("label", (1, 1));
+    let mut frame = Frame::new((2, 2));
+    hud.compose(&mut frame);
+    assert_eq!(frame.to_text(), "  \n a");
+}
+
+#[test]
+fn widget_typed_access() {
+    let mut hud = Hud::new();
+    hud.add("score", Anchor::TopLeft, Label::new("0"));
+    hud.get_mut::<Label>("score").unwrap().set("10");
+    assert_eq!(hud.get::<Label>("score").unwrap().text, "10");
+    assert!(hud.get::<Bar>("score").is_none());
+}
+
+#[test]
+fn game_renders_hud() {
+    let mut game = Game::headless(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        (3, 5),
+    )
+    .unwrap();
+    game.hud_mut()
+        .add("crosshair", Anchor::Center, Crosshair::new('X'));
+    assert_eq!(game.render().unwrap().at(1, 2), 'X');
+}
+
+#[test]
+fn minimap_walls_and_facing() {
+    let scene = WallScene::new();
+    let camera = Camera::new(Point::default(), 2, 1.0, 1.0, (3, 3), 10.0);
+    let mut map = Minimap::new((3, 9), 1.0);
+    map.survey(&CoordSys::default(), &camera, &scene);
+    assert_eq!(
+        (0..3)
+            .map(|r| (0..9).map(|c| map.at(r, c)).collect::<String>())
+            .collect::<Vec<_>>(),
+        vec![".......# ", "....>..# ", ".......# "]
+    );
+}
+
+#[test]
+fn minimap_of_zero_size() {
+    let camera = Camera::new(Point::default(), 2, 1.0, 1.0, (3, 3), 10.0);
+    for size in [(0, 0), (0, 3), (3, 0)] {
+        let mut map = Minimap::new(size, 1.0);
+        map.survey(&CoordSys::default(), &camera, &WallScene::new());
+        assert_eq!(map.size(), (size.0 + 2, size.1 + 2));
+    }
+}
+
+#[test]
+fn minimap_toggled_by_key() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let m = Event::Key(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE));
+    let mut game = Game::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        Headless::new((3, 5)).with_events(vec![m]),
+    )
+    .unwrap();
+    game.hud_mut()
+        .add(MINIMAP, Anchor::TopRight, Minimap::new((1, 1), 1.0));
+    game.run().unwrap();
+    assert!(!game.hud().is_visible(MINIMAP));
+}
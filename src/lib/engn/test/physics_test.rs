@@ -0,0 +1,212 @@
+use {
+    super::{super::*, assert_vec, WallScene},
+    crate::{conf::*, math::*},
+    std::{cell::RefCell, rc::Rc, time::Duration},
+};
+
+fn ground() -> RigidBody {
+    RigidBody::fixed(
+        Volume::Plane {
+            normal: Vector::new(vec![0.0, 0.0, 1.0]),
+        },
+        Point::default(),
+    )
+}
+
+fn ball(z: f64) -> RigidBody {
+    RigidBody::new(
+        Volume::Sphere { radius: 1.0 },
+        Point::new(vec![0.0, 0.0, z]),
+        1.0,
+    )
+}
+
+/// Steps world with frames of 1/30 s for `secs` seconds
+fn simulate(world: &mut PhysicsWorld, secs: f64) {
+    for _ in 0..(secs * 30.0).round() as usize {
+        world.step(1.0 / 30.0).unwrap();
+    }
+}
+
+#[test]
+fn ball_rests_on_ground() {
+    let mut world = PhysicsWorld::new();
+    world.add(ground());
+    let id = world.add(ball(5.0).with_restitution(0.0));
+    for _ in 0..120 {
+        world.step(1.0 / 30.0).unwrap();
+    }
+    let body = world.body(id).unwrap();
+    assert!((body.pos[2] - 1.0).abs() < 0.01);
+    assert!(body.velocity[2].abs() < 0.1);
+    assert_eq!(world.contacts().len(), 1);
+}
+
+#[test]
+fn ball_bounces() {
+    let mut world = PhysicsWorld::new().with_gravity(Vector::new(vec![0.0; 3]));
+    world.add(ground().with_restitution(1.0));
+    let id = world.add(
+        ball(1.5)
+            .with_restitution(1.0)
+            .with_velocity(Vector::new(vec![0.0, 0.0, -2.0])),
+    );
+    simulate(&mut world, 0.5);
+    let body = world.body(id).unwrap();
+    assert!((body.velocity[2] - 2.0).abs() < 1e-9);
+    assert!(body.pos[2] > 1.0);
+}
+
+#[test]
+fn long_step_is_cut() {
+    let mut world = PhysicsWorld::new()
+        .with_gravity(Vector::new(vec![0.0; 3]))
+        .with_max_step(0.1);
+    let id = world.add(ball(0.0).with_velocity(Vector::new(vec![1.0, 0.0, 0.0])));
+    world.step(3600.0).unwrap();
+    assert_vec(
+        &world.body(id).unwrap().pos,
+        [0.1 * MAX_SUBSTEPS as f64, 0.0, 0.0],
+    );
+}
+
+#[test]
+fn equal_balls_exchange_velocities() {
+    let mut world = PhysicsWorld::new().with_gravity(Vector::new(vec![0.0; 3]));
+    let a = world.add(
+        RigidBody::new(Volume::Sphere { radius: 1.0 }, Point::default(), 2.0)
+            .with_restitution(1.0)
+            .with_velocity(Vector::new(vec![1.0, 0.0, 0.0])),
+    );
+    let b = world.add(
+        RigidBody::new(
+            Volume::Sphere { radius: 1.0 },
+            Point::new(vec![2.5, 0.0, 0.0]),
+            2.0,
+        )
+        .with_restitution(1.0),
+    );
+    simulate(&mut world, 1.0);
+    assert!(world.body(a).unwrap().velocity[0].abs() < 1e-9);
+    assert!((world.body(b).unwrap().velocity[0] - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn box_rests_on_box() {
+    let mut world = PhysicsWorld::new();
+    let half = Vector::new(vec![1.0, 1.0, 1.0]);
+    world.add(RigidBody::fixed(
+        Volume::Cuboid { half: half.clone() },
+        Point::default(),
+    ));
+    let id = world.add(
+        RigidBody::new(
+            Volume::Cuboid { half },
+            Point::new(vec![0.5, 0.0, 4.0]),
+            1.0,
+        )
+        .with_restitution(0.0),
+    );
+    simulate(&mut world, 2.0);
+    let body = world.body(id).unwrap();
+    assert!((body.pos[2] - 2.0).abs() < 0.01);
+    assert!(aeq(&body.pos[0], &0.5));
+}
+
+#[test]
+fn ball_hits_box_side() {
+    let mut world = PhysicsWorld::new().with_gravity(Vector::new(vec![0.0; 3]));
+    world.add(RigidBody::fixed(
+        Volume::Cuboid {
+            half: Vector::new(vec![1.0, 1.0, 1.0]),
+        },
+        Point::default(),
+    ));
+    let id = world.add(
+        RigidBody::new(
+            Volume::Sphere { radius: 0.5 },
+            Point::new(vec![3.0, 0.0, 0.0]),
+            1.0,
+        )
+        .with_restitution(1.0)
+        .with_velocity(Vector::new(vec![-2.0, 0.0, 0.0])),
+    );
+    let mut contacts = vec![];
+    for _ in 0..30 {
+        world.step(1.0 / 30.0).unwrap();
+        contacts.extend(world.contacts().iter().cloned());
+    }
+    assert_vec(&contacts[0].normal, [1.0, 0.0, 0.0]);
+    assert!((world.body(id).unwrap().velocity[0] - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn friction_spins_ball() {
+    let mut world = PhysicsWorld::new();
+    world.add(ground());
+    let id = world.add(ball(1.0).with_velocity(Vector::new(vec![3.0, 0.0, 0.0])));
+    simulate(&mut world, 0.5);
+    let body = world.body(id).unwrap();
+    assert!(body.velocity[0] < 3.0);
+    // rolling along x turns the ball about y
+    assert!(body.angular[1] > 0.0);
+}
+
+#[test]
+fn fixed_and_removed_bodies() {
+    let mut world = PhysicsWorld::new();
+    let fixed = world.add(ground());
+    let id = world.add(ball(5.0));
+    assert!(world.body(fixed).unwrap().is_fixed());
+    assert_eq!(world.body(fixed).unwrap().mass(), f64::INFINITY);
+    assert!(world.remove(id).is_some());
+    assert!(world.body(id).is_none());
+    simulate(&mut world, 1.0);
+    assert_eq!(world.bodies().count(), 1);
+    assert_vec(&world.body(fixed).unwrap().pos, [0.0; 3]);
+}
+
+#[test]
+fn bound_object_follows_body() {
+    set_biform_identity();
+    let object = Rc::new(RefCell::new(
+        HypeEllipse::new(
+            Entity::new(IdPool::generate_shared()),
+            Point::default(),
+            Basis::new(Matrix::identity(3).to_multicol()).unwrap(),
+            vec![1.0; 3],
+            None,
+        )
+        .unwrap(),
+    ));
+    let mut world = PhysicsWorld::new().with_gravity(Vector::new(vec![0.0; 3]));
+    world.add(
+        ball(0.0)
+            .with_velocity(Vector::new(vec![1.0, 0.0, 0.0]))
+            .with_object(object.clone()),
+    );
+    simulate(&mut world, 2.0);
+    assert_vec(object.borrow().pos(), [2.0, 0.0, 0.0]);
+}
+
+#[test]
+fn game_steps_physics() {
+    let mut world = PhysicsWorld::new();
+    world.add(ground());
+    let id = world.add(ball(5.0));
+    let backend = Headless::new((3, 5))
+        .with_step(Duration::from_millis(100))
+        .idle(10);
+    let mut game: Game<MovementEvent<WallScene>, _, _, _> = Game::with_backend(
+        Conf::default(),
+        WallScene::new(),
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap()
+    .with_physics(world);
+    game.run().unwrap();
+    let body = game.physics().unwrap().body(id).unwrap();
+    assert!(body.pos[2] < 5.0);
+    assert!(body.pos[2] > 0.9);
+}
@@ -0,0 +1,128 @@
+use {
+    super::super::*,
+    crate::{conf::*, math::*},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers},
+    std::{
+        fs,
+        time::{Duration, Instant},
+    },
+    toml::{Table, Value},
+};
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn plane(x: f64) -> HypePlane {
+    HypePlane::new(
+        Entity::new(IdPool::generate_shared()),
+        Point::new(vec![x, 0.0, 0.0]),
+        Vector::new(vec![1.0, 0.0, 0.0]),
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn frames_are_aggregated() {
+    let start = Instant::now();
+    let mut profiler = Profiler::starting(start);
+    profiler.record(FramePhase::Update, ms(4));
+    profiler.record(FramePhase::Draw, ms(2));
+    profiler.record(FramePhase::Update, ms(2));
+    profiler.counters().count_rays(15);
+    profiler.counters().count_collisions(30);
+    profiler.finish(start + ms(10));
+    profiler.record(FramePhase::Input, ms(1));
+    profiler.finish(start + ms(30));
+
+    assert_eq!(profiler.frames(), 2);
+    let last = profiler.last().unwrap();
+    assert_eq!((last.busy(), last.interval, last.rays), (ms(1), ms(20), 0));
+    let first = profiler.history().next().unwrap();
+    assert_eq!(first.phase(FramePhase::Update), ms(6));
+    assert_eq!((first.rays, first.collisions), (15, 30));
+    assert!((profiler.fps() - 200.0 / 3.0).abs() < 1e-9);
+
+    let summary = profiler.summary();
+    assert_eq!(summary["frames"], Value::Integer(2));
+    assert_eq!(summary["rays_per_frame"], Value::Float(7.5));
+    assert_eq!(summary["collisions_per_frame"], Value::Float(15.0));
+    let update = summary["phases"]["update"].as_table().unwrap();
+    assert_eq!(update["mean_ms"], Value::Float(3.0));
+    assert_eq!(update["max_ms"], Value::Float(6.0));
+
+    let mut stats = StatsOverlay::new(4);
+    stats.refresh(&profiler);
+    let lines = stats.lines();
+    assert_eq!(lines.first().unwrap(), "FPS 66.7  frame 1.0ms");
+    assert_eq!(lines[lines.len() - 2], "fps   #=");
+    assert_eq!(stats.size(), (lines.len() + 2, 23));
+}
+
+#[test]
+fn history_is_limited() {
+    let start = Instant::now();
+    let mut profiler = Profiler::starting(start);
+    for i in 1..=HISTORY as u64 + 5 {
+        profiler.finish(start + ms(i));
+    }
+    assert_eq!(profiler.frames(), HISTORY as u64 + 5);
+    assert_eq!(profiler.history().count(), HISTORY);
+    assert_eq!(profiler.total().interval, ms(HISTORY as u64 + 5));
+}
+
+#[test]
+fn profilers_count_separately() {
+    let start = Instant::now();
+    let (mut first, mut second) = (Profiler::starting(start), Profiler::starting(start));
+    let counters = first.counters().clone();
+    counters.count_rays(3);
+    counters.count_collisions(4);
+    second.counters().count_rays(7);
+    second.finish(start + ms(1));
+    first.finish(start + ms(1));
+
+    let last = first.last().unwrap();
+    assert_eq!((last.rays, last.collisions), (3, 4));
+    let last = second.last().unwrap();
+    assert_eq!((last.rays, last.collisions), (7, 0));
+}
+
+#[test]
+fn game_is_profiled() {
+    let path = std::env::temp_dir().join(format!("stats-{}.toml", std::process::id()));
+    let mut world = World::new();
+    world.register(plane(3.0));
+    world.register(plane(5.0));
+    let f3 = Event::Key(KeyEvent::new(KeyCode::F(3), KeyModifiers::NONE));
+    let backend = Headless::new((3, 5)).with_events([f3]).idle(2);
+    let mut game = Game::<MovementEvent<World>, MovementEventSys, World, Headless>::with_backend(
+        Conf::default(),
+        world,
+        MovementEventSys::new(1.0),
+        backend,
+    )
+    .unwrap()
+    .with_stats_file(&path);
+    game.hud_mut()
+        .add(STATS, Anchor::TopLeft, StatsOverlay::new(10));
+    game.hud_mut().show(STATS, false);
+    game.run().unwrap();
+
+    assert_eq!(game.profiler().frames(), 4);
+    let last = game.profiler().last().unwrap();
+    assert_eq!((last.rays, last.collisions), (15, 30));
+    assert!(game.hud().is_visible(STATS));
+    let stats = game.hud().get::<StatsOverlay>(STATS).unwrap();
+    assert!(stats.lines()[0].starts_with("FPS"));
+
+    let summary = fs::read_to_string(&path).unwrap().parse::<Table>().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(summary["frames"], Value::Integer(4));
+    assert_eq!(summary["rays_per_frame"], Value::Float(15.0));
+    let phases = summary["phases"].as_table().unwrap();
+    assert!(FramePhase::ALL
+        .iter()
+        .all(|phase| phases.contains_key(phase.name())));
+}
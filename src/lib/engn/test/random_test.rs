@@ -0,0 +1,70 @@
+use {
+    super::super::*,
+    crate::conf::*,
+    rand::{Rng, RngCore},
+};
+
+fn draws(rng: &mut impl RngCore) -> Vec<u64> {
+    (0..4).map(|_| rng.gen_range(0..1000)).collect()
+}
+
+#[test]
+fn streams_are_independent() {
+    let random = Random::new(Some(7));
+    let walls = draws(&mut random.stream("walls"));
+    let other = Random::new(Some(7));
+    draws(&mut other.stream("enemies"));
+    draws(&mut other.clone());
+    assert_eq!(draws(&mut other.stream("walls")), walls);
+    assert_ne!(draws(&mut random.stream("enemies")), walls);
+    assert_ne!(draws(&mut Random::new(Some(8)).stream("walls")), walls);
+
+    let mut stream = random.stream("walls");
+    let next = draws(&mut stream.clone());
+    assert_ne!(next, walls);
+    assert_ne!(draws(&mut stream), next);
+    random.reset();
+    assert_eq!(draws(&mut random.stream("walls")), walls);
+    assert!(Random::new(None).seed() <= i64::MAX as u64);
+}
+
+#[test]
+fn streams_are_reproducible() {
+    let mut stream = Random::new(Some(7)).stream("walls");
+    let numbers = (0..2).map(|_| stream.next_u64()).collect::<Vec<_>>();
+    assert_eq!(numbers, vec![71532025255171404, 3329337956671961390]);
+}
+
+#[test]
+fn game_shares_random() {
+    let mut conf = Conf::default();
+    conf.seed = Some(3);
+    let mut world = World::new();
+    world.add_system("spawn", |world, _| {
+        let x: i32 = world.random().stream("spawn").gen_range(0..100);
+        let id = world.spawn();
+        world.insert(id, x).map(|_| ())
+    });
+    let game = Game::<MovementEvent<World>, MovementEventSys, World, Headless>::headless(
+        conf,
+        world,
+        MovementEventSys::new(1.0),
+        (3, 3),
+    )
+    .unwrap();
+    assert_eq!(game.random().seed(), 3);
+    assert_eq!(game.scene().random().seed(), 3);
+
+    let mut game = game.with_random(Random::new(Some(5)));
+    game.scene.tick(0.1).unwrap();
+    game.scene.tick(0.1).unwrap();
+    let mut spawned = vec![];
+    game.scene.query::<&i32>(|_, x| spawned.push(*x));
+    spawned.sort();
+    let mut stream = Random::new(Some(5)).stream("spawn");
+    let mut expected = (0..2)
+        .map(|_| stream.gen_range(0..100))
+        .collect::<Vec<i32>>();
+    expected.sort();
+    assert_eq!(spawned, expected);
+}
@@ -0,0 +1,134 @@
+use {
+    super::super::*,
+    crate::{
+        conf::*,
+        errs::{GameErr::*, ReErr::*},
+    },
+    crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseEvent,
+        MouseEventKind,
+    },
+    std::time::Duration,
+};
+
+const SCENE: &str = "src/lib/engn/test/scene.toml";
+
+type WorldGame = Game<MovementEvent<World>, MovementEventSys, World, Headless>;
+
+fn key(code: KeyCode, kind: KeyEventKind) -> Event {
+    Event::Key(KeyEvent::new_with_kind_and_state(
+        code,
+        KeyModifiers::NONE,
+        kind,
+        KeyEventState::NONE,
+    ))
+}
+
+fn game(backend: Headless, seed: Option<u64>) -> WorldGame {
+    let mut conf = Conf::default();
+    conf.angle_discr = 4;
+    conf.seed = seed;
+    let es = MovementEventSys::new(1.0).with_controller(Controller::new(Motion::default()));
+    Game::with_backend(conf, load_scene(SCENE).unwrap(), es, backend).unwrap()
+}
+
+#[test]
+fn recording_is_parsed() {
+    let mut recording = Recording::new((3, 5), Some(42));
+    recording.start = 10;
+    recording.polls = vec![
+        Poll::new(Duration::from_millis(20), None),
+        Poll::new(
+            Duration::from_millis(35),
+            Some(key(KeyCode::Char('w'), KeyEventKind::Press)),
+        ),
+        Poll::new(
+            Duration::from_millis(50),
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                column: 4,
+                row: 2,
+                modifiers: KeyModifiers::SHIFT,
+            })),
+        ),
+    ];
+    for format in [SceneFormat::Toml, SceneFormat::Json] {
+        let content = recording.to_string(format).unwrap();
+        assert_eq!(Recording::parse(&content, format).unwrap(), recording);
+    }
+    assert_eq!(recording.events().count(), 2);
+    assert_eq!(recording.polls[1].at(), Duration::from_millis(35));
+
+    let parse = |content: &str| Recording::parse(content, SceneFormat::Toml);
+    assert_eq!(
+        parse("version = 2\nsize = [3, 5]\nstart = 0\npolls = []"),
+        Err(GameErr(UnsupportedRecordingVersion(2)))
+    );
+    assert!(matches!(
+        parse("size = [3, 5]\nstart = 0\npolls = []"),
+        Err(GameErr(InvalidRecording { .. }))
+    ));
+    assert!(matches!(
+        parse("version = 1\nsize = [3, 5]\nstart = 0\npolls = [{ time = 1, key = \"w\" }]"),
+        Err(GameErr(InvalidRecording { .. }))
+    ));
+}
+
+#[test]
+fn session_is_replayed() {
+    let path = std::env::temp_dir().join(format!("recording-{}.json", std::process::id()));
+    let events = [
+        key(KeyCode::Char('w'), KeyEventKind::Press),
+        key(KeyCode::Right, KeyEventKind::Press),
+    ];
+    let backend = Headless::new((3, 5))
+        .with_releases(true)
+        .with_step(Duration::from_millis(17))
+        .with_events(events)
+        .idle(6)
+        .with_events([
+            key(KeyCode::Right, KeyEventKind::Release),
+            key(KeyCode::Char('a'), KeyEventKind::Press),
+        ])
+        .idle(4)
+        .with_events([key(KeyCode::Char('w'), KeyEventKind::Release)]);
+    let mut recorded = game(backend, Some(42)).with_recording(&path);
+    let initial = recorded.camera().pos().clone();
+    recorded.run().unwrap();
+    let recording = Recording::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(recorded.recording(), Some(&recording));
+    assert_eq!((recording.seed, recording.size), (Some(42), (3, 5)));
+    assert_eq!(recording.polls.len(), 15);
+    assert_eq!(recording.events().count(), 5);
+    assert_eq!(recording.polls[14].at(), Duration::from_millis(17 * 15));
+
+    let mut replayed = game(Headless::replay(&recording), recording.seed);
+    assert_eq!(replayed.random().seed(), 42);
+    replayed.run().unwrap();
+    let (camera, expected) = (replayed.camera(), recorded.camera());
+    assert_ne!(camera.pos(), &initial);
+    assert_eq!(camera.pos(), expected.pos());
+    assert_eq!(
+        (camera.zenith(), camera.azimuth()),
+        (expected.zenith(), expected.azimuth())
+    );
+    assert_eq!(
+        replayed.backend().last_frame(),
+        recorded.backend().last_frame()
+    );
+}
+
+#[test]
+fn recording_is_written_on_error() {
+    let path = std::env::temp_dir().join(format!("failed-{}.toml", std::process::id()));
+    let backend = Headless::new((3, 5)).idle(3);
+    let mut game = game(backend, Some(7)).with_recording(&path);
+    let id = game.scene.spawn();
+    game.scene
+        .add_system("fail", move |_, _| Err(GameErr(NotAliveEntity(id))));
+    assert_eq!(game.run().unwrap_err().kind(), &GameErr(NotAliveEntity(id)));
+    let recording = Recording::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((recording.seed, recording.polls.len()), (Some(7), 1));
+}
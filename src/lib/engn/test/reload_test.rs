@@ -0,0 +1,111 @@
+use {
+    super::{super::*, WallScene},
+    crate::conf::*,
+    std::{fs, path::PathBuf},
+};
+
+/// Path of temporary file unique for the test
+fn temp(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn watcher_detects_changes() {
+    let path = temp("watched.toml");
+    fs::write(&path, "a = 1").unwrap();
+    let mut watcher = Watcher::new();
+    watcher.watch(&path);
+    assert!(!watcher.changed());
+    fs::write(&path, "a = 10").unwrap();
+    assert!(watcher.changed());
+    assert!(!watcher.changed());
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.changed());
+    assert_eq!(watcher.paths().collect::<Vec<_>>(), vec![path.as_path()]);
+}
+
+#[test]
+fn conf_is_applied_live() {
+    let path = temp("conf.toml");
+    fs::write(
+        &path,
+        "[camera]\ndraw_distance = 5\n[render]\ncharmap = \"ab\"",
+    )
+    .unwrap();
+    let conf = Conf::read([&path]).unwrap();
+    let mut game =
+        Game::<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless>::headless(
+            conf,
+            WallScene::new(),
+            MovementEventSys::new(1.0),
+            (5, 5),
+        )
+        .unwrap()
+        .with_hot_reload();
+    game.camera.rotate_left(3);
+    let azimuth = game.camera.azimuth();
+
+    fs::write(
+        &path,
+        "[camera]\ndraw_distance = 10\nhalf_pi_discretization = 12\n[render]\ncharmap = \"xyz\"",
+    )
+    .unwrap();
+    game.reload().unwrap();
+    assert_eq!(game.canvas.charcoal().charmap, vec!['x', 'y', 'z']);
+    assert_eq!(game.camera.discr, 12);
+    assert_eq!(game.camera.draw_dist, 10.0);
+    assert!((game.camera.azimuth() - azimuth).abs() < 1e-9);
+    assert!(!game.hud.contains(RELOAD_ERRORS));
+
+    fs::write(&path, "[camera]\ndraw_distance = [").unwrap();
+    game.reload().unwrap();
+    assert!(game.hud.contains(RELOAD_ERRORS));
+    assert_eq!(game.conf().draw_dist, 10.0);
+    game.render().unwrap();
+
+    fs::write(&path, "[camera]\ndraw_distance = 20").unwrap();
+    game.reload().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(!game.hud.contains(RELOAD_ERRORS));
+    assert_eq!(game.camera.draw_dist, 20.0);
+    assert_eq!(game.camera.discr, Conf::default().angle_discr);
+}
+
+#[test]
+fn scene_is_reloaded() {
+    let path = temp("scene.toml");
+    fs::write(&path, "").unwrap();
+    let mut game = Game::<MovementEvent<World>, MovementEventSys, World, Headless>::headless(
+        Conf::default(),
+        World::new(),
+        MovementEventSys::new(1.0),
+        (5, 5),
+    )
+    .unwrap()
+    .with_scene_reload(&path, |world, path| {
+        *world = load_scene(path)?;
+        Ok(())
+    });
+    game.reload().unwrap();
+    assert_eq!(game.scene.count::<&Render>(), 0);
+
+    fs::write(
+        &path,
+        "[[materials]]\nkind = \"plane\"\npos = [3, 0, 0]\nnormal = [1, 0, 0]",
+    )
+    .unwrap();
+    game.reload().unwrap();
+    assert_eq!(game.scene.count::<&Render>(), 1);
+
+    fs::write(&path, "[[materials]]\nkind = \"cube\"").unwrap();
+    game.reload().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(game.scene.count::<&Render>(), 1);
+    let panel = game.hud.get::<Panel>(RELOAD_ERRORS).unwrap();
+    assert_eq!(panel.lines.len(), 1);
+    assert!(
+        panel.lines[0].contains("materials[0].kind"),
+        "{}",
+        panel.lines[0]
+    );
+}
@@ -0,0 +1,152 @@
+use {
+    super::super::*,
+    crate::{
+        conf::*,
+        errs::{GameErr::*, ReErr::*},
+        math::*,
+    },
+    std::f64::consts::{FRAC_PI_2, PI},
+    uuid::Uuid,
+};
+
+const SCENE: &str = "src/lib/engn/test/scene.toml";
+const WALL: &str = "1b4e28ba-2fa1-11d2-883f-0016d3cca427";
+
+type WorldGame = Game<MovementEvent<World>, MovementEventSys, World, Headless>;
+
+fn game() -> WorldGame {
+    let mut conf = Conf::default();
+    conf.angle_discr = 4;
+    let es = MovementEventSys::new(1.0).with_controller(Controller::new(Motion::default()));
+    Game::headless(conf, load_scene(SCENE).unwrap(), es, (3, 3)).unwrap()
+}
+
+fn wall(game: &WorldGame) -> EntityId {
+    game.scene.ids().lookup(&WALL.parse().unwrap()).unwrap()
+}
+
+#[test]
+fn camera_angles() {
+    let mut camera = Camera::new(Point::new(vec![0.0; 3]), 4, 1.0, 1.0, (3, 3), 10.0);
+    assert_eq!((camera.zenith(), camera.azimuth()), (0.0, 0.0));
+    camera.rotate_up(2);
+    camera.rotate_right(1);
+    assert!(aeq(&camera.zenith(), &(PI / 4.0)));
+    assert!(aeq(&camera.azimuth(), &(2.0 * PI - PI / 8.0)));
+
+    camera.set_zenith(-0.3);
+    assert!(aeq(&camera.zenith(), &(-PI / 8.0)));
+    camera.set_zenith(PI);
+    assert!(aeq(&camera.zenith(), &(FRAC_PI_2 - PI / 8.0)));
+    camera.set_azimuth(-PI / 2.0);
+    assert_eq!(camera.azi_idx, 12);
+    camera.set_azimuth(5.0 * PI);
+    assert!(aeq(&camera.azimuth(), &PI));
+}
+
+#[test]
+fn game_is_restored() {
+    let mut game = game();
+    game.camera.mv(&Vector::new(vec![1.0, -2.0, 0.5])).unwrap();
+    game.camera.rotate_up(1);
+    game.camera.rotate_left(3);
+    if let Some(controller) = game.es.controller_mut() {
+        controller.set_velocity(Vector::new(vec![1.0, 2.0, 0.0]));
+    }
+    let save = game.snapshot().unwrap();
+    for format in [SceneFormat::Toml, SceneFormat::Json] {
+        let content = save.to_string(format).unwrap();
+        assert_eq!(SaveGame::parse(&content, format).unwrap(), save);
+    }
+
+    let path = std::env::temp_dir().join(format!("save-{}.toml", std::process::id()));
+    game.save(&path).unwrap();
+    let id = wall(&game);
+    let render = game.scene.get_mut::<Render>(id).unwrap();
+    render.0.set_prop("hp", Box::new(3_i64));
+    render.0.set_prop("burning", Box::new(true));
+    let uuid = Uuid::new_v4();
+    let spawned = game.scene.ids_mut().generate_with(uuid).unwrap();
+    let plane = HypePlane::new(
+        Entity::new(spawned),
+        Point::new(vec![0.0; 3]),
+        Vector::new(vec![0.0, 1.0, 0.0]),
+        None,
+    )
+    .unwrap();
+    game.scene.insert(spawned, Render(Box::new(plane))).unwrap();
+    game.camera.rotate_down(2);
+    game.camera.set_pos(Point::new(vec![9.0; 3]));
+    if let Some(controller) = game.es.controller_mut() {
+        controller.set_velocity(Vector::new(vec![0.0; 3]));
+    }
+
+    game.load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(game.camera.pos(), &Point::new(vec![1.0, -2.0, 0.5]));
+    assert_eq!((game.camera.zen_idx, game.camera.azi_idx), (2, 3));
+    assert_eq!(
+        game.es.controller().unwrap().velocity(),
+        &Vector::new(vec![1.0, 2.0, 0.0])
+    );
+    let render = game.scene.get::<Render>(id).unwrap();
+    assert_eq!(render.0.get_prop::<i64>("hp").unwrap(), &10);
+    assert_eq!(render.0.get_prop::<String>("name").unwrap(), "wall");
+    assert!(render.0.get_prop::<bool>("burning").is_err());
+    drop(render);
+    assert!(!game.scene.is_alive(spawned));
+    assert_eq!(game.scene.count::<&Render>(), 2);
+}
+
+#[test]
+fn invalid_saves() {
+    let parse = |content: &str| SaveGame::parse(content, SceneFormat::Toml);
+    let camera = "[camera]\npos = [0, 0, 0]\nzenith = 0\nazimuth = 0";
+    assert!(parse(&format!("version = 1\n{}", camera)).is_ok());
+    assert_eq!(
+        parse(&format!("version = 2\n{}", camera)),
+        Err(GameErr(UnsupportedSaveVersion(2)))
+    );
+    assert!(matches!(
+        parse(camera),
+        Err(GameErr(InvalidSaveFile { .. }))
+    ));
+    assert!(matches!(
+        parse(&format!("version = 1\nlevel = 3\n{}", camera)),
+        Err(GameErr(InvalidSaveFile { .. }))
+    ));
+    match parse("version = 1\n[camera]\npos = [0, 0]\nzenith = 0\nazimuth = 0") {
+        Err(GameErr(InvalidSaveValue { field, .. })) => assert_eq!(field, "camera.pos"),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let mut game = game();
+    let mut save = game.snapshot().unwrap();
+    save.scene = format!("[[entities]]\nid = \"{}\"", Uuid::new_v4())
+        .parse()
+        .unwrap();
+    match game.restore(&save) {
+        Err(GameErr(InvalidSaveValue { field, .. })) => assert_eq!(field, "entities[0].id"),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(game.scene.is_alive(wall(&game)));
+}
+
+#[test]
+fn invalid_save_leaves_game() {
+    let mut game = game();
+    let mut save = game.snapshot().unwrap();
+    save.pos = Point::new(vec![9.0; 3]);
+    save.events = "velocity = [1, 2]".parse().unwrap();
+    let id = wall(&game);
+    let render = game.scene.get_mut::<Render>(id).unwrap();
+    render.0.set_prop("hp", Box::new(3_i64));
+    drop(render);
+    match game.restore(&save) {
+        Err(GameErr(InvalidSaveValue { field, .. })) => assert_eq!(field, "velocity"),
+        res => panic!("unexpected {:?}", res),
+    }
+    let render = game.scene.get::<Render>(id).unwrap();
+    assert_eq!(render.0.get_prop::<i64>("hp").unwrap(), &3);
+    assert_ne!(game.camera.pos(), &save.pos);
+}
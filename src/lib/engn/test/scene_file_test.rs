@@ -0,0 +1,236 @@
+use {
+    super::super::*,
+    crate::{
+        errs::{GameErr::*, Location, ReErr::*, ReRes},
+        math::*,
+    },
+    std::collections::HashMap,
+};
+
+const TOML: &str = "src/lib/engn/test/scene.toml";
+const JSON: &str = "src/lib/engn/test/scene.json";
+
+fn cs() -> CoordSys {
+    CoordSys::new(Point::default(), Basis::default()).unwrap()
+}
+
+fn invalid_field(res: ReRes<impl std::fmt::Debug>) -> (String, String) {
+    match res {
+        Err(GameErr(InvalidSceneValue { file, field, .. })) => (file, field),
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn load_toml_scene() {
+    set_biform_identity();
+    let world = load_scene(TOML).unwrap();
+    assert_eq!(world.count::<&Render>(), 2);
+    let dir = Vector::new(vec![1.0, 0.0, 0.0]);
+    assert_eq!(world.trace(&cs(), &Point::default(), &dir), Some(3.0));
+    let down = Vector::new(vec![0.0, 0.0, -1.0]);
+    let dist = world.trace(&cs(), &Point::default(), &down).unwrap();
+    assert!(aeq(&dist, &3.5));
+
+    let uuid = "1b4e28ba-2fa1-11d2-883f-0016d3cca427".parse().unwrap();
+    let id = world.ids().lookup(&uuid).unwrap();
+    let render = world.get::<Render>(id).unwrap();
+    assert_eq!(render.0.id(), id);
+    assert_eq!(render.0.get_prop::<String>("name").unwrap(), "wall");
+    assert_eq!(render.0.get_prop::<i64>("hp").unwrap(), &10);
+    assert_eq!(render.0.charmap(1.0), Some('#'));
+    assert_eq!(render.0.charmap(6.0), Some('.'));
+}
+
+#[test]
+fn json_errors_name_file_and_field() {
+    let file = SceneFile::read(JSON).unwrap();
+    assert_eq!(file.materials.len(), 2);
+    assert_eq!(file.materials[1].kind(), HypeEllipse::KIND);
+    let (file, field) = invalid_field(file.load(&Materials::new()));
+    assert_eq!(file, JSON);
+    assert_eq!(field, "materials[1].semiaxes");
+}
+
+#[test]
+fn invalid_scenes() {
+    let parse = |content| SceneFile::parse(content, SceneFormat::Toml);
+    let load = |content| parse(content)?.load(&Materials::new());
+
+    let (_, field) = invalid_field(load("[[materials]]\nkind = \"plane\"\npos = [0, 0, 0]"));
+    assert_eq!(field, "materials[0].normal");
+    let (_, field) = invalid_field(load("[[materials]]\nkind = \"cube\""));
+    assert_eq!(field, "materials[0].kind");
+    let (_, field) = invalid_field(parse("[[materials]]\npos = [0, 0, 0]"));
+    assert_eq!(field, "materials[0].kind");
+    let (_, field) = invalid_field(load(
+        "[[materials]]\nkind = \"plane\"\npos = [0, 0]\nnormal = [1, 0, 0]",
+    ));
+    assert_eq!(field, "materials[0]");
+    let (_, field) = invalid_field(load(
+        "[[materials]]\nkind = \"plane\"\npos = [0, 0, 0]\nnormal = [1, 0, 0]\nprops = { a = [1] }",
+    ));
+    assert_eq!(field, "materials[0].props.a");
+
+    match parse("[[materials]\nkind = 1") {
+        Err(GameErr(InvalidSyntax { location, .. })) => {
+            assert_eq!(location.map(|location| location.line), Some(1))
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(matches!(
+        parse("walls = []"),
+        Err(GameErr(InvalidSceneFile { .. }))
+    ));
+    assert!(matches!(
+        SceneFile::read("src/lib/engn/test/missing.toml"),
+        Err(GameErr(InaccessibleFile { .. }))
+    ));
+    match SceneFile::parse("{\n  \"materials\": [}", SceneFormat::Json) {
+        Err(GameErr(InvalidSyntax {
+            location, reason, ..
+        })) => {
+            assert_eq!(
+                location,
+                Some(Location {
+                    line: 2,
+                    column: 17
+                })
+            );
+            assert!(!reason.contains("line"), "{}", reason);
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn duplicate_ids() {
+    let mut file = SceneFile::read(TOML).unwrap();
+    file.materials.push(file.materials[0].clone());
+    let mut world = World::new();
+    let (_, field) = invalid_field(file.load_into(&mut world, &Materials::new()));
+    assert_eq!(field, "materials[0].id");
+    assert_eq!(world.entities().len(), 2);
+}
+
+#[test]
+fn saved_scene_loads_back() {
+    set_biform_identity();
+    let world = load_scene(TOML).unwrap();
+    let saved = SceneFile::from_world(&world).unwrap();
+    for format in [SceneFormat::Toml, SceneFormat::Json] {
+        let content = saved.to_string(format).unwrap();
+        let loaded = SceneFile::parse(&content, format)
+            .unwrap()
+            .load(&Materials::new())
+            .unwrap();
+        assert_eq!(SceneFile::from_world(&loaded).unwrap(), saved);
+    }
+    let table = saved.to_table();
+    assert_eq!(SceneFile::from_table(table).unwrap(), saved);
+
+    let path = std::env::temp_dir().join(format!("scene-{}.json", std::process::id()));
+    save_scene(&world, &path).unwrap();
+    let loaded = load_scene(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let dir = Vector::new(vec![1.0, 0.0, 0.0]);
+    assert_eq!(loaded.trace(&cs(), &Point::default(), &dir), Some(3.0));
+}
+
+/// Material that collides with everything at the given distance
+#[derive(Debug)]
+struct Fog {
+    entity: Entity,
+    dist: f64,
+}
+
+impl AsEntity for Fog {
+    fn id(&self) -> EntityId {
+        self.entity.id()
+    }
+
+    fn props(&self) -> &HashMap<PropKey, PropVal> {
+        self.entity.props()
+    }
+
+    fn props_mut(&mut self) -> &mut HashMap<PropKey, PropVal> {
+        self.entity.props_mut()
+    }
+}
+
+impl AsCollided for Fog {
+    fn collide(&self, _cs: &CoordSys, _inc: &Point, _dir: &Vector) -> Option<f64> {
+        Some(self.dist)
+    }
+
+    fn charmap(&self, _dist: f64) -> Option<char> {
+        Some('~')
+    }
+
+    fn describe(&self) -> Option<MaterialDesc> {
+        let mut desc = MaterialDesc::new("fog");
+        desc.set_float("dist", self.dist);
+        Some(desc)
+    }
+}
+
+#[test]
+fn custom_materials() {
+    set_biform_identity();
+    let mut materials = Materials::new();
+    materials.register("fog", |desc, entity| {
+        let dist = desc.float("dist")?;
+        Ok(Box::new(Fog { entity, dist }))
+    });
+    assert!(materials.is_registered("fog"));
+    let world = SceneFile::parse("[[materials]]\nkind = \"fog\"\ndist = 2", SceneFormat::Toml)
+        .unwrap()
+        .load(&materials)
+        .unwrap();
+    let dir = Vector::new(vec![1.0, 0.0, 0.0]);
+    assert_eq!(world.trace(&cs(), &Point::default(), &dir), Some(2.0));
+    let content = SceneFile::from_world(&world)
+        .unwrap()
+        .to_string(SceneFormat::Toml)
+        .unwrap();
+    assert!(content.contains("kind = \"fog\""));
+    assert!(content.contains("dist = 2.0"));
+}
+
+/// Material without description
+#[derive(Debug)]
+struct Secret(Entity);
+
+impl AsEntity for Secret {
+    fn id(&self) -> EntityId {
+        self.0.id()
+    }
+
+    fn props(&self) -> &HashMap<PropKey, PropVal> {
+        self.0.props()
+    }
+
+    fn props_mut(&mut self) -> &mut HashMap<PropKey, PropVal> {
+        self.0.props_mut()
+    }
+}
+
+impl AsCollided for Secret {
+    fn collide(&self, _cs: &CoordSys, _inc: &Point, _dir: &Vector) -> Option<f64> {
+        None
+    }
+
+    fn charmap(&self, _dist: f64) -> Option<char> {
+        None
+    }
+}
+
+#[test]
+fn unsavable_materials() {
+    let mut world = World::new();
+    world.register(Secret(Entity::new(IdPool::generate_shared())));
+    assert_eq!(
+        SceneFile::from_world(&world),
+        Err(GameErr(UnsavableMaterial))
+    );
+}
@@ -0,0 +1,235 @@
+use {
+    super::{super::*, WallScene},
+    crate::{conf::*, errs::ReRes, math::*},
+    crossterm::event::Event,
+    std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::{Arc, Mutex},
+        time::Duration,
+    },
+};
+
+fn game() -> Game<MovementEvent<WallScene>, MovementEventSys, WallScene, Headless> {
+    let mut conf = Conf::default();
+    conf.initpt = Point::new(vec![0.0, 0.0, 1.0]);
+    Game::headless(conf, WallScene::new(), MovementEventSys::new(1.0), (3, 5)).unwrap()
+}
+
+fn sphere(pos: [f64; 3]) -> Trigger {
+    Trigger::new(
+        IdPool::generate_shared(),
+        Volume::Sphere { radius: 1.0 },
+        Point::new(pos.to_vec()),
+    )
+}
+
+fn phases(events: &[TriggerEvent]) -> Vec<Phase> {
+    events.iter().map(|event| event.phase).collect()
+}
+
+#[test]
+fn camera_enters_stays_exits() {
+    let mut game = game();
+    let trigger = sphere([2.0, 0.0, 1.0]);
+    let id = trigger.id;
+    game.triggers_mut().add(trigger);
+
+    assert!(game.triggers.update(&game.camera, &mut game.hud).is_empty());
+    game.camera_mut()
+        .mv(&Vector::new(vec![1.5, 0.0, 0.0]))
+        .unwrap();
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    assert_eq!(
+        events,
+        vec![TriggerEvent {
+            trigger: id,
+            occupant: Occupant::Camera,
+            phase: Phase::Enter
+        }]
+    );
+    assert!(game.triggers().get(id).unwrap().contains(&Occupant::Camera));
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    assert_eq!(phases(&events), vec![Phase::Stay]);
+
+    game.camera_mut()
+        .mv(&Vector::new(vec![-1.5, 0.0, 0.0]))
+        .unwrap();
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    assert_eq!(phases(&events), vec![Phase::Exit]);
+    assert!(!game.triggers().get(id).unwrap().is_occupied());
+}
+
+#[test]
+fn box_trigger_watches_objects() {
+    let mut game = game();
+    let object = Rc::new(RefCell::new(
+        HypeEllipse::new(
+            Entity::new(IdPool::generate_shared()),
+            Point::new(vec![10.0, 0.0, 0.0]),
+            Basis::new(Matrix::identity(3).to_multicol()).unwrap(),
+            vec![1.0; 3],
+            None,
+        )
+        .unwrap(),
+    ));
+    let object_id = object.borrow().id();
+    game.triggers_mut().add(Trigger::new(
+        IdPool::generate_shared(),
+        Volume::Cuboid {
+            half: Vector::new(vec![1.0, 1.0, 1.0]),
+        },
+        Point::new(vec![10.0, 0.0, 0.0]),
+    ));
+    game.triggers_mut().watch(object.clone());
+
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].occupant, Occupant::Object(object_id));
+    assert_eq!(events[0].phase, Phase::Enter);
+
+    object
+        .borrow_mut()
+        .mv(&Vector::new(vec![0.0, 5.0, 0.0]))
+        .unwrap();
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    assert_eq!(phases(&events), vec![Phase::Exit]);
+
+    object
+        .borrow_mut()
+        .mv(&Vector::new(vec![0.0, -5.0, 0.0]))
+        .unwrap();
+    game.triggers_mut().unwatch(object_id);
+    assert!(game.triggers.update(&game.camera, &mut game.hud).is_empty());
+}
+
+#[test]
+fn callback_updates_hud() {
+    let mut game = game();
+    let trigger = sphere([0.0, 0.0, 1.0]).on(|event, hud| {
+        if event.phase == Phase::Enter {
+            hud.toast("Exit!", Duration::from_secs(1));
+        }
+    });
+    let id = trigger.id;
+    game.triggers_mut().add(trigger);
+    game.triggers.update(&game.camera, &mut game.hud);
+    assert!(game.hud().has_dialogs());
+
+    assert!(game.triggers_mut().remove(id).is_some());
+    assert!(game.triggers().get(id).is_none());
+}
+
+/// Event system that records trigger events
+#[derive(Default)]
+struct Recorder {
+    events: Vec<TriggerEvent>,
+    despawned: Vec<EntityId>,
+}
+
+struct Nothing;
+
+impl From<Event> for Nothing {
+    fn from(_: Event) -> Self {
+        Nothing
+    }
+}
+
+impl AsEvent<WallScene> for Nothing {}
+
+impl AsEventSys<Nothing, WallScene> for Recorder {
+    fn push(&mut self, _event: Nothing) {}
+
+    fn handle_all(
+        &mut self,
+        _cs: &CoordSys,
+        _camera: &mut Camera,
+        _scene: &mut WallScene,
+        _hud: &mut Hud,
+        _input: &InputState,
+    ) -> ReRes<()> {
+        Ok(())
+    }
+
+    fn trigger(&mut self, event: TriggerEvent) {
+        self.events.push(event);
+    }
+
+    fn despawn(&mut self, id: EntityId) {
+        self.despawned.push(id);
+    }
+}
+
+#[test]
+fn game_delivers_trigger_events() {
+    let mut conf = Conf::default();
+    conf.initpt = Point::new(vec![0.0, 0.0, 1.0]);
+    let backend = Headless::new((3, 5)).idle(3);
+    let mut game =
+        Game::with_backend(conf, WallScene::new(), Recorder::default(), backend).unwrap();
+    game.triggers_mut().add(sphere([0.0, 0.0, 1.0]));
+    game.run().unwrap();
+    assert_eq!(
+        phases(&game.es.events),
+        vec![Phase::Enter, Phase::Stay, Phase::Stay]
+    );
+}
+
+#[test]
+fn game_forgets_despawned() {
+    let mut conf = Conf::default();
+    conf.initpt = Point::new(vec![0.0, 0.0, 1.0]);
+    let backend = Headless::new((3, 5)).idle(2);
+    let mut game =
+        Game::with_backend(conf, WallScene::new(), Recorder::default(), backend).unwrap();
+    let id = game.entity().id();
+    let mut trigger = sphere([0.0, 0.0, 1.0]);
+    trigger.id = id;
+    game.triggers_mut().add(trigger);
+    assert!(game.despawn(id));
+    assert!(!game.despawn(id));
+    game.run().unwrap();
+    assert!(game.es.events.is_empty());
+    assert_eq!(game.es.despawned, vec![id]);
+    assert!(game.triggers().get(id).is_none());
+    assert!(game.ids().is_empty());
+}
+
+#[test]
+fn exits_are_ordered() {
+    let mut game = game();
+    let objects = (0..3)
+        .map(|_| {
+            Rc::new(RefCell::new(
+                HypeEllipse::new(
+                    Entity::new(IdPool::generate_shared()),
+                    Point::new(vec![0.0, 0.0, 1.0]),
+                    Basis::new(Matrix::identity(3).to_multicol()).unwrap(),
+                    vec![1.0; 3],
+                    None,
+                )
+                .unwrap(),
+            ))
+        })
+        .collect::<Vec<_>>();
+    game.triggers_mut().add(sphere([0.0, 0.0, 1.0]));
+    for object in objects.iter().rev() {
+        game.triggers_mut().watch(object.clone());
+    }
+    assert_eq!(game.triggers.update(&game.camera, &mut game.hud).len(), 4);
+
+    let away = Vector::new(vec![0.0, 5.0, 0.0]);
+    game.camera_mut().mv(&away).unwrap();
+    for object in &objects {
+        object.borrow_mut().mv(&away).unwrap();
+    }
+    let events = game.triggers.update(&game.camera, &mut game.hud);
+    let mut expected = objects
+        .iter()
+        .map(|object| Occupant::Object(object.borrow().id()))
+        .collect::<Vec<_>>();
+    expected.sort();
+    expected.insert(0, Occupant::Camera);
+    let occupants = events.into_iter().map(|event| event.occupant);
+    assert_eq!(occupants.collect::<Vec<_>>(), expected);
+}
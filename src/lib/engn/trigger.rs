@@ -0,0 +1,189 @@
+use {
+    crate::{engn::*, math::*},
+    std::{
+        cell::RefCell,
+        collections::BTreeSet,
+        fmt::{self, Debug},
+        rc::Rc,
+    },
+};
+
+/// Phase of overlapping trigger volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Occupant has got inside during the frame
+    Enter,
+    /// Occupant is still inside
+    Stay,
+    /// Occupant has left during the frame
+    Exit,
+}
+
+/// What overlaps trigger volume
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Occupant {
+    Camera,
+    /// Watched game object with the given id
+    Object(EntityId),
+}
+
+/// Event of trigger that `Game` passes to `AsEventSys::trigger`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerEvent {
+    /// Id of trigger, usually id of entity it's attached to
+    pub trigger: EntityId,
+    pub occupant: Occupant,
+    pub phase: Phase,
+}
+
+/// Callback called on each event of trigger, it can update overlays
+pub type TriggerCallback = Box<dyn FnMut(&TriggerEvent, &mut Hud)>;
+
+/// Region of space that emits enter, stay and exit events when camera
+/// or watched game object is inside its volume
+pub struct Trigger {
+    pub id: EntityId,
+    pub volume: Volume,
+    pub pos: Point,
+    callback: Option<TriggerCallback>,
+    /// Occupants in order, so exits are emitted in the same order in each run
+    inside: BTreeSet<Occupant>,
+}
+
+impl Trigger {
+    /// Trigger with the given id, pass id of entity to attach trigger to it
+    pub fn new(id: EntityId, volume: Volume, pos: Point) -> Self {
+        Self {
+            id,
+            volume,
+            pos,
+            callback: None,
+            inside: BTreeSet::new(),
+        }
+    }
+
+    /// Sets callback called on each event of the trigger
+    pub fn on(mut self, callback: impl FnMut(&TriggerEvent, &mut Hud) + 'static) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Whether anything is inside the trigger
+    pub fn is_occupied(&self) -> bool {
+        !self.inside.is_empty()
+    }
+
+    /// Whether the occupant is inside the trigger
+    pub fn contains(&self, occupant: &Occupant) -> bool {
+        self.inside.contains(occupant)
+    }
+
+    /// Events for occupants that are inside now
+    fn check(&mut self, now: &[(Occupant, Point)]) -> Vec<TriggerEvent> {
+        let mut events = vec![];
+        let mut inside = BTreeSet::new();
+        for (occupant, pos) in now {
+            if !self.volume.contains(&self.pos, pos) {
+                continue;
+            }
+            let phase = match self.inside.contains(occupant) {
+                true => Phase::Stay,
+                false => Phase::Enter,
+            };
+            events.push(self.event(occupant.clone(), phase));
+            inside.insert(occupant.clone());
+        }
+        for occupant in self.inside.difference(&inside) {
+            events.push(self.event(occupant.clone(), Phase::Exit));
+        }
+        self.inside = inside;
+        events
+    }
+
+    fn event(&self, occupant: Occupant, phase: Phase) -> TriggerEvent {
+        TriggerEvent {
+            trigger: self.id,
+            occupant,
+            phase,
+        }
+    }
+}
+
+impl Debug for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Trigger")
+            .field("id", &self.id)
+            .field("volume", &self.volume)
+            .field("pos", &self.pos)
+            .field("inside", &self.inside)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Triggers of `Game` and game objects they watch besides camera.
+/// Object overlaps trigger when its position is inside trigger volume
+#[derive(Debug, Default)]
+pub struct Triggers {
+    triggers: Vec<Trigger>,
+    objects: Vec<Rc<RefCell<dyn AsGameObject>>>,
+}
+
+impl Triggers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds trigger replacing the one with the same id
+    pub fn add(&mut self, trigger: Trigger) {
+        self.remove(trigger.id);
+        self.triggers.push(trigger);
+    }
+
+    /// Removes trigger with the given id without exit events
+    pub fn remove(&mut self, id: EntityId) -> Option<Trigger> {
+        let idx = self.triggers.iter().position(|t| t.id == id)?;
+        Some(self.triggers.remove(idx))
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&Trigger> {
+        self.triggers.iter().find(|t| t.id == id)
+    }
+
+    /// Makes triggers to check the object as well as camera
+    pub fn watch(&mut self, object: Rc<RefCell<dyn AsGameObject>>) {
+        self.objects.push(object);
+    }
+
+    /// Stops checking the object with the given id
+    pub fn unwatch(&mut self, id: EntityId) {
+        self.objects.retain(|object| object.borrow().id() != id);
+    }
+
+    /// Drops trigger and watched object of despawned entity, `Game` calls it
+    /// for each id released in its `IdPool`
+    pub fn forget(&mut self, id: EntityId) {
+        self.remove(id);
+        self.unwatch(id);
+    }
+
+    /// Checks positions of camera and watched objects against triggers, calls callbacks
+    /// and returns all the events. `Game` calls it each frame after `AsEventSys::handle_all`
+    pub fn update(&mut self, camera: &Camera, hud: &mut Hud) -> Vec<TriggerEvent> {
+        let mut now = vec![(Occupant::Camera, camera.pos().clone())];
+        for object in &self.objects {
+            let object = object.borrow();
+            now.push((Occupant::Object(object.id()), object.pos().clone()));
+        }
+        let mut events = vec![];
+        for trigger in &mut self.triggers {
+            let fired = trigger.check(&now);
+            if let Some(callback) = &mut trigger.callback {
+                for event in &fired {
+                    callback(event, hud);
+                }
+            }
+            events.extend(fired);
+        }
+        events
+    }
+}
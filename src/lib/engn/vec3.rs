@@ -0,0 +1,66 @@
+//! Arithmetic of 3-dimensional vectors as plain arrays, it's used by collision and physics
+//! where `Vector` roundations and dimension checks aren't needed
+
+use crate::math::*;
+
+/// Lengths below it are treated as zero
+pub(crate) const EPS: f64 = 1e-9;
+
+pub(crate) fn arr(v: &Vector) -> [f64; 3] {
+    [v[0], v[1], v[2]]
+}
+
+pub(crate) fn vector(a: [f64; 3]) -> Vector {
+    Vector::new(a.to_vec())
+}
+
+pub(crate) fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn neg(a: [f64; 3]) -> [f64; 3] {
+    [-a[0], -a[1], -a[2]]
+}
+
+pub(crate) fn scale(a: [f64; 3], k: f64) -> [f64; 3] {
+    [a[0] * k, a[1] * k, a[2] * k]
+}
+
+pub(crate) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Vector of length 1 with the same direction, `None` for zero vector
+pub(crate) fn unit(a: [f64; 3]) -> Option<[f64; 3]> {
+    let len = norm(a);
+    match len > EPS {
+        true => Some(scale(a, 1.0 / len)),
+        false => None,
+    }
+}
+
+/// Two unit vectors orthogonal to unit `d` and to each other, `None` if `d` isn't unit
+pub(crate) fn across(d: [f64; 3]) -> Option<([f64; 3], [f64; 3])> {
+    let axis = match d[2].abs() < 0.9 {
+        true => [0.0, 0.0, 1.0],
+        false => [1.0, 0.0, 0.0],
+    };
+    let u = unit(cross(d, axis))?;
+    Some((u, cross(d, u)))
+}
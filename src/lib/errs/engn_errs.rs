@@ -1,20 +1,85 @@
-use thiserror::Error;
+use {
+    super::{at, IoErr, Location},
+    crate::engn::{EntityId, NodeId},
+    thiserror::Error,
+};
 
 /// Errors that can be obtained within `Result::Err::EngnErr`
-#[derive(Error, Debug, Clone, Copy, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum GameErr {
-    #[error("requasted property of GameObject isn't initialized yet")]
-    NotInitializedProp,
+    #[error("requasted property {0:?} of GameObject isn't initialized yet")]
+    NotInitializedProp(String),
 
-    #[error("trying to set property {key:?} to value {val:?}")]
-    InvalidPropF64 { key: &'static str, val: f64 },
+    #[error("property {key:?} isn't of type {expected}")]
+    InvalidPropType { key: String, expected: &'static str },
 
-    #[error("failed to read file {0}")]
-    InvalidConfFilePath(&'static str),
+    #[error("entity {0:?} is despawned or doesn't exist")]
+    NotAliveEntity(EntityId),
 
-    #[error("failed to parse TOML from {0}")]
-    InvalidConfFileContent(&'static str),
+    #[error("screen of size {0:?} has no cells to draw")]
+    EmptyScreen((usize, usize)),
 
-    #[error("failed to parse value with key {0}")]
-    InvalidConfValue(&'static str),
+    #[error("node {0:?} of scene graph is removed or doesn't exist")]
+    NotExistingNode(NodeId),
+
+    #[error("node {node:?} can't be attached to itself or its descendant {parent:?}")]
+    CyclicNode { node: NodeId, parent: NodeId },
+
+    #[error("failed to access file {file}")]
+    InaccessibleFile { file: String, source: IoErr },
+
+    #[error("failed to parse {file}{}: {reason}", at(.location))]
+    InvalidSyntax {
+        file: String,
+        location: Option<Location>,
+        reason: String,
+    },
+
+    #[error("{file}: {reason}")]
+    InvalidSceneFile { file: String, reason: String },
+
+    #[error("{file}: invalid {field}: {reason}")]
+    InvalidSceneValue {
+        file: String,
+        field: String,
+        reason: String,
+    },
+
+    #[error("material can't be saved, it doesn't implement `AsCollided::describe`")]
+    UnsavableMaterial,
+
+    #[error("{file}: {reason}")]
+    InvalidSaveFile { file: String, reason: String },
+
+    #[error("invalid {field} of save: {reason}")]
+    InvalidSaveValue { field: String, reason: String },
+
+    #[error("save version {0} isn't supported")]
+    UnsupportedSaveVersion(i64),
+
+    #[error("{file}: {reason}")]
+    InvalidRecording { file: String, reason: String },
+
+    #[error("recording version {0} isn't supported")]
+    UnsupportedRecordingVersion(i64),
+
+    #[error("invalid value in {file}{}: {reason}", at(.location))]
+    InvalidConfValue {
+        file: String,
+        location: Option<Location>,
+        reason: String,
+    },
+
+    #[error("value with key {key} in {file} must be {expected}")]
+    ConfValueOutOfRange {
+        file: String,
+        key: &'static str,
+        expected: &'static str,
+    },
+
+    #[error("unknown key {key:?}{}", .suggestion.as_ref().map(|s| format!(", did you mean {}?", s)).unwrap_or_default())]
+    UnknownConfKey {
+        key: String,
+        suggestion: Option<String>,
+    },
 }
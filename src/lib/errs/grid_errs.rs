@@ -32,11 +32,11 @@ pub enum GridErr {
     #[error("trying to iterate by rows on {0}")]
     NotIterableByLines(Repr),
 
-    #[error("trying to operate on matrix as on the row or col")]
-    NotRowOrCol,
+    #[error("trying to operate on {0} matrix as on the row or col")]
+    NotRowOrCol(Repr),
 
-    #[error("trying to operate on matrix as on the set of rows or cols")]
-    NotMultiRowOrCol,
+    #[error("trying to operate on {0} matrix as on the set of rows or cols")]
+    NotMultiRowOrCol(Repr),
 
     #[error("matrix should be stratified to rows or cols")]
     NotStratified,
@@ -18,14 +18,14 @@ pub enum MathErr {
     #[error("scalar product between vectors with dim {lhs:?} and {rhs:?}")]
     DimMismatch { lhs: usize, rhs: usize },
 
-    #[error("inverse of matrix with null determinant")]
-    NullDeterminant,
+    #[error("inverse of matrix of size {0:?} with null determinant")]
+    NullDeterminant((usize, usize)),
 
     #[error("division by number zero")]
     ZeroDivision,
 
-    #[error("trying to operate in 3-dim space")]
-    NotIn3Dim,
+    #[error("trying to operate in 3-dim space on vectors with dim {0}")]
+    NotIn3Dim(usize),
 
     #[error("trying to create rotation matrix from {0} to {0} axis")]
     RotationInOneAxis(usize),
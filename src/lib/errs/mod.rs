@@ -1,27 +1,184 @@
-//! All that relates to error handling
+//! All that relates to error handling. Errors of modules are wrapped in `ReErr` variants
+//! so their kinds can be matched, `ReErr::context` adds description of the failed operation
+
+#[cfg(test)]
+mod test;
 
 mod engn_errs;
 mod grid_errs;
 mod math_errs;
 
 pub use {engn_errs::GameErr, grid_errs::GridErr, math_errs::MathErr};
-use {std::io, strum_macros::Display, thiserror::Error};
+use {
+    std::{
+        error::Error as _,
+        fmt::{self, Display},
+        io,
+        path::Path,
+        sync::Arc,
+    },
+    thiserror::Error,
+};
 
 /// `Result` with `ReErr` as `Err` variant
 pub type ReRes<T> = Result<T, ReErr>;
 
 /// Unified errors enum, holds different errors in variants with related names.
-/// `ReErr` stands for RustyEngineError
-#[derive(Debug, Display, Clone, Copy, PartialEq, Error)]
+/// `ReErr` stands for RustyEngineError. Wrapped errors are shown as they are,
+/// `Context` shows its description and gives the wrapped error as `Error::source`
+#[derive(Debug, Clone, PartialEq, Error)]
 pub enum ReErr {
-    GameErr(GameErr),
-    GridErr(GridErr),
-    MathErr(MathErr),
-    IoError,
+    #[error(transparent)]
+    GameErr(#[from] GameErr),
+    #[error(transparent)]
+    GridErr(#[from] GridErr),
+    #[error(transparent)]
+    MathErr(#[from] MathErr),
+    #[error(transparent)]
+    IoError(#[from] IoErr),
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<ReErr>,
+    },
+}
+
+impl ReErr {
+    /// Wraps error with description of the failed operation, eg file or entity
+    pub fn context(self, context: impl Display) -> Self {
+        Self::Context {
+            context: context.to_string(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The innermost error under all the contexts, it's matched on kinds,
+    /// eg `matches!(err.kind(), ReErr::GameErr(GameErr::NotAliveEntity(_)))`
+    pub fn kind(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.kind(),
+            err => err,
+        }
+    }
+
+    /// Messages of the error and of all its sources joined with `: `
+    pub fn report(&self) -> String {
+        let mut report = self.to_string();
+        let mut source = self.source();
+        while let Some(err) = source {
+            report = format!("{}: {}", report, err);
+            source = err.source();
+        }
+        report
+    }
 }
 
 impl From<io::Error> for ReErr {
-    fn from(_error: io::Error) -> Self {
-        Self::IoError
+    fn from(error: io::Error) -> Self {
+        Self::IoError(error.into())
+    }
+}
+
+/// Adds context to errors of `ReRes`, see `ReErr::context`
+pub trait AsContext<T> {
+    fn context(self, context: impl Display) -> ReRes<T>;
+
+    /// Context is computed only if there is an error
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> ReRes<T>;
+}
+
+impl<T> AsContext<T> for ReRes<T> {
+    fn context(self, context: impl Display) -> ReRes<T> {
+        self.map_err(|err| err.context(context))
+    }
+
+    fn with_context<C: Display>(self, context: impl FnOnce() -> C) -> ReRes<T> {
+        self.map_err(|err| err.context(context()))
+    }
+}
+
+/// `io::Error` shared so it can be cloned, errors are equal if their kinds and messages are
+#[derive(Debug, Clone, Error)]
+#[error(transparent)]
+pub struct IoErr(Arc<io::Error>);
+
+impl IoErr {
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+
+    pub fn get(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+impl From<io::Error> for IoErr {
+    fn from(error: io::Error) -> Self {
+        Self(Arc::new(error))
+    }
+}
+
+impl PartialEq for IoErr {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind() && self.0.to_string() == other.0.to_string()
+    }
+}
+
+/// Position in text, line and column are counted from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    /// Position of byte offset in content
+    pub fn of(content: &str, offset: usize) -> Self {
+        let before = &content[..offset.min(content.len())];
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before.rsplit('\n').next().unwrap_or("").chars().count() + 1,
+        }
+    }
+
+    /// Position of TOML error in the parsed content if it's known
+    pub fn toml(err: &toml::de::Error, content: &str) -> Option<Self> {
+        err.span().map(|span| Self::of(content, span.start))
     }
 }
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Suffix of error messages with location if it's known
+pub(crate) fn at(location: &Option<Location>) -> String {
+    location
+        .map(|location| format!(" at {}", location))
+        .unwrap_or_default()
+}
+
+/// `GameErr::InaccessibleFile` with the error of reading or writing it
+pub(crate) fn inaccessible(path: &Path, err: io::Error) -> ReErr {
+    ReErr::GameErr(GameErr::InaccessibleFile {
+        file: path.display().to_string(),
+        source: err.into(),
+    })
+}
+
+/// `GameErr::InvalidSyntax` of TOML content
+pub(crate) fn toml_syntax(file: &str, err: &toml::de::Error, content: &str) -> ReErr {
+    ReErr::GameErr(GameErr::InvalidSyntax {
+        file: file.to_string(),
+        location: Location::toml(err, content),
+        reason: oneline(err.message()),
+    })
+}
+
+/// Joins lines of error message
+pub(crate) fn oneline(message: &str) -> String {
+    message.trim().lines().collect::<Vec<_>>().join(" ")
+}
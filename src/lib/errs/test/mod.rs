@@ -0,0 +1,74 @@
+use {
+    super::*,
+    std::{error::Error, io},
+};
+
+#[test]
+fn wrapped_errors_are_shown_as_they_are() {
+    let err = ReErr::MathErr(MathErr::DimMismatch { lhs: 2, rhs: 3 });
+    assert_eq!(
+        err.to_string(),
+        "scalar product between vectors with dim 2 and 3"
+    );
+    assert!(err.source().is_none());
+    assert_eq!(err.kind(), &err);
+}
+
+#[test]
+fn io_errors_keep_source() {
+    let err = ReErr::from(io::Error::new(io::ErrorKind::NotFound, "no scene"));
+    assert_eq!(err.to_string(), "no scene");
+    match &err {
+        ReErr::IoError(io) => {
+            assert_eq!(io.kind(), io::ErrorKind::NotFound);
+            assert_eq!(io.get().to_string(), "no scene");
+        }
+        _ => panic!("unexpected {:?}", err),
+    }
+    assert_eq!(err.clone(), err);
+
+    let err = ReErr::GameErr(GameErr::InaccessibleFile {
+        file: "scene.toml".to_string(),
+        source: io::Error::new(io::ErrorKind::NotFound, "no scene").into(),
+    });
+    assert_eq!(err.source().unwrap().to_string(), "no scene");
+    assert_eq!(err.report(), "failed to access file scene.toml: no scene");
+}
+
+#[test]
+fn contexts_are_chained() {
+    let res: ReRes<()> = Err(ReErr::GameErr(GameErr::UnsupportedSaveVersion(2)));
+    let err = res
+        .context("failed to restore save.toml")
+        .with_context(|| format!("failed to load level {}", 3))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "failed to load level 3");
+    assert_eq!(
+        err.source().unwrap().to_string(),
+        "failed to restore save.toml"
+    );
+    assert_eq!(
+        err.report(),
+        "failed to load level 3: failed to restore save.toml: save version 2 isn't supported"
+    );
+    assert!(matches!(
+        err.kind(),
+        ReErr::GameErr(GameErr::UnsupportedSaveVersion(2))
+    ));
+}
+
+#[test]
+fn syntax_errors_are_located() {
+    let content = "a = 1\nb = [";
+    let err = content.parse::<toml::Table>().unwrap_err();
+    match toml_syntax("conf.toml", &err, content) {
+        ReErr::GameErr(GameErr::InvalidSyntax { location, .. }) => {
+            assert_eq!(location, Some(Location { line: 2, column: 6 }))
+        }
+        err => panic!("unexpected {:?}", err),
+    }
+    assert_eq!(
+        Location::of("ab\ncdé\nf", 7),
+        Location { line: 2, column: 4 }
+    );
+}
@@ -473,7 +473,7 @@ impl<E> Grid<E> {
     pub fn ag_not_row_or_col(&self) -> ReRes<&Self> {
         match self.repr() {
             Repr::Row | Repr::Col => Ok(self),
-            _ => Err(GridErr(NotRowOrCol)),
+            repr => Err(GridErr(NotRowOrCol(repr))),
         }
     }
 
@@ -497,7 +497,7 @@ impl<E> Grid<E> {
     pub fn ag_not_multi_row_or_col(&self) -> ReRes<&Self> {
         match self.repr() {
             Repr::MultiRow | Repr::MultiCol => Ok(self),
-            _ => Err(GridErr(NotMultiRowOrCol)),
+            repr => Err(GridErr(NotMultiRowOrCol(repr))),
         }
     }
 
@@ -505,7 +505,7 @@ impl<E> Grid<E> {
     pub fn ag_not_stratified(&self) -> ReRes<&Self> {
         match self.repr() {
             Repr::Row | Repr::MultiRow | Repr::Col | Repr::MultiCol => Ok(self),
-            _ => Err(GridErr(NotMultiRowOrCol)),
+            repr => Err(GridErr(NotMultiRowOrCol(repr))),
         }
     }
 
@@ -56,7 +56,7 @@ impl Matrix {
     pub fn inv(&self) -> ReRes<Self> {
         let det = self.det()?;
         if aeq(&det, &0.0) {
-            return Err(MathErr(NullDeterminant));
+            return Err(MathErr(NullDeterminant((self.rows(), self.cols()))));
         }
 
         let mut rows = vec![true; self.rows()];
@@ -686,8 +686,8 @@ impl Matrix {
     pub fn ag_not_3_dim(&self) -> ReRes<&Self> {
         match self.dim() {
             Ok(3) => Ok(self),
+            Ok(dim) => Err(MathErr(NotIn3Dim(dim))),
             Err(err) => Err(err),
-            _ => Err(MathErr(NotIn3Dim)),
         }
     }
 
@@ -695,7 +695,7 @@ impl Matrix {
     pub fn ag_linear_dependence(&self) -> ReRes<&Self> {
         if let Ok(det) = self.det() {
             if det == 0.0 {
-                return Err(MathErr(NullDeterminant));
+                return Err(MathErr(NullDeterminant((self.rows(), self.cols()))));
             }
         }
         Ok(self)
@@ -69,13 +69,13 @@ fn df_points() {
 #[test]
 fn basis_not_stratified() {
     let basis = Basis::new(Matrix::identity(3));
-    assert_eq!(basis, Err(GridErr(NotMultiRowOrCol)));
+    assert_eq!(basis, Err(GridErr(NotMultiRowOrCol(Square))));
 }
 
 #[test]
 fn linear_dep_basis() {
     let basis = Basis::new(Matrix::new(3, 3, 0.0).to_multicol());
-    assert_eq!(basis, Err(MathErr(NullDeterminant)));
+    assert_eq!(basis, Err(MathErr(NullDeterminant((3, 3)))));
 }
 
 #[test]